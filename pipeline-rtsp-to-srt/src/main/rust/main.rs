@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use clap::Parser;
+use tokio::signal::unix::{signal, SignalKind};
 use tokio::sync::oneshot;
 use tracing::{error, info};
 use warp::Filter;
@@ -57,13 +58,13 @@ async fn main() -> Result<()> {
     let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
     let shutdown_tx = Arc::new(tokio::sync::Mutex::new(Some(shutdown_tx)));
 
-    // Handle Ctrl+C
+    // Handle SIGTERM (docker stop) and SIGINT (Ctrl+C)
     let shutdown_tx_clone = shutdown_tx.clone();
     tokio::spawn(async move {
-        tokio::signal::ctrl_c()
+        let name = shutdown_signal()
             .await
-            .expect("Failed to listen for ctrl+c");
-        info!("Received shutdown signal");
+            .expect("Failed to listen for shutdown signals");
+        info!("Received shutdown signal ({})", name);
         running_for_signal.store(false, Ordering::SeqCst);
         if let Some(tx) = shutdown_tx_clone.lock().await.take() {
             let _ = tx.send(());
@@ -127,3 +128,13 @@ async fn main() -> Result<()> {
     info!("Pipeline shutdown complete");
     Ok(())
 }
+
+/// Wait for SIGTERM or SIGINT, returning the signal's name
+async fn shutdown_signal() -> std::io::Result<&'static str> {
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    Ok(tokio::select! {
+        _ = terminate.recv() => "SIGTERM",
+        _ = interrupt.recv() => "SIGINT",
+    })
+}
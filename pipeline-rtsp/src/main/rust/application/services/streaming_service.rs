@@ -1,15 +1,35 @@
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
 
-use crate::domain::entities::StreamSession;
-use crate::domain::errors::Result;
-use crate::domain::ports::{MetricsReporter, StreamingServer};
-use crate::domain::value_objects::{ServerConfig, StreamConfig};
+use crate::domain::entities::{ClientSession, StreamSession};
+use crate::domain::errors::{DomainError, Result};
+use crate::domain::ports::{MediaProbe, MetricsReporter, StreamingServer};
+use crate::domain::value_objects::{ChaosSchedule, Fault, ServerConfig, StreamConfig, WatchEvent};
+
+/// How often the scheduler compares scheduled mounts against the clock
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often a drain checks whether the last client has left
+const DRAIN_CHECK_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Random fault injection running against a mount
+struct ChaosRun {
+    schedule: ChaosSchedule,
+    task: JoinHandle<()>,
+}
 
 /// Application service orchestrating streaming operations
 pub struct StreamingService {
     server: Arc<RwLock<Box<dyn StreamingServer>>>,
     metrics: Arc<dyn MetricsReporter>,
+    probe: Option<Arc<dyn MediaProbe>>,
+    chaos: Mutex<BTreeMap<String, ChaosRun>>,
+    draining: AtomicBool,
 }
 
 impl StreamingService {
@@ -17,17 +37,41 @@ impl StreamingService {
         Self {
             server: Arc::new(RwLock::new(server)),
             metrics,
+            probe: None,
+            chaos: Mutex::new(BTreeMap::new()),
+            draining: AtomicBool::new(false),
         }
     }
 
-    /// Start streaming session (use case)
+    /// Inspect source files before serving them instead of trusting the configured codec
+    pub fn with_media_probe(mut self, probe: Arc<dyn MediaProbe>) -> Self {
+        self.probe = Some(probe);
+        self
+    }
+
+    /// Start streaming session on a mount point (use case)
     pub async fn start_streaming(
         &self,
         stream_config: StreamConfig,
         server_config: ServerConfig,
     ) -> Result<StreamSession> {
-        // Validate stream configuration
-        stream_config.validate()?;
+        if self.is_draining() {
+            return Err(DomainError::ShuttingDown);
+        }
+
+        // Validate stream configuration against the file contents when possible
+        let stream_config = match &self.probe {
+            Some(probe) => {
+                let probe = probe.clone();
+                tokio::task::spawn_blocking(move || stream_config.validate_with(probe.as_ref()))
+                    .await
+                    .map_err(|e| DomainError::MediaProbeFailed(e.to_string()))??
+            }
+            None => {
+                stream_config.validate()?;
+                stream_config
+            }
+        };
 
         // Start server
         let session = {
@@ -47,34 +91,323 @@ impl StreamingService {
         Ok(session)
     }
 
-    /// Stop streaming session
+    /// Stop streaming on a single mount point (use case)
+    pub async fn stop_mount(&self, mount_point: &str) -> Result<StreamSession> {
+        self.stop_chaos(mount_point);
+        let mut server = self.server.write().await;
+
+        // Get session before stopping for metrics
+        if let Some(session) = server.session(mount_point) {
+            self.metrics.report_session_stopped(&session);
+        }
+
+        let session = server.remove(mount_point).await?;
+
+        tracing::info!(
+            session_id = %session.id(),
+            mount_point = %mount_point,
+            "Streaming session stopped"
+        );
+
+        Ok(session)
+    }
+
+    /// Stop all streaming sessions
     pub async fn stop_streaming(&self) -> Result<()> {
+        for run in std::mem::take(&mut *self.chaos.lock().unwrap()).into_values() {
+            run.task.abort();
+        }
+
         let mut server = self.server.write().await;
 
         if !server.is_running() {
             return Ok(());
         }
 
-        // Get session before stopping for metrics
-        if let Some(session) = server.current_session() {
-            self.metrics.report_session_stopped(session);
+        // Report sessions before stopping for metrics
+        for session in server.sessions() {
+            self.metrics.report_session_stopped(&session);
         }
 
-        tracing::info!("Stopping streaming session");
+        tracing::info!("Stopping streaming sessions");
         server.stop().await?;
 
         Ok(())
     }
 
+    /// Stop taking clients and mounts, end every stream, then wait for clients to leave (use case)
+    ///
+    /// Returns once no client is connected or `timeout` has passed, whichever
+    /// comes first. Sessions keep running until `stop_streaming`.
+    pub async fn drain(&self, timeout: Duration) -> Result<()> {
+        if self.draining.swap(true, Ordering::Relaxed) {
+            return Ok(());
+        }
+        for run in std::mem::take(&mut *self.chaos.lock().unwrap()).into_values() {
+            run.task.abort();
+        }
+
+        self.server.write().await.drain().await?;
+        tracing::info!(timeout = ?timeout, "Draining clients");
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = self.server.read().await.clients().len();
+            if remaining == 0 {
+                tracing::info!("All clients disconnected");
+                break;
+            }
+            if Instant::now() >= deadline {
+                tracing::warn!(clients = remaining, "Drain timeout reached");
+                break;
+            }
+            tokio::time::sleep(DRAIN_CHECK_INTERVAL).await;
+        }
+
+        Ok(())
+    }
+
+    /// Whether a drain has started; readiness probes should fail from then on
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Relaxed)
+    }
+
+    /// Switch every scheduled mount whose schedule now selects another file (use case)
+    ///
+    /// Runs every second, so due mounts are found under the read lock and the
+    /// write lock is only taken when one of them has to switch.
+    pub async fn apply_schedules(&self) {
+        let due: Vec<String> = self
+            .server
+            .read()
+            .await
+            .sessions()
+            .iter()
+            .filter(|session| session.pending_scheduled_source().is_some())
+            .map(|session| session.server_config().mount_point().to_string())
+            .collect();
+        if due.is_empty() {
+            return;
+        }
+
+        let mut server = self.server.write().await;
+        for mount_point in due {
+            // The mount may have been removed or switched while the lock was released
+            let Some(session) = server.session(&mount_point) else {
+                continue;
+            };
+            let Some(wanted) = session.pending_scheduled_source() else {
+                continue;
+            };
+
+            match server.switch_source(&mount_point, wanted).await {
+                Ok(()) => tracing::info!(
+                    mount_point = %mount_point,
+                    from = %session.active_source().display(),
+                    to = %wanted.display(),
+                    "Scheduled source switched"
+                ),
+                Err(e) => tracing::error!(
+                    mount_point = %mount_point,
+                    "Scheduled source switch failed: {}",
+                    e
+                ),
+            }
+        }
+    }
+
+    /// Run `apply_schedules` in the background until the returned task is aborted
+    pub fn spawn_scheduler(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SCHEDULE_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                self.apply_schedules().await;
+            }
+        })
+    }
+
+    /// Publish or withdraw a clip of the watched directory (use case)
+    ///
+    /// Files are probed and validated like any other mount. A clip is only
+    /// withdrawn when its mount still plays the removed file.
+    pub async fn apply_watch_event(&self, event: &WatchEvent, rtsp_port: u16) -> Result<()> {
+        let result = match event {
+            WatchEvent::Added(path) => self.publish_clip(path, rtsp_port).await,
+            WatchEvent::Removed(path) => self.withdraw_clip(path).await,
+        };
+
+        match &result {
+            Ok(Some(mount_point)) => {
+                self.metrics.report_watch_event(event, true);
+                tracing::info!(
+                    mount_point = %mount_point,
+                    file = %event.path().display(),
+                    "Watched clip {}",
+                    event.kind()
+                );
+            }
+            Ok(None) => {}
+            Err(e) => {
+                self.metrics.report_watch_event(event, false);
+                tracing::warn!(
+                    file = %event.path().display(),
+                    "Watched clip {} rejected: {}",
+                    event.kind(),
+                    e
+                );
+            }
+        }
+        result.map(|_| ())
+    }
+
+    async fn publish_clip(&self, path: &Path, rtsp_port: u16) -> Result<Option<String>> {
+        let mount_point = WatchEvent::Added(path.to_path_buf()).mount_point()?;
+        let server_config = ServerConfig::new(rtsp_port, mount_point.clone())?;
+
+        // A rewritten file replaces its own mount
+        if self.plays_file(&mount_point, path).await {
+            self.stop_mount(&mount_point).await?;
+        }
+        self.start_streaming(StreamConfig::new(path.to_path_buf()), server_config)
+            .await?;
+        Ok(Some(mount_point))
+    }
+
+    async fn withdraw_clip(&self, path: &Path) -> Result<Option<String>> {
+        let mount_point = WatchEvent::Removed(path.to_path_buf()).mount_point()?;
+        if !self.plays_file(&mount_point, path).await {
+            return Ok(None);
+        }
+        self.stop_mount(&mount_point).await?;
+        Ok(Some(mount_point))
+    }
+
+    async fn plays_file(&self, mount_point: &str, path: &Path) -> bool {
+        self.session(mount_point)
+            .await
+            .is_some_and(|session| session.stream_config().source_path() == path)
+    }
+
+    /// Apply watch events in the background until the sender is dropped
+    pub fn spawn_watch_publisher(
+        self: Arc<Self>,
+        mut events: mpsc::Receiver<WatchEvent>,
+        rtsp_port: u16,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                let _ = self.apply_watch_event(&event, rtsp_port).await;
+            }
+        })
+    }
+
+    /// Disturb a mount's output to exercise client resilience (use case)
+    pub async fn inject_fault(&self, mount_point: &str, fault: Fault) -> Result<()> {
+        {
+            let mut server = self.server.write().await;
+            server.inject_fault(mount_point, fault).await?;
+        }
+
+        self.metrics.report_fault_injected(mount_point, &fault);
+
+        tracing::warn!(
+            mount_point = %mount_point,
+            fault = %fault,
+            "Fault injected"
+        );
+
+        Ok(())
+    }
+
+    /// Inject random faults into a mount until `stop_chaos`, replacing any running schedule
+    pub async fn start_chaos(
+        self: Arc<Self>,
+        mount_point: &str,
+        schedule: ChaosSchedule,
+    ) -> Result<()> {
+        if self.session(mount_point).await.is_none() {
+            return Err(DomainError::MountNotFound(mount_point.to_string()));
+        }
+
+        let service = self.clone();
+        let mount = mount_point.to_string();
+        let task_schedule = schedule.clone();
+        let task = tokio::spawn(async move {
+            let mut state = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(1, |elapsed| elapsed.as_nanos() as u64);
+            loop {
+                let (wait, fault) = task_schedule.next(&mut state);
+                tokio::time::sleep(wait).await;
+                if let Err(e) = service.inject_fault(&mount, fault).await {
+                    tracing::error!(mount_point = %mount, "Chaos schedule stopped: {}", e);
+                    break;
+                }
+            }
+        });
+
+        let previous = self
+            .chaos
+            .lock()
+            .unwrap()
+            .insert(mount_point.to_string(), ChaosRun { schedule, task });
+        if let Some(previous) = previous {
+            previous.task.abort();
+        }
+
+        tracing::warn!(mount_point = %mount_point, "Chaos schedule started");
+        Ok(())
+    }
+
+    /// Stop random fault injection on a mount, returning the schedule that ran
+    pub fn stop_chaos(&self, mount_point: &str) -> Option<ChaosSchedule> {
+        let run = self.chaos.lock().unwrap().remove(mount_point)?;
+        run.task.abort();
+        tracing::info!(mount_point = %mount_point, "Chaos schedule stopped");
+        Some(run.schedule)
+    }
+
+    /// Random fault injection running on a mount, if any
+    pub fn chaos_schedule(&self, mount_point: &str) -> Option<ChaosSchedule> {
+        self.chaos
+            .lock()
+            .unwrap()
+            .get(mount_point)
+            .filter(|run| !run.task.is_finished())
+            .map(|run| run.schedule.clone())
+    }
+
+    /// Current picture of a mount as a JPEG (use case)
+    pub async fn snapshot(&self, mount_point: &str, max_width: u32) -> Result<Vec<u8>> {
+        // Decoding takes a while, so it must not hold the server lock
+        let job = self.server.read().await.snapshot(mount_point, max_width)?;
+        tokio::task::spawn_blocking(job)
+            .await
+            .map_err(|e| DomainError::SnapshotFailed(e.to_string()))?
+    }
+
     /// Check if currently streaming
     pub async fn is_streaming(&self) -> bool {
         let server = self.server.read().await;
         server.is_running()
     }
 
-    /// Get current session info
-    pub async fn current_session(&self) -> Option<StreamSession> {
+    /// Get session info for a mount point
+    pub async fn session(&self, mount_point: &str) -> Option<StreamSession> {
+        let server = self.server.read().await;
+        server.session(mount_point)
+    }
+
+    /// Get info for all sessions
+    pub async fn sessions(&self) -> Vec<StreamSession> {
+        let server = self.server.read().await;
+        server.sessions()
+    }
+
+    /// Get info for all connected RTSP clients
+    pub async fn clients(&self) -> Vec<ClientSession> {
         let server = self.server.read().await;
-        server.current_session().cloned()
+        server.clients()
     }
 }
@@ -1,5 +1,16 @@
 use clap::Parser;
+use serde::Deserialize;
+use std::collections::HashSet;
 use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::domain::errors::{DomainError, Result};
+use crate::domain::value_objects::{
+    AuthConfig, AuthMethod, ChaosSchedule, Fault, HeaderExtension, MulticastPool, PatternOverlay,
+    Playlist, PlaylistItem, PortRange, RecordingConfig, RtpProfile, RtspUser, Schedule,
+    ServerConfig, SharingMode, SourceKind, StreamConfig, TestPattern, TlsConfig, TranscodeProfile,
+    TransportPolicy,
+};
 
 #[derive(Parser, Debug, Clone)]
 #[command(
@@ -18,6 +29,67 @@ pub struct Config {
     )]
     pub video_path: PathBuf,
 
+    /// Source of the default mount: file (--video-path) or test-pattern (generated, no file needed)
+    #[arg(long, env = "VIDEO_SOURCE", default_value = "file")]
+    pub source: SourceKind,
+
+    /// videotestsrc pattern for --source test-pattern, e.g. smpte, ball, snow
+    #[arg(long, env = "TEST_PATTERN", default_value = "smpte")]
+    pub pattern: String,
+
+    /// Width of the generated test pattern in pixels
+    #[arg(long, env = "TEST_PATTERN_WIDTH", default_value = "1280")]
+    pub pattern_width: u32,
+
+    /// Height of the generated test pattern in pixels
+    #[arg(long, env = "TEST_PATTERN_HEIGHT", default_value = "720")]
+    pub pattern_height: u32,
+
+    /// Frame rate of the generated test pattern
+    #[arg(long, env = "TEST_PATTERN_FPS", default_value = "30")]
+    pub pattern_fps: u32,
+
+    /// Burn the wall clock (clock) or the stream running time (time) into the test pattern
+    #[arg(long, env = "TEST_PATTERN_OVERLAY")]
+    pub pattern_overlay: Option<PatternOverlay>,
+
+    /// Clips to play back to back on the default mount instead of --video-path, comma separated
+    #[arg(long, env = "PLAYLIST", value_delimiter = ',')]
+    pub playlist: Vec<PathBuf>,
+
+    /// Shuffle the --playlist order each time the server starts
+    #[arg(long, env = "PLAYLIST_SHUFFLE")]
+    pub shuffle: bool,
+
+    /// Daily windows playing other files on the default mount, as HH:MM-HH:MM=path, comma separated
+    #[arg(long, env = "SCHEDULE", value_delimiter = ',')]
+    pub schedule: Vec<String>,
+
+    /// Timezone of --schedule windows as minutes ahead of UTC, e.g. 60 or -300
+    #[arg(
+        long,
+        env = "SCHEDULE_UTC_OFFSET_MINUTES",
+        default_value = "0",
+        allow_hyphen_values = true
+    )]
+    pub schedule_utc_offset: i32,
+
+    /// Audio codec of the default mount's audio track (aac or opus); omit for video only
+    #[arg(long, env = "AUDIO_CODEC")]
+    pub audio_codec: Option<String>,
+
+    /// Re-encode the default mount to B-frame-free H.264 with this profile (baseline, main, high)
+    #[arg(long, env = "TRANSCODE_PROFILE")]
+    pub transcode: Option<String>,
+
+    /// Target bitrate in kbit/s when transcoding
+    #[arg(long, env = "TRANSCODE_BITRATE_KBPS", default_value = "2000")]
+    pub transcode_bitrate: u32,
+
+    /// Keyframe interval in frames when transcoding
+    #[arg(long, env = "TRANSCODE_GOP", default_value = "60")]
+    pub transcode_gop: u32,
+
     /// RTSP server port
     #[arg(long, env = "RTSP_PORT", default_value = "8554")]
     pub rtsp_port: u16,
@@ -26,26 +98,543 @@ pub struct Config {
     #[arg(long, env = "RTSP_MOUNT_POINT", default_value = "/cam1")]
     pub mount_point: String,
 
+    /// JSON file listing additional mounts to serve alongside the default one
+    #[arg(long, env = "RTSP_MOUNTS_FILE")]
+    pub mounts_file: Option<PathBuf>,
+
+    /// Directory whose video files are each served on /clips/<file stem> while they exist
+    #[arg(long, env = "WATCH_DIR")]
+    pub watch_dir: Option<PathBuf>,
+
+    /// RTSP users as username:password, comma separated; enables authentication
+    #[arg(long = "rtsp-user", env = "RTSP_USERS", value_delimiter = ',')]
+    pub rtsp_users: Vec<RtspUser>,
+
+    /// Authentication schemes offered to clients (basic, digest)
+    #[arg(
+        long,
+        env = "RTSP_AUTH_METHODS",
+        value_delimiter = ',',
+        default_value = "basic,digest"
+    )]
+    pub auth_methods: Vec<AuthMethod>,
+
+    /// PEM certificate to serve rtsps:// (requires --tls-key)
+    #[arg(long, env = "RTSP_TLS_CERT")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM private key matching --tls-cert
+    #[arg(long, env = "RTSP_TLS_KEY")]
+    pub tls_key: Option<PathBuf>,
+
+    /// RTP profiles offered on the default mount (avp, savp, avpf, savpf); savp/savpf use SRTP
+    #[arg(
+        long,
+        env = "RTSP_RTP_PROFILES",
+        value_delimiter = ',',
+        default_value = "avp"
+    )]
+    pub rtp_profiles: Vec<RtpProfile>,
+
+    /// Multicast groups for the default mount, e.g. 239.255.0.1-239.255.0.16; enables UDP multicast
+    #[arg(long, env = "RTSP_MULTICAST_ADDRESSES")]
+    pub multicast_addresses: Option<String>,
+
+    /// UDP port range handed out with the multicast groups
+    #[arg(long, env = "RTSP_MULTICAST_PORTS", default_value = "5000-5999")]
+    pub multicast_ports: String,
+
+    /// TTL of multicast packets
+    #[arg(long, env = "RTSP_MULTICAST_TTL", default_value = "16")]
+    pub multicast_ttl: u8,
+
+    /// Lower transports allowed on the default mount (both, tcp, udp)
+    #[arg(long, env = "RTSP_TRANSPORT", default_value = "both")]
+    pub transport: TransportPolicy,
+
+    /// Server UDP ports for unicast RTP/RTCP on the default mount, e.g. 20000-20999
+    #[arg(long, env = "RTSP_UDP_PORTS")]
+    pub udp_ports: Option<PortRange>,
+
+    /// Media sharing on the default mount: shared (one looping timeline) or per-client (seekable VOD)
+    #[arg(long, env = "RTSP_SHARING_MODE", default_value = "shared")]
+    pub sharing_mode: SharingMode,
+
+    /// Stop the default mount after the file has been restarted this many times (default: forever)
+    #[arg(long, env = "RTSP_MAX_LOOPS")]
+    pub max_loops: Option<u32>,
+
+    /// Clock the default mount against the system wall clock so RTCP sender reports carry real NTP time
+    #[arg(long, env = "RTSP_LIVE")]
+    pub live: bool,
+
+    /// Stamp RTP packets of the live default mount with capture time (ntp-64, onvif-replay)
+    #[arg(long, env = "RTSP_HEADER_EXTENSION")]
+    pub header_extension: Option<HeaderExtension>,
+
+    /// Record the default mount to segmented MP4 files in this directory; {mount} expands to the mount name
+    #[arg(long, env = "RECORD_DIR")]
+    pub record_dir: Option<String>,
+
+    /// Length of each recording file in seconds
+    #[arg(long, env = "RECORD_SEGMENT_SECS", default_value = "60")]
+    pub record_segment_secs: u64,
+
+    /// Recording files to keep; the oldest are deleted beyond this (default: keep all)
+    #[arg(long, env = "RECORD_MAX_SEGMENTS")]
+    pub record_max_segments: Option<u32>,
+
+    /// Allow the control API to inject faults into mounts (freeze, drop, latency, eos, teardown)
+    #[arg(long, env = "RTSP_CHAOS")]
+    pub chaos: bool,
+
+    /// Largest width of GET /mounts/{name}/snapshot.jpg in pixels; wider frames are scaled down
+    #[arg(long, env = "SNAPSHOT_MAX_WIDTH", default_value = "640")]
+    pub snapshot_max_width: u32,
+
+    /// Seconds clients get to leave after SIGTERM/SIGINT before the server exits
+    #[arg(long, env = "DRAIN_TIMEOUT_SECS", default_value = "10")]
+    pub drain_timeout_secs: u64,
+
     /// Metrics server port
     #[arg(long, env = "METRICS_PORT", default_value = "9001")]
     pub metrics_port: u16,
 
+    /// Browser origins allowed to change mounts and inject faults, comma separated (default: read-only)
+    #[arg(long, env = "CORS_ORIGINS", value_delimiter = ',')]
+    pub cors_origins: Vec<String>,
+
     /// Enable verbose logging
     #[arg(short, long)]
     pub verbose: bool,
 }
 
+/// A single mount served by the RTSP server
+///
+/// Entries of the mounts file use this shape, e.g.
+/// `[{"mount_point": "/cam2", "video_path": "/videos/b.mkv", "container": "mkv"}]`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct MountSpec {
+    pub mount_point: String,
+    /// Source file; leave out when `playlist` or `test_pattern` is given
+    #[serde(default)]
+    pub video_path: PathBuf,
+    #[serde(default)]
+    pub test_pattern: Option<TestPatternSpec>,
+    #[serde(default)]
+    pub playlist: Option<PlaylistSpec>,
+    #[serde(default)]
+    pub schedule: Option<ScheduleSpec>,
+    #[serde(default)]
+    pub codec: Option<String>,
+    #[serde(default)]
+    pub container: Option<String>,
+    #[serde(default)]
+    pub audio_codec: Option<String>,
+    #[serde(default)]
+    pub transcode: Option<TranscodeSpec>,
+    /// Users allowed to play the mount when authentication is enabled (default: all)
+    #[serde(default)]
+    pub users: Option<Vec<String>>,
+    /// RTP profiles offered for the mount, e.g. `["savp", "savpf"]` (default: avp)
+    #[serde(default)]
+    pub rtp_profiles: Option<Vec<String>>,
+    #[serde(default)]
+    pub multicast: Option<MulticastSpec>,
+    /// Allowed lower transports: `both` (default), `tcp` or `udp`
+    #[serde(default)]
+    pub transport: Option<String>,
+    /// Server UDP port range for unicast clients, e.g. `"20000-20999"`
+    #[serde(default)]
+    pub udp_ports: Option<String>,
+    /// `shared` (default) or `per-client` for seekable video on demand
+    #[serde(default)]
+    pub sharing_mode: Option<String>,
+    #[serde(default = "default_looping")]
+    pub looping: bool,
+    /// Restarts before the stream ends, when looping (default: unlimited)
+    #[serde(default)]
+    pub max_loops: Option<u32>,
+    /// Clock the media against the system wall clock
+    #[serde(default)]
+    pub live: bool,
+    /// `ntp-64` (RFC 6051) or `onvif-replay`, for live mounts
+    #[serde(default)]
+    pub header_extension: Option<String>,
+    #[serde(default)]
+    pub recording: Option<RecordingSpec>,
+}
+
+/// Transcode settings of a mount, e.g. `{"profile": "baseline", "bitrate_kbps": 1500}`
+///
+/// Omitted fields fall back to the `TranscodeProfile` defaults.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TranscodeSpec {
+    #[serde(default)]
+    pub codec: Option<String>,
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub gop_length: Option<u32>,
+    #[serde(default)]
+    pub bitrate_kbps: Option<u32>,
+}
+
+impl TranscodeSpec {
+    pub fn to_transcode_profile(&self) -> Result<TranscodeProfile> {
+        let mut profile = TranscodeProfile::new();
+        if let Some(codec) = &self.codec {
+            profile = profile.with_codec(codec.parse()?);
+        }
+        if let Some(name) = &self.profile {
+            profile = profile.with_profile(name.parse()?);
+        }
+        if let Some(frames) = self.gop_length {
+            profile = profile.with_gop_length(frames);
+        }
+        if let Some(kbps) = self.bitrate_kbps {
+            profile = profile.with_bitrate_kbps(kbps);
+        }
+        Ok(profile)
+    }
+}
+
+/// Multicast pool of a mount, e.g. `{"addresses": "239.255.0.1-239.255.0.16", "ttl": 4}`
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct MulticastSpec {
+    pub addresses: String,
+    #[serde(default = "default_multicast_ports")]
+    pub ports: String,
+    #[serde(default = "default_multicast_ttl")]
+    pub ttl: u8,
+}
+
+impl MulticastSpec {
+    pub fn to_multicast_pool(&self) -> Result<MulticastPool> {
+        Ok(MulticastPool::parse(&self.addresses, self.ports.parse()?)?.with_ttl(self.ttl))
+    }
+}
+
+/// Clips of a mount, e.g. `{"items": [{"path": "/videos/day.mp4", "repeat": 2}], "shuffle": true}`
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct PlaylistSpec {
+    pub items: Vec<PlaylistItemSpec>,
+    #[serde(default)]
+    pub shuffle: bool,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct PlaylistItemSpec {
+    pub path: PathBuf,
+    #[serde(default = "default_repeat")]
+    pub repeat: u32,
+}
+
+impl PlaylistSpec {
+    pub fn to_playlist(&self) -> Result<Playlist> {
+        let items = self
+            .items
+            .iter()
+            .map(|item| PlaylistItem::new(item.path.clone()).with_repeat(item.repeat))
+            .collect();
+        let playlist = Playlist::new(items)?;
+        if !self.shuffle {
+            return Ok(playlist);
+        }
+
+        // A new order every time the mount starts
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(1, |elapsed| elapsed.as_nanos() as u64);
+        Ok(playlist.with_shuffle_seed(seed))
+    }
+}
+
+/// Time-of-day programming of a mount, e.g.
+/// `{"windows": ["06:00-18:00=/videos/day.mp4", "22:00-06:00=/videos/night.mp4"], "utc_offset_minutes": 60}`
+///
+/// Outside every window the mount plays its `video_path`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ScheduleSpec {
+    pub windows: Vec<String>,
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+}
+
+impl ScheduleSpec {
+    pub fn to_schedule(&self) -> Result<Schedule> {
+        let windows = self
+            .windows
+            .iter()
+            .map(|window| window.parse())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Schedule::new(windows)?.with_utc_offset_minutes(self.utc_offset_minutes))
+    }
+}
+
+/// Generated video of a mount, e.g. `{"pattern": "ball", "width": 640, "height": 480, "fps": 25, "overlay": "clock"}`
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct TestPatternSpec {
+    #[serde(default = "default_pattern")]
+    pub pattern: String,
+    #[serde(default = "default_pattern_width")]
+    pub width: u32,
+    #[serde(default = "default_pattern_height")]
+    pub height: u32,
+    #[serde(default = "default_pattern_fps")]
+    pub fps: u32,
+    /// `clock` (wall clock) or `time` (running time)
+    #[serde(default)]
+    pub overlay: Option<String>,
+}
+
+impl TestPatternSpec {
+    pub fn to_test_pattern(&self) -> Result<TestPattern> {
+        let mut pattern = TestPattern::new()
+            .with_pattern(&self.pattern)?
+            .with_resolution(self.width, self.height)
+            .with_fps(self.fps);
+        if let Some(overlay) = &self.overlay {
+            pattern = pattern.with_overlay(overlay.parse()?);
+        }
+        Ok(pattern)
+    }
+}
+
+/// Fault to inject into a mount, e.g. `{"kind": "drop", "percent": 20, "duration_ms": 5000}`
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct FaultSpec {
+    /// `freeze`, `drop`, `latency`, `eos` or `teardown`
+    pub kind: String,
+    /// How long `freeze`, `drop` and `latency` last
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    /// Share of RTP packets `drop` discards
+    #[serde(default)]
+    pub percent: Option<u8>,
+    /// Delay `latency` adds to every packet
+    #[serde(default)]
+    pub delay_ms: Option<u64>,
+}
+
+impl FaultSpec {
+    pub fn to_fault(&self) -> Result<Fault> {
+        let required = |value: Option<u64>, field: &str| {
+            value
+                .map(Duration::from_millis)
+                .ok_or_else(|| DomainError::InvalidFault(format!("{} needs {}", self.kind, field)))
+        };
+
+        match self.kind.as_str() {
+            "freeze" => Fault::freeze(required(self.duration_ms, "duration_ms")?),
+            "drop" => Fault::drop_packets(
+                self.percent
+                    .ok_or_else(|| DomainError::InvalidFault("drop needs percent".to_string()))?,
+                required(self.duration_ms, "duration_ms")?,
+            ),
+            "latency" => Fault::latency(
+                required(self.delay_ms, "delay_ms")?,
+                required(self.duration_ms, "duration_ms")?,
+            ),
+            "eos" => Ok(Fault::Eos),
+            "teardown" => Ok(Fault::TeardownClients),
+            other => Err(DomainError::InvalidFault(format!(
+                "unknown fault {}, expected one of {}",
+                other,
+                Fault::KINDS.join(", ")
+            ))),
+        }
+    }
+}
+
+/// Recording of a mount, e.g. `{"directory": "/recordings/{mount}", "segment_secs": 300, "max_segments": 288}`
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordingSpec {
+    /// Output directory; `{mount}` expands to the mount name
+    pub directory: String,
+    #[serde(default = "default_segment_secs")]
+    pub segment_secs: u64,
+    /// Files to keep (default: all)
+    #[serde(default)]
+    pub max_segments: Option<u32>,
+}
+
+impl RecordingSpec {
+    pub fn to_recording_config(&self, mount_point: &str) -> Result<RecordingConfig> {
+        let mut config = RecordingConfig::new(&self.directory, mount_point)?
+            .with_segment_duration(Duration::from_secs(self.segment_secs));
+        if let Some(count) = self.max_segments {
+            config = config.with_max_segments(count);
+        }
+        Ok(config)
+    }
+}
+
+/// Random fault injection on a mount, e.g.
+/// `{"faults": [{"kind": "eos"}, {"kind": "freeze", "duration_ms": 3000}], "min_interval_secs": 30, "max_interval_secs": 120}`
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ChaosSpec {
+    pub faults: Vec<FaultSpec>,
+    pub min_interval_secs: u64,
+    pub max_interval_secs: u64,
+}
+
+impl ChaosSpec {
+    pub fn to_chaos_schedule(&self) -> Result<ChaosSchedule> {
+        let faults = self
+            .faults
+            .iter()
+            .map(FaultSpec::to_fault)
+            .collect::<Result<Vec<_>>>()?;
+        ChaosSchedule::new(
+            faults,
+            Duration::from_secs(self.min_interval_secs),
+            Duration::from_secs(self.max_interval_secs),
+        )
+    }
+}
+
+fn default_pattern() -> String {
+    "smpte".to_string()
+}
+
+fn default_pattern_width() -> u32 {
+    1280
+}
+
+fn default_pattern_height() -> u32 {
+    720
+}
+
+fn default_pattern_fps() -> u32 {
+    30
+}
+
+fn default_repeat() -> u32 {
+    1
+}
+
+fn default_segment_secs() -> u64 {
+    60
+}
+
+fn default_multicast_ports() -> String {
+    "5000-5999".to_string()
+}
+
+fn default_multicast_ttl() -> u8 {
+    16
+}
+
+fn default_looping() -> bool {
+    true
+}
+
+impl MountSpec {
+    pub fn to_stream_config(&self) -> Result<StreamConfig> {
+        let mut config = match (&self.test_pattern, &self.playlist) {
+            (Some(_), Some(_)) => {
+                return Err(DomainError::InvalidTestPattern(format!(
+                    "mount {} sets both test_pattern and playlist",
+                    self.mount_point
+                )));
+            }
+            (Some(_), None) if !self.video_path.as_os_str().is_empty() => {
+                return Err(DomainError::InvalidTestPattern(format!(
+                    "mount {} sets both video_path and test_pattern",
+                    self.mount_point
+                )));
+            }
+            (Some(pattern), None) => StreamConfig::from_test_pattern(pattern.to_test_pattern()?),
+            (None, Some(_)) if !self.video_path.as_os_str().is_empty() => {
+                return Err(DomainError::InvalidPlaylist(format!(
+                    "mount {} sets both video_path and playlist",
+                    self.mount_point
+                )));
+            }
+            (None, Some(playlist)) => StreamConfig::from_playlist(playlist.to_playlist()?),
+            (None, None) => StreamConfig::new(self.video_path.clone()),
+        };
+        if let Some(codec) = &self.codec {
+            config = config.with_codec(codec.parse()?);
+        }
+        if let Some(container) = &self.container {
+            config = config.with_container(container.parse()?);
+        }
+        if let Some(audio_codec) = &self.audio_codec {
+            config = config.with_audio_codec(audio_codec.parse()?);
+        }
+        if let Some(transcode) = &self.transcode {
+            config = config.with_transcode(transcode.to_transcode_profile()?);
+        }
+        if let Some(schedule) = &self.schedule {
+            config = config.with_schedule(schedule.to_schedule()?);
+        }
+        if let Some(recording) = &self.recording {
+            config = config.with_recording(recording.to_recording_config(&self.mount_point)?);
+        }
+        Ok(config)
+    }
+
+    pub fn to_server_config(&self, port: u16) -> Result<ServerConfig> {
+        let mut config =
+            ServerConfig::new(port, self.mount_point.clone())?.with_looping(self.looping);
+        if let Some(users) = &self.users {
+            config = config.with_allowed_users(users.clone());
+        }
+        if let Some(profiles) = &self.rtp_profiles {
+            let profiles = profiles
+                .iter()
+                .map(|name| name.parse())
+                .collect::<Result<Vec<_>>>()?;
+            config = config.with_rtp_profiles(profiles)?;
+        }
+        if let Some(multicast) = &self.multicast {
+            config = config.with_multicast(multicast.to_multicast_pool()?);
+        }
+        if let Some(policy) = &self.transport {
+            config = config.with_transport_policy(policy.parse()?);
+        }
+        if let Some(ports) = &self.udp_ports {
+            config = config.with_udp_ports(ports.parse()?);
+        }
+        if let Some(mode) = &self.sharing_mode {
+            config = config.with_sharing_mode(mode.parse()?);
+        }
+        if let Some(max_loops) = self.max_loops {
+            config = config.with_max_loops(max_loops);
+        }
+        if self.live {
+            config = config.with_live(true);
+        }
+        if let Some(extension) = &self.header_extension {
+            config = config.with_header_extension(extension.parse()?);
+        }
+        config.validate_transport()?;
+        config.validate_live()?;
+        Ok(config)
+    }
+}
+
 /// Minimum allowed port (ports below 1024 are privileged)
 const MIN_USER_PORT: u16 = 1024;
 
 impl Config {
     pub fn validate(&self) -> anyhow::Result<()> {
-        if !self.video_path.exists() {
-            anyhow::bail!("Video file not found: {:?}", self.video_path);
-        }
+        let sources: &[PathBuf] = match self.source {
+            // Generated video needs no file at all
+            SourceKind::TestPattern => &[],
+            SourceKind::File if self.playlist.is_empty() => std::slice::from_ref(&self.video_path),
+            SourceKind::File => self.playlist.as_slice(),
+        };
+        for path in sources {
+            if !path.exists() {
+                anyhow::bail!("Video file not found: {:?}", path);
+            }
 
-        if !self.video_path.is_file() {
-            anyhow::bail!("Video path is not a file: {:?}", self.video_path);
+            if !path.is_file() {
+                anyhow::bail!("Video path is not a file: {:?}", path);
+            }
         }
 
         Self::validate_port(self.rtsp_port, "RTSP")?;
@@ -55,8 +644,156 @@ impl Config {
             anyhow::bail!("RTSP port and metrics port cannot be the same");
         }
 
-        Self::validate_mount_point(&self.mount_point)?;
+        if self.snapshot_max_width == 0 {
+            anyhow::bail!("Snapshot max width must be at least one pixel");
+        }
+
+        for origin in &self.cors_origins {
+            Self::validate_cors_origin(origin)?;
+        }
+
+        if let Some(directory) = &self.watch_dir {
+            if !directory.is_dir() {
+                anyhow::bail!("Watch directory not found: {:?}", directory);
+            }
+        }
+
+        self.tls_config()?;
+        let auth = self.auth_config()?;
+
+        let mut seen = HashSet::new();
+        for spec in self.mount_specs()? {
+            Self::validate_mount_point(&spec.mount_point)?;
+            self.validate_mount_transport(&spec)?;
+            Self::validate_mount_schedule(&spec)?;
+            self.validate_mount_users(&spec, auth.as_ref())?;
+            if !seen.insert(spec.mount_point.clone()) {
+                anyhow::bail!(
+                    "Mount point is defined more than once: {}",
+                    spec.mount_point
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Authentication settings, or None when no RTSP users are configured
+    pub fn auth_config(&self) -> anyhow::Result<Option<AuthConfig>> {
+        if self.rtsp_users.is_empty() {
+            return Ok(None);
+        }
+        AuthConfig::new(self.rtsp_users.clone(), self.auth_methods.clone())
+            .map(Some)
+            .map_err(|e| anyhow::anyhow!("{}", e))
+    }
+
+    /// TLS settings, or None to serve plain rtsp://
+    pub fn tls_config(&self) -> anyhow::Result<Option<TlsConfig>> {
+        match (&self.tls_cert, &self.tls_key) {
+            (Some(cert), Some(key)) => TlsConfig::new(cert.clone(), key.clone())
+                .map(Some)
+                .map_err(|e| anyhow::anyhow!("Invalid TLS configuration: {}", e)),
+            (None, None) => Ok(None),
+            _ => anyhow::bail!("--tls-cert and --tls-key must be given together"),
+        }
+    }
+
+    /// All mounts to serve: the default mount followed by the mounts file entries
+    pub fn mount_specs(&self) -> anyhow::Result<Vec<MountSpec>> {
+        let playlist = (!self.playlist.is_empty()).then(|| PlaylistSpec {
+            items: self
+                .playlist
+                .iter()
+                .map(|path| PlaylistItemSpec {
+                    path: path.clone(),
+                    repeat: 1,
+                })
+                .collect(),
+            shuffle: self.shuffle,
+        });
+        let test_pattern = (self.source == SourceKind::TestPattern).then(|| TestPatternSpec {
+            pattern: self.pattern.clone(),
+            width: self.pattern_width,
+            height: self.pattern_height,
+            fps: self.pattern_fps,
+            overlay: self
+                .pattern_overlay
+                .map(|overlay| overlay.as_str().to_string()),
+        });
+        let mut specs = vec![MountSpec {
+            mount_point: self.mount_point.clone(),
+            // A playlist or test pattern replaces the default video path
+            video_path: if playlist.is_some() || test_pattern.is_some() {
+                PathBuf::new()
+            } else {
+                self.video_path.clone()
+            },
+            test_pattern,
+            playlist,
+            schedule: (!self.schedule.is_empty()).then(|| ScheduleSpec {
+                windows: self.schedule.clone(),
+                utc_offset_minutes: self.schedule_utc_offset,
+            }),
+            codec: None,
+            container: None,
+            audio_codec: self.audio_codec.clone(),
+            transcode: self.transcode.as_ref().map(|profile| TranscodeSpec {
+                codec: None,
+                profile: Some(profile.clone()),
+                gop_length: Some(self.transcode_gop),
+                bitrate_kbps: Some(self.transcode_bitrate),
+            }),
+            users: None,
+            rtp_profiles: Some(
+                self.rtp_profiles
+                    .iter()
+                    .map(|profile| profile.as_str().to_string())
+                    .collect(),
+            ),
+            multicast: self
+                .multicast_addresses
+                .as_ref()
+                .map(|addresses| MulticastSpec {
+                    addresses: addresses.clone(),
+                    ports: self.multicast_ports.clone(),
+                    ttl: self.multicast_ttl,
+                }),
+            transport: Some(self.transport.as_str().to_string()),
+            udp_ports: self.udp_ports.map(|ports| ports.to_string()),
+            sharing_mode: Some(self.sharing_mode.as_str().to_string()),
+            looping: true,
+            max_loops: self.max_loops,
+            live: self.live,
+            header_extension: self
+                .header_extension
+                .map(|extension| extension.as_str().to_string()),
+            recording: self.record_dir.as_ref().map(|directory| RecordingSpec {
+                directory: directory.clone(),
+                segment_secs: self.record_segment_secs,
+                max_segments: self.record_max_segments,
+            }),
+        }];
 
+        if let Some(path) = &self.mounts_file {
+            let contents = std::fs::read_to_string(path)
+                .map_err(|e| anyhow::anyhow!("Cannot read mounts file {:?}: {}", path, e))?;
+            let extra: Vec<MountSpec> = serde_json::from_str(&contents)
+                .map_err(|e| anyhow::anyhow!("Invalid mounts file {:?}: {}", path, e))?;
+            specs.extend(extra);
+        }
+
+        Ok(specs)
+    }
+
+    /// Origins are a scheme and a host with an optional port, e.g. `https://ops.example.com`
+    fn validate_cors_origin(origin: &str) -> anyhow::Result<()> {
+        let valid = origin.split_once("://").is_some_and(|(scheme, host)| {
+            matches!(scheme, "http" | "https") && !host.is_empty() && !host.contains('/')
+        });
+        if !valid {
+            anyhow::bail!("Invalid CORS origin: {}", origin);
+        }
         Ok(())
     }
 
@@ -76,6 +813,71 @@ impl Config {
         Ok(())
     }
 
+    fn validate_mount_transport(&self, spec: &MountSpec) -> anyhow::Result<()> {
+        let server_config = spec
+            .to_server_config(self.rtsp_port)
+            .map_err(|e| anyhow::anyhow!("Invalid transport for {}: {}", spec.mount_point, e))?;
+
+        let ports = server_config
+            .udp_ports()
+            .into_iter()
+            .chain(server_config.multicast().map(MulticastPool::ports));
+        for range in ports {
+            if range.first() < MIN_USER_PORT {
+                anyhow::bail!(
+                    "Invalid UDP ports for {}: {} includes privileged ports (< {})",
+                    spec.mount_point,
+                    range,
+                    MIN_USER_PORT
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_mount_schedule(spec: &MountSpec) -> anyhow::Result<()> {
+        let Some(schedule) = &spec.schedule else {
+            return Ok(());
+        };
+        let schedule = schedule
+            .to_schedule()
+            .map_err(|e| anyhow::anyhow!("Invalid schedule for {}: {}", spec.mount_point, e))?;
+
+        for window in schedule.windows() {
+            if !window.source_path().is_file() {
+                anyhow::bail!(
+                    "Scheduled video file not found for {}: {:?}",
+                    spec.mount_point,
+                    window.source_path()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_mount_users(
+        &self,
+        spec: &MountSpec,
+        auth: Option<&AuthConfig>,
+    ) -> anyhow::Result<()> {
+        let Some(auth) = auth else {
+            if spec.users.is_some() {
+                anyhow::bail!(
+                    "Mount {} restricts users but no --rtsp-user is configured",
+                    spec.mount_point
+                );
+            }
+            return Ok(());
+        };
+
+        let server_config = spec
+            .to_server_config(self.rtsp_port)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        auth.users_for_mount(&server_config)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        Ok(())
+    }
+
     fn validate_mount_point(mount_point: &str) -> anyhow::Result<()> {
         if !mount_point.starts_with('/') {
             anyhow::bail!("Mount point must start with '/': {}", mount_point);
@@ -0,0 +1,152 @@
+use std::time::{Duration, Instant, SystemTime};
+use uuid::Uuid;
+
+use crate::domain::value_objects::{ClientTransport, RtpProfile};
+
+/// A single RTSP client connection and what it is pulling
+#[derive(Debug, Clone)]
+pub struct ClientSession {
+    id: String,
+    remote_address: Option<String>,
+    mount_point: Option<String>,
+    transport: ClientTransport,
+    rtp_profile: Option<RtpProfile>,
+    playing: bool,
+    connected_at: SystemTime,
+    started_at: Instant,
+    bytes_sent: u64,
+}
+
+impl ClientSession {
+    pub fn new(remote_address: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            remote_address,
+            mount_point: None,
+            transport: ClientTransport::default(),
+            rtp_profile: None,
+            playing: false,
+            connected_at: SystemTime::now(),
+            started_at: Instant::now(),
+            bytes_sent: 0,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn remote_address(&self) -> Option<&str> {
+        self.remote_address.as_deref()
+    }
+
+    pub fn mount_point(&self) -> Option<&str> {
+        self.mount_point.as_deref()
+    }
+
+    pub fn transport(&self) -> ClientTransport {
+        self.transport
+    }
+
+    /// RTP profile negotiated in SETUP, if the client got that far
+    pub fn rtp_profile(&self) -> Option<RtpProfile> {
+        self.rtp_profile
+    }
+
+    /// Whether the client receives SRTP-encrypted media
+    pub fn uses_srtp(&self) -> bool {
+        self.rtp_profile.is_some_and(|profile| profile.is_secure())
+    }
+
+    /// Whether the client sent PLAY and has not paused or torn down since
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn connected_at(&self) -> SystemTime {
+        self.connected_at
+    }
+
+    pub fn connected_for(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    /// Bind the client to the mount it requested; the first mount wins
+    pub fn assign_mount(&mut self, mount_point: String) -> bool {
+        if self.mount_point.is_some() {
+            return false;
+        }
+        self.mount_point = Some(mount_point);
+        true
+    }
+
+    pub fn set_transport(&mut self, transport: ClientTransport) {
+        self.transport = transport;
+    }
+
+    pub fn set_rtp_profile(&mut self, profile: RtpProfile) {
+        self.rtp_profile = Some(profile);
+    }
+
+    pub fn set_playing(&mut self, playing: bool) {
+        self.playing = playing;
+    }
+
+    pub fn add_bytes_sent(&mut self, bytes: u64) {
+        self.bytes_sent += bytes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_client_has_no_mount() {
+        let client = ClientSession::new(Some("10.0.0.5".to_string()));
+        assert_eq!(client.remote_address(), Some("10.0.0.5"));
+        assert_eq!(client.mount_point(), None);
+        assert_eq!(client.transport(), ClientTransport::Unknown);
+        assert_eq!(client.bytes_sent(), 0);
+        assert_eq!(client.rtp_profile(), None);
+        assert!(!client.uses_srtp());
+        assert!(!client.is_playing());
+    }
+
+    #[test]
+    fn test_secure_profile_means_srtp() {
+        let mut client = ClientSession::new(None);
+        client.set_rtp_profile(RtpProfile::Avp);
+        assert!(!client.uses_srtp());
+
+        client.set_rtp_profile(RtpProfile::Savpf);
+        assert!(client.uses_srtp());
+    }
+
+    #[test]
+    fn test_first_mount_wins() {
+        let mut client = ClientSession::new(None);
+        assert!(client.assign_mount("/cam1".to_string()));
+        assert!(!client.assign_mount("/cam2".to_string()));
+        assert_eq!(client.mount_point(), Some("/cam1"));
+    }
+
+    #[test]
+    fn test_add_bytes_sent_accumulates() {
+        let mut client = ClientSession::new(None);
+        client.add_bytes_sent(1400);
+        client.add_bytes_sent(600);
+        assert_eq!(client.bytes_sent(), 2000);
+    }
+
+    #[test]
+    fn test_clients_have_unique_ids() {
+        let client1 = ClientSession::new(None);
+        let client2 = ClientSession::new(None);
+        assert_ne!(client1.id(), client2.id());
+    }
+}
@@ -1,3 +1,5 @@
+mod client_session;
 mod stream_session;
 
-pub use stream_session::{SessionState, StreamSession};
+pub use client_session::ClientSession;
+pub use stream_session::{RecordingState, SessionState, StreamSession};
@@ -1,3 +1,4 @@
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 use uuid::Uuid;
 
@@ -11,6 +12,28 @@ pub enum SessionState {
     Stopped,
 }
 
+/// Progress of a mount's recording branch
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordingState {
+    /// The mount has no recording configured
+    Disabled,
+    /// Configured, but no file is open
+    Idle,
+    Recording,
+    Failed(String),
+}
+
+impl RecordingState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Disabled => "disabled",
+            Self::Idle => "idle",
+            Self::Recording => "recording",
+            Self::Failed(_) => "failed",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct StreamSession {
     id: String,
@@ -18,16 +41,35 @@ pub struct StreamSession {
     server_config: ServerConfig,
     started_at: Instant,
     state: SessionState,
+    bytes_sent: u64,
+    media_count: u32,
+    loops: u64,
+    faults: u64,
+    active_source: PathBuf,
+    recording_state: RecordingState,
+    recorded_segments: Vec<PathBuf>,
 }
 
 impl StreamSession {
     pub fn new(stream_config: StreamConfig, server_config: ServerConfig) -> Self {
+        let active_source = stream_config.source_path().clone();
+        let recording_state = match stream_config.recording() {
+            Some(_) => RecordingState::Idle,
+            None => RecordingState::Disabled,
+        };
         Self {
             id: Uuid::new_v4().to_string(),
             stream_config,
             server_config,
             started_at: Instant::now(),
             state: SessionState::Starting,
+            bytes_sent: 0,
+            media_count: 0,
+            loops: 0,
+            faults: 0,
+            active_source,
+            recording_state,
+            recorded_segments: Vec::new(),
         }
     }
 
@@ -76,6 +118,98 @@ impl StreamSession {
         }
     }
 
+    pub fn add_bytes_sent(&mut self, bytes: u64) {
+        self.bytes_sent += bytes;
+    }
+
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    /// A media pipeline was constructed; shared mounts have at most one
+    pub fn add_media(&mut self) {
+        self.media_count += 1;
+    }
+
+    pub fn remove_media(&mut self) {
+        self.media_count = self.media_count.saturating_sub(1);
+    }
+
+    /// Media pipelines currently prepared for this mount
+    pub fn media_count(&self) -> u32 {
+        self.media_count
+    }
+
+    /// The file was restarted at the end of a loop iteration
+    pub fn add_loop(&mut self) {
+        self.loops += 1;
+    }
+
+    pub fn loop_count(&self) -> u64 {
+        self.loops
+    }
+
+    /// A fault was injected into the mount's output
+    pub fn add_fault(&mut self) {
+        self.faults += 1;
+    }
+
+    pub fn fault_count(&self) -> u64 {
+        self.faults
+    }
+
+    /// File the mount's media is playing now
+    pub fn active_source(&self) -> &Path {
+        &self.active_source
+    }
+
+    pub fn set_active_source(&mut self, source: PathBuf) {
+        self.active_source = source;
+    }
+
+    /// File the schedule selects right now, if the mount plays another one
+    pub fn pending_scheduled_source(&self) -> Option<&Path> {
+        self.stream_config.schedule()?;
+        let wanted = self.stream_config.scheduled_source();
+        (wanted != self.active_source).then_some(wanted)
+    }
+
+    pub fn recording_state(&self) -> &RecordingState {
+        &self.recording_state
+    }
+
+    pub fn set_recording_state(&mut self, state: RecordingState) {
+        self.recording_state = state;
+    }
+
+    /// Recording files of the mount that are still kept, oldest first
+    pub fn recorded_segments(&self) -> &[PathBuf] {
+        &self.recorded_segments
+    }
+
+    /// The recording moved on to a new file; returns the files past the retention count
+    pub fn add_recorded_segment(&mut self, path: PathBuf) -> Vec<PathBuf> {
+        self.recording_state = RecordingState::Recording;
+        self.recorded_segments.push(path);
+        self.expire_recorded_segments()
+    }
+
+    /// Take over files earlier runs recorded, oldest first; returns those past the retention count
+    pub fn restore_recorded_segments(&mut self, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        self.recorded_segments = paths;
+        self.expire_recorded_segments()
+    }
+
+    fn expire_recorded_segments(&mut self) -> Vec<PathBuf> {
+        let kept = self
+            .stream_config
+            .recording()
+            .and_then(|recording| recording.max_segments())
+            .map_or(usize::MAX, |count| count as usize);
+        let expired = self.recorded_segments.len().saturating_sub(kept);
+        self.recorded_segments.drain(..expired).collect()
+    }
+
     pub fn stop(&mut self) {
         self.state = SessionState::Stopping;
     }
@@ -88,7 +222,7 @@ impl StreamSession {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use crate::domain::value_objects::{RecordingConfig, Schedule};
 
     fn create_test_session() -> StreamSession {
         let stream_config = StreamConfig::new(PathBuf::from("/test/video.mp4"));
@@ -136,6 +270,129 @@ mod tests {
         assert_eq!(session.client_count(), 0);
     }
 
+    #[test]
+    fn test_add_bytes_sent_accumulates() {
+        let mut session = create_test_session();
+        session.activate();
+        session.add_bytes_sent(1200);
+        session.add_bytes_sent(300);
+        assert_eq!(session.bytes_sent(), 1500);
+    }
+
+    #[test]
+    fn test_media_count_tracks_instances() {
+        let mut session = create_test_session();
+        session.add_media();
+        session.add_media();
+        session.remove_media();
+        assert_eq!(session.media_count(), 1);
+        session.remove_media();
+        session.remove_media();
+        assert_eq!(session.media_count(), 0);
+    }
+
+    #[test]
+    fn test_add_loop_counts_restarts() {
+        let mut session = create_test_session();
+        session.add_loop();
+        session.add_loop();
+        assert_eq!(session.loop_count(), 2);
+    }
+
+    #[test]
+    fn test_add_fault_counts_injections() {
+        let mut session = create_test_session();
+        session.add_fault();
+        assert_eq!(session.fault_count(), 1);
+    }
+
+    #[test]
+    fn test_active_source_defaults_to_source_path() {
+        let mut session = create_test_session();
+        assert_eq!(session.active_source(), Path::new("/test/video.mp4"));
+        session.set_active_source(PathBuf::from("/test/night.mp4"));
+        assert_eq!(session.active_source(), Path::new("/test/night.mp4"));
+    }
+
+    #[test]
+    fn test_pending_scheduled_source() {
+        assert_eq!(create_test_session().pending_scheduled_source(), None);
+
+        // Two windows covering the whole day always select one of their files
+        let windows = [
+            "00:00-12:00=/test/morning.mp4",
+            "12:00-00:00=/test/evening.mp4",
+        ]
+        .iter()
+        .map(|window| window.parse().unwrap())
+        .collect();
+        let stream_config = StreamConfig::new(PathBuf::from("/test/video.mp4"))
+            .with_schedule(Schedule::new(windows).unwrap());
+        let server_config = ServerConfig::new(8554, "/cam1".to_string()).unwrap();
+        let mut session = StreamSession::new(stream_config, server_config);
+
+        let wanted = session.pending_scheduled_source().unwrap().to_path_buf();
+        assert_ne!(wanted, Path::new("/test/video.mp4"));
+        session.set_active_source(wanted);
+        assert_eq!(session.pending_scheduled_source(), None);
+    }
+
+    #[test]
+    fn test_recorded_segments_follow_retention() {
+        let recording = RecordingConfig::new("/recordings", "/cam1")
+            .unwrap()
+            .with_max_segments(2);
+        let stream_config =
+            StreamConfig::new(PathBuf::from("/test/video.mp4")).with_recording(recording);
+        let server_config = ServerConfig::new(8554, "/cam1".to_string()).unwrap();
+        let mut session = StreamSession::new(stream_config, server_config);
+        assert_eq!(session.recording_state(), &RecordingState::Idle);
+
+        assert!(session
+            .add_recorded_segment(PathBuf::from("/recordings/a.mp4"))
+            .is_empty());
+        assert!(session
+            .add_recorded_segment(PathBuf::from("/recordings/b.mp4"))
+            .is_empty());
+        assert_eq!(
+            session.add_recorded_segment(PathBuf::from("/recordings/c.mp4")),
+            vec![PathBuf::from("/recordings/a.mp4")]
+        );
+        assert_eq!(session.recorded_segments().len(), 2);
+        assert_eq!(session.recording_state(), &RecordingState::Recording);
+        assert_eq!(
+            create_test_session().recording_state(),
+            &RecordingState::Disabled
+        );
+    }
+
+    #[test]
+    fn test_restored_segments_count_toward_retention() {
+        let recording = RecordingConfig::new("/recordings", "/cam1")
+            .unwrap()
+            .with_max_segments(2);
+        let stream_config =
+            StreamConfig::new(PathBuf::from("/test/video.mp4")).with_recording(recording);
+        let server_config = ServerConfig::new(8554, "/cam1".to_string()).unwrap();
+        let mut session = StreamSession::new(stream_config, server_config);
+
+        let earlier = [
+            "/recordings/1-00000.mp4",
+            "/recordings/1-00001.mp4",
+            "/recordings/2-00000.mp4",
+        ]
+        .map(PathBuf::from);
+        assert_eq!(
+            session.restore_recorded_segments(earlier.to_vec()),
+            vec![PathBuf::from("/recordings/1-00000.mp4")]
+        );
+        assert_eq!(session.recording_state(), &RecordingState::Idle);
+        assert_eq!(
+            session.add_recorded_segment(PathBuf::from("/recordings/3-00000.mp4")),
+            vec![PathBuf::from("/recordings/1-00001.mp4")]
+        );
+    }
+
     #[test]
     fn test_session_has_unique_id() {
         let session1 = create_test_session();
@@ -29,6 +29,66 @@ pub enum DomainError {
 
     #[error("Unsupported container format: {0}")]
     UnsupportedContainer(String),
+
+    #[error("Invalid transcode profile: {0}")]
+    InvalidTranscodeProfile(String),
+
+    #[error("Invalid authentication config: {0}")]
+    InvalidAuthConfig(String),
+
+    #[error("Invalid TLS certificate: {0}")]
+    InvalidTlsCertificate(String),
+
+    #[error("Invalid RTP profile: {0}")]
+    InvalidRtpProfile(String),
+
+    #[error("Invalid transport configuration: {0}")]
+    InvalidTransportConfig(String),
+
+    #[error("Invalid sharing mode: {0}")]
+    InvalidSharingMode(String),
+
+    #[error("Invalid playlist: {0}")]
+    InvalidPlaylist(String),
+
+    #[error("Invalid schedule: {0}")]
+    InvalidSchedule(String),
+
+    #[error("Invalid live configuration: {0}")]
+    InvalidLiveConfig(String),
+
+    #[error("Invalid test pattern: {0}")]
+    InvalidTestPattern(String),
+
+    #[error("Invalid recording: {0}")]
+    InvalidRecording(String),
+
+    #[error("Invalid fault: {0}")]
+    InvalidFault(String),
+
+    #[error("Fault injection is disabled (start with --chaos)")]
+    FaultInjectionDisabled,
+
+    #[error("Source switch failed: {0}")]
+    SourceSwitchFailed(String),
+
+    #[error("Snapshot failed: {0}")]
+    SnapshotFailed(String),
+
+    #[error("Media probe failed: {0}")]
+    MediaProbeFailed(String),
+
+    #[error("Mount point already in use: {0}")]
+    DuplicateMount(String),
+
+    #[error("Mount point not found: {0}")]
+    MountNotFound(String),
+
+    #[error("Server is bound to port {expected}, cannot mount on port {actual}")]
+    PortMismatch { expected: u16, actual: u16 },
+
+    #[error("Server is shutting down")]
+    ShuttingDown,
 }
 
 pub type Result<T> = std::result::Result<T, DomainError>;
@@ -0,0 +1,10 @@
+use std::path::Path;
+
+use crate::domain::errors::Result;
+use crate::domain::value_objects::MediaInfo;
+
+/// Port for inspecting media files before they are served
+pub trait MediaProbe: Send + Sync {
+    /// Detect container, codecs and stream properties of a file
+    fn probe(&self, path: &Path) -> Result<MediaInfo>;
+}
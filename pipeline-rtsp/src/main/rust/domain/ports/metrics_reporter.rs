@@ -1,9 +1,17 @@
 use crate::domain::entities::StreamSession;
+use crate::domain::value_objects::{ClientTransport, Fault, WatchEvent};
 
 /// Port for metrics reporting
 pub trait MetricsReporter: Send + Sync {
     fn report_session_started(&self, session: &StreamSession);
     fn report_session_stopped(&self, session: &StreamSession);
-    fn report_client_connected(&self);
-    fn report_client_disconnected(&self);
+    fn report_client_connected(&self, mount_point: &str);
+    fn report_client_disconnected(&self, mount_point: &str);
+    fn report_bytes_sent(&self, mount_point: &str, bytes: u64);
+    fn report_unauthenticated_attempt(&self);
+    fn report_srtp_negotiated(&self, mount_point: &str);
+    fn report_client_transport(&self, mount_point: &str, transport: ClientTransport);
+    fn report_loop_completed(&self, mount_point: &str);
+    fn report_fault_injected(&self, mount_point: &str, fault: &Fault);
+    fn report_watch_event(&self, event: &WatchEvent, accepted: bool);
 }
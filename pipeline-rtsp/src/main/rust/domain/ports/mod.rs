@@ -1,5 +1,7 @@
+mod media_probe;
 mod metrics_reporter;
 mod streaming_server;
 
+pub use media_probe::MediaProbe;
 pub use metrics_reporter::MetricsReporter;
-pub use streaming_server::StreamingServer;
+pub use streaming_server::{SnapshotJob, StreamingServer};
@@ -1,25 +1,56 @@
 use async_trait::async_trait;
+use std::path::Path;
 
-use crate::domain::entities::StreamSession;
+use crate::domain::entities::{ClientSession, StreamSession};
 use crate::domain::errors::Result;
-use crate::domain::value_objects::{ServerConfig, StreamConfig};
+use crate::domain::value_objects::{Fault, ServerConfig, StreamConfig};
+
+/// Decoding of a snapshot, run once the server is no longer borrowed
+pub type SnapshotJob = Box<dyn FnOnce() -> Result<Vec<u8>> + Send>;
 
 /// Port for streaming server implementations
+///
+/// A server hosts any number of mount points, each backed by its own
+/// `StreamSession` and keyed by mount point.
 #[async_trait]
 pub trait StreamingServer: Send + Sync {
-    /// Start server and begin streaming
+    /// Start server if needed and begin streaming on the config's mount point
     async fn start(
         &mut self,
         stream_config: StreamConfig,
         server_config: ServerConfig,
     ) -> Result<StreamSession>;
 
-    /// Stop server gracefully
+    /// Remove a single mount, returning its stopped session
+    async fn remove(&mut self, mount_point: &str) -> Result<StreamSession>;
+
+    /// Play another file on a mount without disconnecting its clients
+    async fn switch_source(&mut self, mount_point: &str, source: &Path) -> Result<()>;
+
+    /// Disturb a mount's output; timed faults end on their own
+    async fn inject_fault(&mut self, mount_point: &str, fault: Fault) -> Result<()>;
+
+    /// Current picture of a mount as a JPEG no wider than `max_width`
+    ///
+    /// Only gathers what the picture needs; the returned job blocks while the
+    /// frame is decoded.
+    fn snapshot(&self, mount_point: &str, max_width: u32) -> Result<SnapshotJob>;
+
+    /// Refuse new clients and send EOS to every mount, leaving connected clients to finish
+    async fn drain(&mut self) -> Result<()>;
+
+    /// Stop server gracefully, tearing down every mount
     async fn stop(&mut self) -> Result<()>;
 
     /// Check if server is running
     fn is_running(&self) -> bool;
 
-    /// Get current session if any
-    fn current_session(&self) -> Option<&StreamSession>;
+    /// Get a snapshot of the session serving a mount point, if any
+    fn session(&self, mount_point: &str) -> Option<StreamSession>;
+
+    /// Get snapshots of all sessions, ordered by mount point
+    fn sessions(&self) -> Vec<StreamSession>;
+
+    /// Get snapshots of all connected RTSP clients
+    fn clients(&self) -> Vec<ClientSession>;
 }
@@ -0,0 +1,50 @@
+use std::str::FromStr;
+
+use crate::domain::errors::DomainError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioCodec {
+    AAC,
+    Opus,
+}
+
+impl AudioCodec {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AudioCodec::AAC => "AAC",
+            AudioCodec::Opus => "Opus",
+        }
+    }
+}
+
+impl FromStr for AudioCodec {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "aac" => Ok(AudioCodec::AAC),
+            "opus" => Ok(AudioCodec::Opus),
+            _ => Err(DomainError::UnsupportedCodec(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_audio_codec() {
+        assert_eq!("AAC".parse::<AudioCodec>().unwrap(), AudioCodec::AAC);
+        assert_eq!("opus".parse::<AudioCodec>().unwrap(), AudioCodec::Opus);
+    }
+
+    #[test]
+    fn test_parse_unknown_audio_codec() {
+        let result = "mp3".parse::<AudioCodec>();
+        assert!(matches!(
+            result.unwrap_err(),
+            DomainError::UnsupportedCodec(_)
+        ));
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use super::ServerConfig;
+use crate::domain::errors::{DomainError, Result};
+
+/// RTSP authentication scheme offered to clients
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthMethod {
+    Basic,
+    Digest,
+}
+
+impl AuthMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthMethod::Basic => "basic",
+            AuthMethod::Digest => "digest",
+        }
+    }
+}
+
+impl FromStr for AuthMethod {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "basic" => Ok(AuthMethod::Basic),
+            "digest" => Ok(AuthMethod::Digest),
+            _ => Err(DomainError::InvalidAuthConfig(format!(
+                "unknown auth method {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Credentials of one RTSP user, parsed from `username:password`
+#[derive(Clone, PartialEq)]
+pub struct RtspUser {
+    username: String,
+    password: String,
+}
+
+impl RtspUser {
+    pub fn new(username: String, password: String) -> Result<Self> {
+        Self::validate_username(&username)?;
+        if password.is_empty() {
+            return Err(DomainError::InvalidAuthConfig(format!(
+                "user {} has an empty password",
+                username
+            )));
+        }
+        Ok(Self { username, password })
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    /// Usernames double as permission roles, which must be valid structure names
+    fn validate_username(username: &str) -> Result<()> {
+        let starts_with_letter = username
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic());
+        let valid_chars = username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.');
+        if !starts_with_letter || !valid_chars {
+            return Err(DomainError::InvalidAuthConfig(format!(
+                "invalid username {:?}",
+                username
+            )));
+        }
+        Ok(())
+    }
+}
+
+// Keep passwords out of logged configuration
+impl fmt::Debug for RtspUser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RtspUser")
+            .field("username", &self.username)
+            .field("password", &"***")
+            .finish()
+    }
+}
+
+impl FromStr for RtspUser {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (username, password) = s.split_once(':').ok_or_else(|| {
+            DomainError::InvalidAuthConfig("expected username:password".to_string())
+        })?;
+        Self::new(username.trim().to_string(), password.to_string())
+    }
+}
+
+/// Users and schemes required to access the RTSP server
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthConfig {
+    users: Vec<RtspUser>,
+    methods: Vec<AuthMethod>,
+}
+
+impl AuthConfig {
+    pub fn new(users: Vec<RtspUser>, methods: Vec<AuthMethod>) -> Result<Self> {
+        if users.is_empty() {
+            return Err(DomainError::InvalidAuthConfig(
+                "at least one user is required".to_string(),
+            ));
+        }
+        if methods.is_empty() {
+            return Err(DomainError::InvalidAuthConfig(
+                "at least one auth method is required".to_string(),
+            ));
+        }
+
+        let mut seen = HashSet::new();
+        if let Some(duplicate) = users.iter().find(|user| !seen.insert(user.username())) {
+            return Err(DomainError::InvalidAuthConfig(format!(
+                "user {} is defined more than once",
+                duplicate.username()
+            )));
+        }
+
+        Ok(Self { users, methods })
+    }
+
+    pub fn users(&self) -> &[RtspUser] {
+        &self.users
+    }
+
+    pub fn methods(&self) -> &[AuthMethod] {
+        &self.methods
+    }
+
+    pub fn supports(&self, method: AuthMethod) -> bool {
+        self.methods.contains(&method)
+    }
+
+    /// Users allowed to play a mount: its own list, or every user when unrestricted
+    pub fn users_for_mount<'a>(&'a self, server_config: &'a ServerConfig) -> Result<Vec<&'a str>> {
+        let Some(allowed) = server_config.allowed_users() else {
+            return Ok(self.users.iter().map(RtspUser::username).collect());
+        };
+
+        if let Some(unknown) = allowed.iter().find(|name| {
+            !self
+                .users
+                .iter()
+                .any(|user| user.username() == name.as_str())
+        }) {
+            return Err(DomainError::InvalidAuthConfig(format!(
+                "mount {} allows unknown user {}",
+                server_config.mount_point(),
+                unknown
+            )));
+        }
+
+        Ok(allowed.iter().map(String::as_str).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn users() -> Vec<RtspUser> {
+        vec![
+            "alice:secret".parse().unwrap(),
+            "bob:hunter2".parse().unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_parse_user() {
+        let user: RtspUser = "alice:pa:ss".parse().unwrap();
+        assert_eq!(user.username(), "alice");
+        assert_eq!(user.password(), "pa:ss");
+        assert!(!format!("{:?}", user).contains("pa:ss"));
+    }
+
+    #[test]
+    fn test_rejects_malformed_users() {
+        assert!("alice".parse::<RtspUser>().is_err());
+        assert!("alice:".parse::<RtspUser>().is_err());
+        assert!("1alice:secret".parse::<RtspUser>().is_err());
+        assert!("al ice:secret".parse::<RtspUser>().is_err());
+    }
+
+    #[test]
+    fn test_rejects_duplicate_users() {
+        let mut users = users();
+        users.push("alice:other".parse().unwrap());
+
+        let result = AuthConfig::new(users, vec![AuthMethod::Digest]);
+        assert!(matches!(
+            result.unwrap_err(),
+            DomainError::InvalidAuthConfig(_)
+        ));
+    }
+
+    #[test]
+    fn test_users_for_mount() {
+        let auth = AuthConfig::new(users(), vec![AuthMethod::Basic, AuthMethod::Digest]).unwrap();
+
+        let open = ServerConfig::new(8554, "/cam1".to_string()).unwrap();
+        assert_eq!(auth.users_for_mount(&open).unwrap(), vec!["alice", "bob"]);
+
+        let restricted = open.clone().with_allowed_users(vec!["bob".to_string()]);
+        assert_eq!(auth.users_for_mount(&restricted).unwrap(), vec!["bob"]);
+
+        let unknown = open.with_allowed_users(vec!["mallory".to_string()]);
+        assert!(auth.users_for_mount(&unknown).is_err());
+    }
+}
@@ -0,0 +1,25 @@
+/// Lower transport negotiated by an RTSP client during SETUP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientTransport {
+    Unknown,
+    Udp,
+    UdpMulticast,
+    Tcp,
+}
+
+impl ClientTransport {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClientTransport::Unknown => "unknown",
+            ClientTransport::Udp => "udp",
+            ClientTransport::UdpMulticast => "udp-multicast",
+            ClientTransport::Tcp => "tcp",
+        }
+    }
+}
+
+impl Default for ClientTransport {
+    fn default() -> Self {
+        ClientTransport::Unknown
+    }
+}
@@ -1,7 +1,17 @@
+use std::str::FromStr;
+
+use super::VideoCodec;
+use crate::domain::errors::DomainError;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ContainerFormat {
     MP4,
     MKV,
+    MpegTs,
+    WebM,
+    Flv,
+    /// Bare Annex-B H.264/H.265 bitstream without a container
+    RawElementaryStream,
 }
 
 impl ContainerFormat {
@@ -9,6 +19,29 @@ impl ContainerFormat {
         match self {
             ContainerFormat::MP4 => "MP4",
             ContainerFormat::MKV => "MKV",
+            ContainerFormat::MpegTs => "MPEG-TS",
+            ContainerFormat::WebM => "WebM",
+            ContainerFormat::Flv => "FLV",
+            ContainerFormat::RawElementaryStream => "RAW",
+        }
+    }
+
+    /// Whether the format can carry an audio track next to the video
+    pub fn supports_audio(&self) -> bool {
+        !matches!(self, ContainerFormat::RawElementaryStream)
+    }
+
+    /// Whether the demuxer can read this video codec from the format
+    pub fn carries(&self, codec: VideoCodec) -> bool {
+        match self {
+            ContainerFormat::MP4 | ContainerFormat::MKV => true,
+            ContainerFormat::MpegTs | ContainerFormat::RawElementaryStream => {
+                matches!(codec, VideoCodec::H264 | VideoCodec::H265)
+            }
+            ContainerFormat::WebM => {
+                matches!(codec, VideoCodec::VP8 | VideoCodec::VP9 | VideoCodec::AV1)
+            }
+            ContainerFormat::Flv => codec == VideoCodec::H264,
         }
     }
 }
@@ -18,3 +51,74 @@ impl Default for ContainerFormat {
         ContainerFormat::MP4
     }
 }
+
+impl FromStr for ContainerFormat {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "mp4" | "mov" => Ok(ContainerFormat::MP4),
+            "mkv" | "matroska" => Ok(ContainerFormat::MKV),
+            "ts" | "mpegts" | "mpeg-ts" | "m2ts" => Ok(ContainerFormat::MpegTs),
+            "webm" => Ok(ContainerFormat::WebM),
+            "flv" => Ok(ContainerFormat::Flv),
+            "raw" | "es" | "annexb" | "annex-b" => Ok(ContainerFormat::RawElementaryStream),
+            _ => Err(DomainError::UnsupportedContainer(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_container_aliases() {
+        assert_eq!(
+            "mp4".parse::<ContainerFormat>().unwrap(),
+            ContainerFormat::MP4
+        );
+        assert_eq!(
+            "Matroska".parse::<ContainerFormat>().unwrap(),
+            ContainerFormat::MKV
+        );
+        assert_eq!(
+            "mpegts".parse::<ContainerFormat>().unwrap(),
+            ContainerFormat::MpegTs
+        );
+        assert_eq!(
+            "WebM".parse::<ContainerFormat>().unwrap(),
+            ContainerFormat::WebM
+        );
+        assert_eq!(
+            "flv".parse::<ContainerFormat>().unwrap(),
+            ContainerFormat::Flv
+        );
+        assert_eq!(
+            "annexb".parse::<ContainerFormat>().unwrap(),
+            ContainerFormat::RawElementaryStream
+        );
+    }
+
+    #[test]
+    fn test_containers_carry_their_codecs() {
+        assert!(ContainerFormat::RawElementaryStream.carries(VideoCodec::H265));
+        assert!(!ContainerFormat::RawElementaryStream.carries(VideoCodec::VP8));
+        assert!(ContainerFormat::MpegTs.carries(VideoCodec::H264));
+        assert!(!ContainerFormat::MpegTs.carries(VideoCodec::MJPEG));
+        assert!(ContainerFormat::WebM.carries(VideoCodec::AV1));
+        assert!(!ContainerFormat::WebM.carries(VideoCodec::H264));
+        assert!(!ContainerFormat::Flv.carries(VideoCodec::H265));
+        assert!(ContainerFormat::MKV.carries(VideoCodec::H265));
+        assert!(ContainerFormat::MKV.carries(VideoCodec::VP9));
+    }
+
+    #[test]
+    fn test_parse_unknown_container() {
+        let result = "avi".parse::<ContainerFormat>();
+        assert!(matches!(
+            result.unwrap_err(),
+            DomainError::UnsupportedContainer(_)
+        ));
+    }
+}
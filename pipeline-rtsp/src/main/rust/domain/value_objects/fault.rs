@@ -0,0 +1,218 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::domain::errors::{DomainError, Result};
+
+/// Failure injected into a mount's output to exercise client resilience
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fault {
+    /// Send nothing for a while, like a camera that hangs
+    Freeze { duration: Duration },
+    /// Discard a share of RTP packets, like a lossy network
+    DropPackets { percent: u8, duration: Duration },
+    /// Hold every packet back by `delay`
+    Latency { delay: Duration, duration: Duration },
+    /// End the stream
+    Eos,
+    /// Disconnect every client of the mount
+    TeardownClients,
+}
+
+impl Fault {
+    /// Every value `kind` returns
+    pub const KINDS: [&'static str; 5] = ["freeze", "drop", "latency", "eos", "teardown"];
+
+    /// Longest latency; packets are held on the streaming thread, which stalls meanwhile
+    pub const MAX_DELAY: Duration = Duration::from_secs(5);
+
+    pub fn freeze(duration: Duration) -> Result<Self> {
+        Self::Freeze { duration }.validated()
+    }
+
+    pub fn drop_packets(percent: u8, duration: Duration) -> Result<Self> {
+        Self::DropPackets { percent, duration }.validated()
+    }
+
+    pub fn latency(delay: Duration, duration: Duration) -> Result<Self> {
+        Self::Latency { delay, duration }.validated()
+    }
+
+    fn validated(self) -> Result<Self> {
+        if self.duration().is_some_and(|duration| duration.is_zero()) {
+            return Err(DomainError::InvalidFault(format!(
+                "{} needs a duration",
+                self.kind()
+            )));
+        }
+        match self {
+            Self::DropPackets { percent, .. } if !(1..=100).contains(&percent) => Err(
+                DomainError::InvalidFault(format!("drop percent {} is not in 1-100", percent)),
+            ),
+            Self::Latency { delay, .. } if delay.is_zero() => Err(DomainError::InvalidFault(
+                "latency needs a delay".to_string(),
+            )),
+            Self::Latency { delay, .. } if delay > Self::MAX_DELAY => {
+                Err(DomainError::InvalidFault(format!(
+                    "latency {:?} exceeds {:?}",
+                    delay,
+                    Self::MAX_DELAY
+                )))
+            }
+            _ => Ok(self),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Freeze { .. } => "freeze",
+            Self::DropPackets { .. } => "drop",
+            Self::Latency { .. } => "latency",
+            Self::Eos => "eos",
+            Self::TeardownClients => "teardown",
+        }
+    }
+
+    /// How long the fault lasts; EOS and teardown happen at once
+    pub fn duration(&self) -> Option<Duration> {
+        match self {
+            Self::Freeze { duration }
+            | Self::DropPackets { duration, .. }
+            | Self::Latency { duration, .. } => Some(*duration),
+            Self::Eos | Self::TeardownClients => None,
+        }
+    }
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Freeze { duration } => write!(f, "freeze for {:?}", duration),
+            Self::DropPackets { percent, duration } => {
+                write!(f, "drop {}% for {:?}", percent, duration)
+            }
+            Self::Latency { delay, duration } => {
+                write!(f, "latency {:?} for {:?}", delay, duration)
+            }
+            Self::Eos => write!(f, "eos"),
+            Self::TeardownClients => write!(f, "teardown"),
+        }
+    }
+}
+
+/// Random fault injection: one of `faults` every `min_interval` to `max_interval`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChaosSchedule {
+    faults: Vec<Fault>,
+    min_interval: Duration,
+    max_interval: Duration,
+}
+
+impl ChaosSchedule {
+    pub fn new(faults: Vec<Fault>, min_interval: Duration, max_interval: Duration) -> Result<Self> {
+        if faults.is_empty() {
+            return Err(DomainError::InvalidFault(
+                "a chaos schedule needs at least one fault".to_string(),
+            ));
+        }
+        if min_interval.is_zero() || min_interval > max_interval {
+            return Err(DomainError::InvalidFault(format!(
+                "interval {:?}-{:?} is not a valid range",
+                min_interval, max_interval
+            )));
+        }
+        Ok(Self {
+            faults,
+            min_interval,
+            max_interval,
+        })
+    }
+
+    pub fn faults(&self) -> &[Fault] {
+        &self.faults
+    }
+
+    pub fn min_interval(&self) -> Duration {
+        self.min_interval
+    }
+
+    pub fn max_interval(&self) -> Duration {
+        self.max_interval
+    }
+
+    /// Wait before the next fault and the fault to inject, advancing an xorshift64 state
+    pub fn next(&self, state: &mut u64) -> (Duration, Fault) {
+        // The state must never be zero
+        let mut next_random = || {
+            *state = (*state).max(1);
+            *state ^= *state << 13;
+            *state ^= *state >> 7;
+            *state ^= *state << 17;
+            *state
+        };
+
+        let spread = (self.max_interval - self.min_interval).as_millis() as u64;
+        let wait = self.min_interval + Duration::from_millis(next_random() % (spread + 1));
+        let fault = self.faults[(next_random() % self.faults.len() as u64) as usize];
+        (wait, fault)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn test_timed_faults_need_a_duration() {
+        assert!(Fault::freeze(SECOND).is_ok());
+        assert!(matches!(
+            Fault::freeze(Duration::ZERO).unwrap_err(),
+            DomainError::InvalidFault(_)
+        ));
+        assert!(Fault::latency(Duration::ZERO, SECOND).is_err());
+        assert_eq!(Fault::Eos.duration(), None);
+    }
+
+    #[test]
+    fn test_latency_delay_is_capped() {
+        assert!(Fault::latency(Fault::MAX_DELAY, SECOND).is_ok());
+        assert!(matches!(
+            Fault::latency(Fault::MAX_DELAY + SECOND, SECOND).unwrap_err(),
+            DomainError::InvalidFault(_)
+        ));
+    }
+
+    #[test]
+    fn test_drop_percent_range() {
+        assert!(Fault::drop_packets(100, SECOND).is_ok());
+        assert!(Fault::drop_packets(0, SECOND).is_err());
+        assert!(Fault::drop_packets(101, SECOND).is_err());
+    }
+
+    #[test]
+    fn test_schedule_rejects_invalid_ranges() {
+        let faults = vec![Fault::Eos];
+        assert!(ChaosSchedule::new(vec![], SECOND, SECOND).is_err());
+        assert!(ChaosSchedule::new(faults.clone(), Duration::ZERO, SECOND).is_err());
+        assert!(ChaosSchedule::new(faults.clone(), 2 * SECOND, SECOND).is_err());
+        assert!(ChaosSchedule::new(faults, SECOND, SECOND).is_ok());
+    }
+
+    #[test]
+    fn test_next_stays_in_range_and_uses_every_fault() {
+        let faults = vec![Fault::Eos, Fault::TeardownClients];
+        let schedule = ChaosSchedule::new(faults, 10 * SECOND, 20 * SECOND).unwrap();
+
+        let mut state = 42;
+        let mut seen = Vec::new();
+        for _ in 0..50 {
+            let (wait, fault) = schedule.next(&mut state);
+            assert!((10 * SECOND..=20 * SECOND).contains(&wait));
+            if !seen.contains(&fault) {
+                seen.push(fault);
+            }
+        }
+        assert_eq!(seen.len(), 2);
+    }
+}
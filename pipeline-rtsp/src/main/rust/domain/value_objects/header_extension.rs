@@ -0,0 +1,54 @@
+use std::str::FromStr;
+
+use crate::domain::errors::DomainError;
+
+/// Absolute capture time added to every RTP packet of a live mount
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderExtension {
+    /// RFC 6051 64-bit NTP timestamp (`urn:ietf:params:rtp-hdrext:ntp-64`)
+    Ntp64,
+    /// ONVIF replay extension: NTP timestamp plus clean-point and discontinuity flags
+    OnvifReplay,
+}
+
+impl HeaderExtension {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HeaderExtension::Ntp64 => "ntp-64",
+            HeaderExtension::OnvifReplay => "onvif-replay",
+        }
+    }
+}
+
+impl FromStr for HeaderExtension {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "ntp-64" | "ntp64" | "rfc6051" => Ok(HeaderExtension::Ntp64),
+            "onvif-replay" | "onvif" => Ok(HeaderExtension::OnvifReplay),
+            _ => Err(DomainError::InvalidLiveConfig(format!(
+                "unknown header extension {}",
+                s
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header_extensions() {
+        assert_eq!(
+            "RFC6051".parse::<HeaderExtension>().unwrap(),
+            HeaderExtension::Ntp64
+        );
+        assert_eq!(
+            "onvif-replay".parse::<HeaderExtension>().unwrap(),
+            HeaderExtension::OnvifReplay
+        );
+        assert!("abs-send-time".parse::<HeaderExtension>().is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use super::{AudioCodec, ContainerFormat, VideoCodec};
+
+/// What a media file actually contains, as reported by a `MediaProbe`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaInfo {
+    container: ContainerFormat,
+    /// None when the codec can only be decoded, not passed through
+    video_codec: Option<VideoCodec>,
+    /// Media type of a codec that can only be decoded, such as `video/mpeg`
+    unsupported_video_type: Option<String>,
+    audio_codec: Option<AudioCodec>,
+    resolution: Option<(u32, u32)>,
+    framerate: Option<(u32, u32)>,
+    duration: Option<Duration>,
+}
+
+impl MediaInfo {
+    pub fn new(container: ContainerFormat, video_codec: VideoCodec) -> Self {
+        Self {
+            container,
+            video_codec: Some(video_codec),
+            unsupported_video_type: None,
+            audio_codec: None,
+            resolution: None,
+            framerate: None,
+            duration: None,
+        }
+    }
+
+    /// A file whose video codec cannot be served as is, only transcoded
+    pub fn with_unsupported_codec(container: ContainerFormat, media_type: &str) -> Self {
+        Self {
+            video_codec: None,
+            unsupported_video_type: Some(media_type.to_string()),
+            ..Self::new(container, VideoCodec::default())
+        }
+    }
+
+    pub fn with_audio_codec(mut self, codec: AudioCodec) -> Self {
+        self.audio_codec = Some(codec);
+        self
+    }
+
+    pub fn with_resolution(mut self, width: u32, height: u32) -> Self {
+        self.resolution = Some((width, height));
+        self
+    }
+
+    pub fn with_framerate(mut self, numerator: u32, denominator: u32) -> Self {
+        if denominator > 0 {
+            self.framerate = Some((numerator, denominator));
+        }
+        self
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    pub fn container(&self) -> &ContainerFormat {
+        &self.container
+    }
+
+    pub fn video_codec(&self) -> Option<&VideoCodec> {
+        self.video_codec.as_ref()
+    }
+
+    pub fn unsupported_video_type(&self) -> Option<&str> {
+        self.unsupported_video_type.as_deref()
+    }
+
+    pub fn audio_codec(&self) -> Option<&AudioCodec> {
+        self.audio_codec.as_ref()
+    }
+
+    /// Width and height in pixels
+    pub fn resolution(&self) -> Option<(u32, u32)> {
+        self.resolution
+    }
+
+    /// Frames per second
+    pub fn framerate(&self) -> Option<f64> {
+        self.framerate
+            .map(|(numerator, denominator)| f64::from(numerator) / f64::from(denominator))
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_framerate_from_fraction() {
+        let info =
+            MediaInfo::new(ContainerFormat::MP4, VideoCodec::H264).with_framerate(30000, 1001);
+        let framerate = info.framerate().unwrap();
+        assert!((framerate - 29.97).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_ignores_zero_denominator() {
+        let info = MediaInfo::new(ContainerFormat::MP4, VideoCodec::H264).with_framerate(30, 0);
+        assert_eq!(info.framerate(), None);
+    }
+}
@@ -1,9 +1,47 @@
+mod audio_codec;
+mod auth_config;
+mod client_transport;
 mod container_format;
+mod fault;
+mod header_extension;
+mod media_info;
+mod multicast_pool;
+mod playlist;
+mod port_range;
+mod recording_config;
+mod rtp_profile;
+mod schedule;
 mod server_config;
+mod sharing_mode;
+mod source_kind;
 mod stream_config;
+mod test_pattern;
+mod tls_config;
+mod transcode_profile;
+mod transport_policy;
 mod video_codec;
+mod watch_event;
 
+pub use audio_codec::AudioCodec;
+pub use auth_config::{AuthConfig, AuthMethod, RtspUser};
+pub use client_transport::ClientTransport;
 pub use container_format::ContainerFormat;
+pub use fault::{ChaosSchedule, Fault};
+pub use header_extension::HeaderExtension;
+pub use media_info::MediaInfo;
+pub use multicast_pool::MulticastPool;
+pub use playlist::{Playlist, PlaylistItem};
+pub use port_range::PortRange;
+pub use recording_config::RecordingConfig;
+pub use rtp_profile::RtpProfile;
+pub use schedule::{Schedule, ScheduleWindow, TimeOfDay};
 pub use server_config::ServerConfig;
+pub use sharing_mode::SharingMode;
+pub use source_kind::SourceKind;
 pub use stream_config::StreamConfig;
+pub use test_pattern::{PatternOverlay, TestPattern};
+pub use tls_config::TlsConfig;
+pub use transcode_profile::{EncoderProfile, TranscodeProfile};
+pub use transport_policy::TransportPolicy;
 pub use video_codec::VideoCodec;
+pub use watch_event::{WatchEvent, CLIP_MOUNT_PREFIX};
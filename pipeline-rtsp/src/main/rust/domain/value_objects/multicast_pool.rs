@@ -0,0 +1,111 @@
+use std::net::Ipv4Addr;
+use std::str::FromStr;
+
+use super::PortRange;
+use crate::domain::errors::{DomainError, Result};
+
+/// Multicast groups and ports handed out to clients requesting UDP multicast
+#[derive(Debug, Clone, PartialEq)]
+pub struct MulticastPool {
+    first_address: Ipv4Addr,
+    last_address: Ipv4Addr,
+    ports: PortRange,
+    ttl: u8,
+}
+
+impl MulticastPool {
+    pub fn new(first_address: Ipv4Addr, last_address: Ipv4Addr, ports: PortRange) -> Result<Self> {
+        if !first_address.is_multicast() || !last_address.is_multicast() {
+            return Err(DomainError::InvalidTransportConfig(format!(
+                "{}-{} is not a multicast range",
+                first_address, last_address
+            )));
+        }
+        if first_address > last_address {
+            return Err(DomainError::InvalidTransportConfig(format!(
+                "multicast range {}-{} is reversed",
+                first_address, last_address
+            )));
+        }
+
+        Ok(Self {
+            first_address,
+            last_address,
+            ports,
+            ttl: 16,
+        })
+    }
+
+    /// Parse an address range such as `239.255.0.1-239.255.0.254` or a single group
+    pub fn parse(addresses: &str, ports: PortRange) -> Result<Self> {
+        let parse_address = |address: &str| {
+            Ipv4Addr::from_str(address.trim()).map_err(|_| {
+                DomainError::InvalidTransportConfig(format!(
+                    "invalid multicast address {}",
+                    address
+                ))
+            })
+        };
+
+        let (first, last) = addresses.split_once('-').unwrap_or((addresses, addresses));
+        Self::new(parse_address(first)?, parse_address(last)?, ports)
+    }
+
+    pub fn with_ttl(mut self, ttl: u8) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn first_address(&self) -> Ipv4Addr {
+        self.first_address
+    }
+
+    pub fn last_address(&self) -> Ipv4Addr {
+        self.last_address
+    }
+
+    pub fn ports(&self) -> &PortRange {
+        &self.ports
+    }
+
+    pub fn ttl(&self) -> u8 {
+        self.ttl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ports() -> PortRange {
+        PortRange::new(5000, 5999).unwrap()
+    }
+
+    #[test]
+    fn test_parse_address_range() {
+        let pool = MulticastPool::parse("239.255.0.1-239.255.0.254", ports())
+            .unwrap()
+            .with_ttl(4);
+
+        assert_eq!(pool.first_address(), Ipv4Addr::new(239, 255, 0, 1));
+        assert_eq!(pool.last_address(), Ipv4Addr::new(239, 255, 0, 254));
+        assert_eq!(pool.ttl(), 4);
+    }
+
+    #[test]
+    fn test_single_group() {
+        let pool = MulticastPool::parse("239.1.1.1", ports()).unwrap();
+        assert_eq!(pool.first_address(), pool.last_address());
+        assert_eq!(pool.ttl(), 16);
+    }
+
+    #[test]
+    fn test_rejects_unicast_and_reversed_ranges() {
+        assert!(matches!(
+            MulticastPool::parse("10.0.0.1-10.0.0.9", ports()).unwrap_err(),
+            DomainError::InvalidTransportConfig(_)
+        ));
+        assert!(MulticastPool::parse("239.0.0.9-239.0.0.1", ports()).is_err());
+        assert!(MulticastPool::parse("not-an-address", ports()).is_err());
+    }
+}
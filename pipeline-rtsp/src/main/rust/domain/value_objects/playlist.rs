@@ -0,0 +1,168 @@
+use std::path::PathBuf;
+
+use super::MediaInfo;
+use crate::domain::errors::{DomainError, Result};
+
+/// One clip of a playlist, played `repeat` times in a row
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistItem {
+    path: PathBuf,
+    repeat: u32,
+    media_info: Option<MediaInfo>,
+}
+
+impl PlaylistItem {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            repeat: 1,
+            media_info: None,
+        }
+    }
+
+    pub fn with_repeat(mut self, repeat: u32) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn repeat(&self) -> u32 {
+        self.repeat
+    }
+
+    /// Properties detected by the last probe, if any
+    pub fn media_info(&self) -> Option<&MediaInfo> {
+        self.media_info.as_ref()
+    }
+}
+
+/// Ordered clips served back to back on one mount
+#[derive(Debug, Clone, PartialEq)]
+pub struct Playlist {
+    items: Vec<PlaylistItem>,
+    shuffle_seed: Option<u64>,
+}
+
+impl Playlist {
+    pub fn new(items: Vec<PlaylistItem>) -> Result<Self> {
+        if items.is_empty() {
+            return Err(DomainError::InvalidPlaylist(
+                "at least one item is required".to_string(),
+            ));
+        }
+        if let Some(item) = items.iter().find(|item| item.repeat == 0) {
+            return Err(DomainError::InvalidPlaylist(format!(
+                "{} repeats 0 times",
+                item.path.display()
+            )));
+        }
+
+        Ok(Self {
+            items,
+            shuffle_seed: None,
+        })
+    }
+
+    /// Shuffle the items, keeping each item's repeats together
+    pub fn with_shuffle_seed(mut self, seed: u64) -> Self {
+        self.shuffle_seed = Some(seed);
+        self
+    }
+
+    /// Attach probe results, one per item in configured order
+    pub fn with_media_info(mut self, infos: Vec<Option<MediaInfo>>) -> Self {
+        for (item, info) in self.items.iter_mut().zip(infos) {
+            item.media_info = info;
+        }
+        self
+    }
+
+    pub fn items(&self) -> &[PlaylistItem] {
+        &self.items
+    }
+
+    pub fn is_shuffled(&self) -> bool {
+        self.shuffle_seed.is_some()
+    }
+
+    /// Items in the order they are played, with repeats expanded
+    pub fn play_order(&self) -> Vec<&PlaylistItem> {
+        let mut order: Vec<&PlaylistItem> = self.items.iter().collect();
+        if let Some(seed) = self.shuffle_seed {
+            // Fisher-Yates driven by xorshift64; the state must never be zero
+            let mut state = seed.max(1);
+            for i in (1..order.len()).rev() {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                order.swap(i, (state % (i as u64 + 1)) as usize);
+            }
+        }
+
+        order
+            .into_iter()
+            .flat_map(|item| std::iter::repeat_n(item, item.repeat as usize))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> Vec<PlaylistItem> {
+        ["day.mp4", "dusk.mp4", "night.mp4", "dawn.mp4"]
+            .into_iter()
+            .map(|name| PlaylistItem::new(PathBuf::from(name)))
+            .collect()
+    }
+
+    fn names(order: Vec<&PlaylistItem>) -> Vec<String> {
+        order
+            .iter()
+            .map(|item| item.path().display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_rejects_empty_playlist() {
+        assert!(matches!(
+            Playlist::new(vec![]).unwrap_err(),
+            DomainError::InvalidPlaylist(_)
+        ));
+    }
+
+    #[test]
+    fn test_rejects_zero_repeat() {
+        let items = vec![PlaylistItem::new(PathBuf::from("day.mp4")).with_repeat(0)];
+        assert!(Playlist::new(items).is_err());
+    }
+
+    #[test]
+    fn test_play_order_expands_repeats() {
+        let mut items = items();
+        items[1] = items[1].clone().with_repeat(2);
+        let playlist = Playlist::new(items).unwrap();
+
+        assert_eq!(
+            names(playlist.play_order()),
+            vec!["day.mp4", "dusk.mp4", "dusk.mp4", "night.mp4", "dawn.mp4"]
+        );
+    }
+
+    #[test]
+    fn test_shuffle_is_a_seeded_permutation() {
+        let playlist = Playlist::new(items()).unwrap().with_shuffle_seed(42);
+        assert!(playlist.is_shuffled());
+
+        let order = names(playlist.play_order());
+        assert_eq!(order, names(playlist.play_order()));
+
+        let mut sorted = order.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec!["dawn.mp4", "day.mp4", "dusk.mp4", "night.mp4"]);
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::domain::errors::{DomainError, Result};
+
+/// Inclusive range of UDP ports, allocated in RTP/RTCP pairs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortRange {
+    first: u16,
+    last: u16,
+}
+
+impl PortRange {
+    pub fn new(first: u16, last: u16) -> Result<Self> {
+        if first == 0 {
+            return Err(DomainError::InvalidTransportConfig(
+                "port range cannot start at 0".to_string(),
+            ));
+        }
+        // RTP needs an even port with RTCP on the next one
+        let first_even = u32::from(first) + u32::from(first % 2);
+        if first_even + 1 > u32::from(last) {
+            return Err(DomainError::InvalidTransportConfig(format!(
+                "port range {}-{} must hold at least one RTP/RTCP pair",
+                first, last
+            )));
+        }
+        Ok(Self { first, last })
+    }
+
+    pub fn first(&self) -> u16 {
+        self.first
+    }
+
+    pub fn last(&self) -> u16 {
+        self.last
+    }
+}
+
+impl fmt::Display for PortRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.first, self.last)
+    }
+}
+
+impl FromStr for PortRange {
+    type Err = DomainError;
+
+    /// Parse `first-last`, e.g. `5000-5999`
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || DomainError::InvalidTransportConfig(format!("invalid port range {}", s));
+        let (first, last) = s.split_once('-').ok_or_else(invalid)?;
+        let first = first.trim().parse().map_err(|_| invalid())?;
+        let last = last.trim().parse().map_err(|_| invalid())?;
+        Self::new(first, last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_port_range() {
+        let range: PortRange = "5000-5999".parse().unwrap();
+        assert_eq!(range.first(), 5000);
+        assert_eq!(range.last(), 5999);
+        assert_eq!(range.to_string(), "5000-5999");
+    }
+
+    #[test]
+    fn test_rejects_invalid_ranges() {
+        assert!("5000".parse::<PortRange>().is_err());
+        assert!("6000-5000".parse::<PortRange>().is_err());
+        assert!("5000-5000".parse::<PortRange>().is_err());
+        assert!("5001-5002".parse::<PortRange>().is_err());
+        assert!("5001-5003".parse::<PortRange>().is_ok());
+        assert!("65535-65535".parse::<PortRange>().is_err());
+        assert!("0-100".parse::<PortRange>().is_err());
+        assert!("a-b".parse::<PortRange>().is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::domain::errors::{DomainError, Result};
+
+/// Placeholder in directory templates replaced by the mount name
+pub const MOUNT_PLACEHOLDER: &str = "{mount}";
+
+/// Segmented MP4 copy of what a mount serves
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordingConfig {
+    directory: PathBuf,
+    file_prefix: String,
+    segment_duration: Duration,
+    max_segments: Option<u32>,
+}
+
+impl RecordingConfig {
+    /// Record into `directory_template`, with `{mount}` replaced by the mount point minus its leading slash
+    pub fn new(directory_template: &str, mount_point: &str) -> Result<Self> {
+        if directory_template.trim().is_empty() {
+            return Err(DomainError::InvalidRecording(
+                "recording directory cannot be empty".to_string(),
+            ));
+        }
+        let mount_name = mount_point.trim_start_matches('/');
+        Ok(Self {
+            directory: PathBuf::from(directory_template.replace(MOUNT_PLACEHOLDER, mount_name)),
+            // Mount names cannot contain dots, so no two mounts share a prefix
+            file_prefix: mount_name.replace('/', "."),
+            segment_duration: Duration::from_secs(60),
+            max_segments: None,
+        })
+    }
+
+    /// Start a new file after this much media
+    pub fn with_segment_duration(mut self, duration: Duration) -> Self {
+        self.segment_duration = duration;
+        self
+    }
+
+    /// Delete the oldest files once more than `count` exist
+    pub fn with_max_segments(mut self, count: u32) -> Self {
+        self.max_segments = Some(count);
+        self
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Start of the mount's file names, keeping mounts that share a directory apart
+    pub fn file_prefix(&self) -> &str {
+        &self.file_prefix
+    }
+
+    pub fn segment_duration(&self) -> Duration {
+        self.segment_duration
+    }
+
+    /// Files kept on disk (default: all)
+    pub fn max_segments(&self) -> Option<u32> {
+        self.max_segments
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.segment_duration < Duration::from_secs(1) {
+            return Err(DomainError::InvalidRecording(format!(
+                "segment duration {:?} is shorter than a second",
+                self.segment_duration
+            )));
+        }
+        if self.max_segments == Some(0) {
+            return Err(DomainError::InvalidRecording(
+                "max segments must keep at least one file".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_uses_mount_name() {
+        let config = RecordingConfig::new("/recordings/{mount}", "/clips/lobby").unwrap();
+        assert_eq!(config.directory(), Path::new("/recordings/clips/lobby"));
+        assert_eq!(config.file_prefix(), "clips.lobby");
+
+        let fixed = RecordingConfig::new("/recordings", "/cam1").unwrap();
+        assert_eq!(fixed.directory(), Path::new("/recordings"));
+    }
+
+    #[test]
+    fn test_validation() {
+        let config = RecordingConfig::new("/recordings/{mount}", "/cam1").unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.segment_duration(), Duration::from_secs(60));
+        assert!(config
+            .clone()
+            .with_segment_duration(Duration::from_millis(500))
+            .validate()
+            .is_err());
+        assert!(matches!(
+            config.with_max_segments(0).validate().unwrap_err(),
+            DomainError::InvalidRecording(_)
+        ));
+        assert!(RecordingConfig::new(" ", "/cam1").is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use std::str::FromStr;
+
+use crate::domain::errors::DomainError;
+
+/// RTP transport profile offered to clients during SETUP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RtpProfile {
+    Avp,
+    Savp,
+    Avpf,
+    Savpf,
+}
+
+impl RtpProfile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RtpProfile::Avp => "RTP/AVP",
+            RtpProfile::Savp => "RTP/SAVP",
+            RtpProfile::Avpf => "RTP/AVPF",
+            RtpProfile::Savpf => "RTP/SAVPF",
+        }
+    }
+
+    /// Whether media is encrypted with SRTP
+    pub fn is_secure(&self) -> bool {
+        matches!(self, RtpProfile::Savp | RtpProfile::Savpf)
+    }
+}
+
+impl Default for RtpProfile {
+    fn default() -> Self {
+        RtpProfile::Avp
+    }
+}
+
+impl FromStr for RtpProfile {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase();
+        match name.strip_prefix("rtp/").unwrap_or(&name) {
+            "avp" => Ok(RtpProfile::Avp),
+            "savp" => Ok(RtpProfile::Savp),
+            "avpf" => Ok(RtpProfile::Avpf),
+            "savpf" => Ok(RtpProfile::Savpf),
+            _ => Err(DomainError::InvalidRtpProfile(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profiles() {
+        assert_eq!("avp".parse::<RtpProfile>().unwrap(), RtpProfile::Avp);
+        assert_eq!("RTP/SAVP".parse::<RtpProfile>().unwrap(), RtpProfile::Savp);
+        assert_eq!("savpf".parse::<RtpProfile>().unwrap(), RtpProfile::Savpf);
+        assert!(matches!(
+            "srtp".parse::<RtpProfile>().unwrap_err(),
+            DomainError::InvalidRtpProfile(_)
+        ));
+    }
+
+    #[test]
+    fn test_secure_profiles() {
+        assert!(!RtpProfile::Avp.is_secure());
+        assert!(!RtpProfile::Avpf.is_secure());
+        assert!(RtpProfile::Savp.is_secure());
+        assert!(RtpProfile::Savpf.is_secure());
+    }
+}
@@ -0,0 +1,292 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::domain::errors::{DomainError, Result};
+
+const MINUTES_PER_DAY: u16 = 24 * 60;
+
+/// Wall-clock time with minute precision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimeOfDay {
+    minutes: u16,
+}
+
+impl TimeOfDay {
+    pub fn new(hour: u16, minute: u16) -> Result<Self> {
+        if hour >= 24 || minute >= 60 {
+            return Err(DomainError::InvalidSchedule(format!(
+                "{:02}:{:02} is not a time of day",
+                hour, minute
+            )));
+        }
+        Ok(Self {
+            minutes: hour * 60 + minute,
+        })
+    }
+
+    /// Time of day at a Unix timestamp, shifted into a timezone `utc_offset_minutes` from UTC
+    pub fn from_unix_secs(secs: i64, utc_offset_minutes: i32) -> Self {
+        let minutes = (secs.div_euclid(60) + i64::from(utc_offset_minutes))
+            .rem_euclid(i64::from(MINUTES_PER_DAY));
+        Self {
+            minutes: minutes as u16,
+        }
+    }
+
+    pub fn hour(&self) -> u16 {
+        self.minutes / 60
+    }
+
+    pub fn minute(&self) -> u16 {
+        self.minutes % 60
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour(), self.minute())
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = DomainError;
+
+    /// Parse `HH:MM` in 24-hour format
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || DomainError::InvalidSchedule(format!("invalid time {}", s));
+        let (hour, minute) = s.trim().split_once(':').ok_or_else(invalid)?;
+        Self::new(
+            hour.parse().map_err(|_| invalid())?,
+            minute.parse().map_err(|_| invalid())?,
+        )
+    }
+}
+
+/// Daily window `[start, end)` playing one file; windows may wrap past midnight
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleWindow {
+    start: TimeOfDay,
+    end: TimeOfDay,
+    source_path: PathBuf,
+}
+
+impl ScheduleWindow {
+    pub fn new(start: TimeOfDay, end: TimeOfDay, source_path: PathBuf) -> Result<Self> {
+        if start == end {
+            return Err(DomainError::InvalidSchedule(format!(
+                "window {}-{} is empty",
+                start, end
+            )));
+        }
+        Ok(Self {
+            start,
+            end,
+            source_path,
+        })
+    }
+
+    pub fn start(&self) -> TimeOfDay {
+        self.start
+    }
+
+    pub fn end(&self) -> TimeOfDay {
+        self.end
+    }
+
+    pub fn source_path(&self) -> &Path {
+        &self.source_path
+    }
+
+    pub fn contains(&self, time: TimeOfDay) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+impl fmt::Display for ScheduleWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}={}",
+            self.start,
+            self.end,
+            self.source_path.display()
+        )
+    }
+}
+
+impl FromStr for ScheduleWindow {
+    type Err = DomainError;
+
+    /// Parse `HH:MM-HH:MM=/path/to/file`
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid =
+            || DomainError::InvalidSchedule(format!("expected HH:MM-HH:MM=path, got {}", s));
+        let (window, path) = s.split_once('=').ok_or_else(invalid)?;
+        let (start, end) = window.split_once('-').ok_or_else(invalid)?;
+        if path.trim().is_empty() {
+            return Err(invalid());
+        }
+        Self::new(start.parse()?, end.parse()?, PathBuf::from(path.trim()))
+    }
+}
+
+/// Time-of-day programming of a mount
+///
+/// Outside every window the mount plays its configured source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    windows: Vec<ScheduleWindow>,
+    utc_offset_minutes: i32,
+}
+
+impl Schedule {
+    pub fn new(windows: Vec<ScheduleWindow>) -> Result<Self> {
+        if windows.is_empty() {
+            return Err(DomainError::InvalidSchedule(
+                "at least one window is required".to_string(),
+            ));
+        }
+
+        // Two daily windows overlap exactly when one starts inside the other
+        for (index, window) in windows.iter().enumerate() {
+            if let Some(other) = windows[index + 1..]
+                .iter()
+                .find(|other| other.contains(window.start) || window.contains(other.start))
+            {
+                return Err(DomainError::InvalidSchedule(format!(
+                    "windows {}-{} and {}-{} overlap",
+                    window.start, window.end, other.start, other.end
+                )));
+            }
+        }
+
+        Ok(Self {
+            windows,
+            utc_offset_minutes: 0,
+        })
+    }
+
+    /// Evaluate windows in a timezone this many minutes ahead of UTC
+    pub fn with_utc_offset_minutes(mut self, minutes: i32) -> Self {
+        self.utc_offset_minutes = minutes;
+        self
+    }
+
+    pub fn windows(&self) -> &[ScheduleWindow] {
+        &self.windows
+    }
+
+    pub fn utc_offset_minutes(&self) -> i32 {
+        self.utc_offset_minutes
+    }
+
+    /// File of the window covering `time`, if any
+    pub fn source_at(&self, time: TimeOfDay) -> Option<&Path> {
+        self.windows
+            .iter()
+            .find(|window| window.contains(time))
+            .map(ScheduleWindow::source_path)
+    }
+
+    /// Current wall-clock time in the schedule's timezone
+    pub fn now(&self) -> TimeOfDay {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() as i64);
+        TimeOfDay::from_unix_secs(secs, self.utc_offset_minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> TimeOfDay {
+        s.parse().unwrap()
+    }
+
+    fn day_and_night() -> Schedule {
+        Schedule::new(vec![
+            "06:00-18:00=/videos/day.mp4".parse().unwrap(),
+            "22:00-04:30=/videos/night.mp4".parse().unwrap(),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_time_of_day() {
+        assert_eq!(at("07:05").to_string(), "07:05");
+        assert!("24:00".parse::<TimeOfDay>().is_err());
+        assert!("7".parse::<TimeOfDay>().is_err());
+        assert!("07:60".parse::<TimeOfDay>().is_err());
+    }
+
+    #[test]
+    fn test_time_of_day_from_unix_secs() {
+        // 1970-01-01 23:30 UTC
+        let secs = 23 * 3600 + 30 * 60;
+        assert_eq!(TimeOfDay::from_unix_secs(secs, 0), at("23:30"));
+        assert_eq!(TimeOfDay::from_unix_secs(secs, 60), at("00:30"));
+        assert_eq!(TimeOfDay::from_unix_secs(secs, -120), at("21:30"));
+    }
+
+    #[test]
+    fn test_source_at_wraps_midnight() {
+        let schedule = day_and_night();
+
+        assert_eq!(
+            schedule.source_at(at("06:00")),
+            Some(Path::new("/videos/day.mp4"))
+        );
+        assert_eq!(
+            schedule.source_at(at("23:59")),
+            Some(Path::new("/videos/night.mp4"))
+        );
+        assert_eq!(
+            schedule.source_at(at("04:29")),
+            Some(Path::new("/videos/night.mp4"))
+        );
+        assert_eq!(schedule.source_at(at("18:00")), None);
+        assert_eq!(schedule.source_at(at("05:00")), None);
+    }
+
+    #[test]
+    fn test_window_round_trips_through_display() {
+        let window: ScheduleWindow = "22:00-04:30=/videos/night.mp4".parse().unwrap();
+        assert_eq!(window.to_string(), "22:00-04:30=/videos/night.mp4");
+    }
+
+    #[test]
+    fn test_rejects_invalid_windows() {
+        assert!("06:00-06:00=/videos/day.mp4"
+            .parse::<ScheduleWindow>()
+            .is_err());
+        assert!("06:00-18:00".parse::<ScheduleWindow>().is_err());
+        assert!("06:00=/videos/day.mp4".parse::<ScheduleWindow>().is_err());
+        assert!(Schedule::new(vec![]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_overlapping_windows() {
+        let result = Schedule::new(vec![
+            "06:00-18:00=/videos/day.mp4".parse().unwrap(),
+            "17:00-05:00=/videos/night.mp4".parse().unwrap(),
+        ]);
+        assert!(matches!(
+            result.unwrap_err(),
+            DomainError::InvalidSchedule(_)
+        ));
+
+        let wrapping = Schedule::new(vec![
+            "22:00-06:00=/videos/night.mp4".parse().unwrap(),
+            "01:00-02:00=/videos/late.mp4".parse().unwrap(),
+        ]);
+        assert!(wrapping.is_err());
+    }
+}
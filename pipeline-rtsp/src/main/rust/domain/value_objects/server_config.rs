@@ -1,3 +1,4 @@
+use super::{HeaderExtension, MulticastPool, PortRange, RtpProfile, SharingMode, TransportPolicy};
 use crate::domain::errors::{DomainError, Result};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -5,6 +6,15 @@ pub struct ServerConfig {
     port: u16,
     mount_point: String,
     enable_looping: bool,
+    allowed_users: Option<Vec<String>>,
+    rtp_profiles: Vec<RtpProfile>,
+    multicast: Option<MulticastPool>,
+    transport_policy: TransportPolicy,
+    udp_ports: Option<PortRange>,
+    sharing_mode: SharingMode,
+    max_loops: Option<u32>,
+    live: bool,
+    header_extension: Option<HeaderExtension>,
 }
 
 impl ServerConfig {
@@ -16,6 +26,15 @@ impl ServerConfig {
             port,
             mount_point,
             enable_looping: true,
+            allowed_users: None,
+            rtp_profiles: vec![RtpProfile::Avp],
+            multicast: None,
+            transport_policy: TransportPolicy::Both,
+            udp_ports: None,
+            sharing_mode: SharingMode::Shared,
+            max_loops: None,
+            live: false,
+            header_extension: None,
         })
     }
 
@@ -24,6 +43,65 @@ impl ServerConfig {
         self
     }
 
+    /// Restrict the mount to these users when authentication is enabled
+    pub fn with_allowed_users(mut self, users: Vec<String>) -> Self {
+        self.allowed_users = Some(users);
+        self
+    }
+
+    /// RTP profiles clients may negotiate; SAVP/SAVPF enable SRTP keyed via MIKEY
+    pub fn with_rtp_profiles(mut self, profiles: Vec<RtpProfile>) -> Result<Self> {
+        if profiles.is_empty() {
+            return Err(DomainError::InvalidRtpProfile(
+                "at least one profile is required".to_string(),
+            ));
+        }
+        self.rtp_profiles = profiles;
+        Ok(self)
+    }
+
+    /// Offer UDP multicast from this pool; all multicast clients share one media
+    pub fn with_multicast(mut self, pool: MulticastPool) -> Self {
+        self.multicast = Some(pool);
+        self
+    }
+
+    /// Lower transports clients may use for this mount
+    pub fn with_transport_policy(mut self, policy: TransportPolicy) -> Self {
+        self.transport_policy = policy;
+        self
+    }
+
+    /// Server-side ports for unicast UDP instead of arbitrary ephemeral ones
+    pub fn with_udp_ports(mut self, ports: PortRange) -> Self {
+        self.udp_ports = Some(ports);
+        self
+    }
+
+    /// Give each client its own seekable media instead of one shared timeline
+    pub fn with_sharing_mode(mut self, mode: SharingMode) -> Self {
+        self.sharing_mode = mode;
+        self
+    }
+
+    /// End the stream after the file has been restarted this many times
+    pub fn with_max_loops(mut self, max_loops: u32) -> Self {
+        self.max_loops = Some(max_loops);
+        self
+    }
+
+    /// Clock the media against the system's wall clock, like a real camera
+    pub fn with_live(mut self, live: bool) -> Self {
+        self.live = live;
+        self
+    }
+
+    /// Stamp every RTP packet with its absolute capture time
+    pub fn with_header_extension(mut self, extension: HeaderExtension) -> Self {
+        self.header_extension = Some(extension);
+        self
+    }
+
     pub fn port(&self) -> u16 {
         self.port
     }
@@ -32,8 +110,98 @@ impl ServerConfig {
         &self.mount_point
     }
 
+    /// Only shared media loops; per-client playback ends at the end of the file
     pub fn looping_enabled(&self) -> bool {
-        self.enable_looping
+        self.enable_looping && self.sharing_mode.is_shared()
+    }
+
+    pub fn max_loops(&self) -> Option<u32> {
+        self.max_loops
+    }
+
+    /// Whether the file should restart after `completed` earlier restarts
+    pub fn continue_looping(&self, completed: u32) -> bool {
+        self.looping_enabled() && self.max_loops.is_none_or(|max| completed < max)
+    }
+
+    pub fn allowed_users(&self) -> Option<&[String]> {
+        self.allowed_users.as_deref()
+    }
+
+    pub fn rtp_profiles(&self) -> &[RtpProfile] {
+        &self.rtp_profiles
+    }
+
+    /// Whether any offered profile encrypts media
+    pub fn srtp_enabled(&self) -> bool {
+        self.rtp_profiles.iter().any(RtpProfile::is_secure)
+    }
+
+    pub fn multicast(&self) -> Option<&MulticastPool> {
+        self.multicast.as_ref()
+    }
+
+    pub fn transport_policy(&self) -> TransportPolicy {
+        self.transport_policy
+    }
+
+    pub fn udp_ports(&self) -> Option<&PortRange> {
+        self.udp_ports.as_ref()
+    }
+
+    pub fn sharing_mode(&self) -> SharingMode {
+        self.sharing_mode
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.live
+    }
+
+    pub fn header_extension(&self) -> Option<HeaderExtension> {
+        self.header_extension
+    }
+
+    /// Wall-clock time only means something on one shared timeline
+    pub fn validate_live(&self) -> Result<()> {
+        if self.header_extension.is_some() && !self.live {
+            return Err(DomainError::InvalidLiveConfig(format!(
+                "mount {} needs live mode for RTP header extensions",
+                self.mount_point
+            )));
+        }
+        if self.live && !self.sharing_mode.is_shared() {
+            return Err(DomainError::InvalidLiveConfig(format!(
+                "mount {} cannot be live with per-client media",
+                self.mount_point
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reject UDP settings on a mount that only allows TCP, and multicast without shared media
+    pub fn validate_transport(&self) -> Result<()> {
+        if self.multicast.is_some() && !self.sharing_mode.is_shared() {
+            return Err(DomainError::InvalidTransportConfig(format!(
+                "mount {} needs shared media to serve multicast",
+                self.mount_point
+            )));
+        }
+        if self.transport_policy.allows_udp() {
+            return Ok(());
+        }
+        if self.multicast.is_some() {
+            return Err(DomainError::InvalidTransportConfig(format!(
+                "mount {} is TCP-only but has a multicast pool",
+                self.mount_point
+            )));
+        }
+        if self.udp_ports.is_some() {
+            return Err(DomainError::InvalidTransportConfig(format!(
+                "mount {} is TCP-only but has a UDP port range",
+                self.mount_point
+            )));
+        }
+        Ok(())
     }
 
     fn validate_port(port: u16) -> Result<()> {
@@ -50,6 +218,16 @@ impl ServerConfig {
         if mount_point.len() > 100 {
             return Err(DomainError::MountPointTooLong);
         }
+
+        // Mounts can be added at runtime, so enforce the same rules as the CLI
+        let valid_chars = mount_point
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '/' || c == '-' || c == '_');
+        let malformed =
+            mount_point.contains("//") || (mount_point.len() > 1 && mount_point.ends_with('/'));
+        if !valid_chars || malformed {
+            return Err(DomainError::InvalidMountPoint(mount_point.to_string()));
+        }
         Ok(())
     }
 }
@@ -83,6 +261,17 @@ mod tests {
         assert!(matches!(result.unwrap_err(), DomainError::MountPointTooLong));
     }
 
+    #[test]
+    fn test_rejects_malformed_mount_point() {
+        for mount in ["/cam 1", "/cam1/", "/cams//cam1", "/cam?1"] {
+            let result = ServerConfig::new(8554, mount.to_string());
+            assert!(matches!(
+                result.unwrap_err(),
+                DomainError::InvalidMountPoint(_)
+            ));
+        }
+    }
+
     #[test]
     fn test_accepts_valid_config() {
         let result = ServerConfig::new(8554, "/cam1".to_string());
@@ -102,4 +291,91 @@ mod tests {
 
         assert!(!config.looping_enabled());
     }
+
+    #[test]
+    fn test_rtp_profiles() {
+        let config = ServerConfig::new(8554, "/cam1".to_string()).unwrap();
+        assert_eq!(config.rtp_profiles(), &[RtpProfile::Avp]);
+        assert!(!config.srtp_enabled());
+
+        let secure = config
+            .clone()
+            .with_rtp_profiles(vec![RtpProfile::Savp, RtpProfile::Savpf])
+            .unwrap();
+        assert!(secure.srtp_enabled());
+
+        assert!(matches!(
+            config.with_rtp_profiles(vec![]).unwrap_err(),
+            DomainError::InvalidRtpProfile(_)
+        ));
+    }
+
+    #[test]
+    fn test_tcp_only_rejects_udp_settings() {
+        let config = ServerConfig::new(8554, "/cam1".to_string())
+            .unwrap()
+            .with_udp_ports(PortRange::new(20000, 20099).unwrap());
+        assert_eq!(config.transport_policy(), TransportPolicy::Both);
+        assert!(config.validate_transport().is_ok());
+        assert!(config
+            .clone()
+            .with_transport_policy(TransportPolicy::UdpOnly)
+            .validate_transport()
+            .is_ok());
+
+        let tcp_only = config.with_transport_policy(TransportPolicy::TcpOnly);
+        assert!(matches!(
+            tcp_only.validate_transport().unwrap_err(),
+            DomainError::InvalidTransportConfig(_)
+        ));
+    }
+
+    #[test]
+    fn test_per_client_mode_disables_looping() {
+        let config = ServerConfig::new(8554, "/vod".to_string()).unwrap();
+        assert_eq!(config.sharing_mode(), SharingMode::Shared);
+
+        let vod = config.with_sharing_mode(SharingMode::PerClient);
+        assert!(!vod.looping_enabled());
+
+        let ports = PortRange::new(5000, 5099).unwrap();
+        let multicast = vod.with_multicast(MulticastPool::parse("239.1.1.1", ports).unwrap());
+        assert!(matches!(
+            multicast.validate_transport().unwrap_err(),
+            DomainError::InvalidTransportConfig(_)
+        ));
+    }
+
+    #[test]
+    fn test_max_loops() {
+        let config = ServerConfig::new(8554, "/cam1".to_string()).unwrap();
+        assert!(config.continue_looping(1_000));
+
+        let limited = config.with_max_loops(2);
+        assert_eq!(limited.max_loops(), Some(2));
+        assert!(limited.continue_looping(0));
+        assert!(limited.continue_looping(1));
+        assert!(!limited.continue_looping(2));
+        assert!(!limited.with_looping(false).continue_looping(0));
+    }
+
+    #[test]
+    fn test_live_mode_requirements() {
+        let config = ServerConfig::new(8554, "/live".to_string()).unwrap();
+        assert!(!config.is_live());
+
+        let extension_only = config
+            .clone()
+            .with_header_extension(HeaderExtension::OnvifReplay);
+        assert!(matches!(
+            extension_only.validate_live().unwrap_err(),
+            DomainError::InvalidLiveConfig(_)
+        ));
+        assert!(extension_only.with_live(true).validate_live().is_ok());
+
+        let per_client = config
+            .with_live(true)
+            .with_sharing_mode(SharingMode::PerClient);
+        assert!(per_client.validate_live().is_err());
+    }
 }
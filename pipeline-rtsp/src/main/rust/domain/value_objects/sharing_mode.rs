@@ -0,0 +1,68 @@
+use std::str::FromStr;
+
+use crate::domain::errors::DomainError;
+
+/// Whether clients of a mount share one media pipeline or each get their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SharingMode {
+    /// One timeline for every client, like a live camera
+    Shared,
+    /// Video on demand: each client plays from the start and may seek with `Range`
+    PerClient,
+}
+
+impl SharingMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SharingMode::Shared => "shared",
+            SharingMode::PerClient => "per-client",
+        }
+    }
+
+    pub fn is_shared(&self) -> bool {
+        matches!(self, SharingMode::Shared)
+    }
+}
+
+impl Default for SharingMode {
+    fn default() -> Self {
+        SharingMode::Shared
+    }
+}
+
+impl FromStr for SharingMode {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "shared" => Ok(SharingMode::Shared),
+            "per-client" | "per_client" | "vod" => Ok(SharingMode::PerClient),
+            _ => Err(DomainError::InvalidSharingMode(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sharing_modes() {
+        assert_eq!(
+            "shared".parse::<SharingMode>().unwrap(),
+            SharingMode::Shared
+        );
+        assert_eq!(
+            "VOD".parse::<SharingMode>().unwrap(),
+            SharingMode::PerClient
+        );
+        assert_eq!(
+            "per-client".parse::<SharingMode>().unwrap(),
+            SharingMode::PerClient
+        );
+        assert!(matches!(
+            "exclusive".parse::<SharingMode>().unwrap_err(),
+            DomainError::InvalidSharingMode(_)
+        ));
+    }
+}
@@ -0,0 +1,55 @@
+use std::str::FromStr;
+
+use crate::domain::errors::DomainError;
+
+/// Where a mount's video comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    File,
+    TestPattern,
+}
+
+impl SourceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SourceKind::File => "file",
+            SourceKind::TestPattern => "test-pattern",
+        }
+    }
+}
+
+impl Default for SourceKind {
+    fn default() -> Self {
+        SourceKind::File
+    }
+}
+
+impl FromStr for SourceKind {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "file" => Ok(SourceKind::File),
+            "test-pattern" | "test_pattern" | "testsrc" => Ok(SourceKind::TestPattern),
+            _ => Err(DomainError::InvalidTestPattern(format!(
+                "unknown source kind {}",
+                s
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_source_kinds() {
+        assert_eq!("file".parse::<SourceKind>().unwrap(), SourceKind::File);
+        assert_eq!(
+            "Test-Pattern".parse::<SourceKind>().unwrap(),
+            SourceKind::TestPattern
+        );
+        assert!("camera".parse::<SourceKind>().is_err());
+    }
+}
@@ -1,14 +1,29 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use super::{ContainerFormat, VideoCodec};
+use super::{
+    AudioCodec, ContainerFormat, MediaInfo, Playlist, RecordingConfig, Schedule, SourceKind,
+    TestPattern, TranscodeProfile, VideoCodec,
+};
 use crate::domain::errors::{DomainError, Result};
+use crate::domain::ports::MediaProbe;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct StreamConfig {
     source_path: PathBuf,
     codec: VideoCodec,
     container: ContainerFormat,
-    rtp_payload_type: u8,
+    rtp_payload_type: Option<u8>,
+    audio_codec: Option<AudioCodec>,
+    audio_rtp_payload_type: u8,
+    transcode: Option<TranscodeProfile>,
+    playlist: Option<Playlist>,
+    schedule: Option<Schedule>,
+    test_pattern: Option<TestPattern>,
+    recording: Option<RecordingConfig>,
+    // Explicitly configured values must match the probed file
+    codec_pinned: bool,
+    container_pinned: bool,
+    media_info: Option<MediaInfo>,
 }
 
 impl StreamConfig {
@@ -17,22 +32,78 @@ impl StreamConfig {
             source_path,
             codec: VideoCodec::default(),
             container: ContainerFormat::default(),
-            rtp_payload_type: 96,
+            rtp_payload_type: None,
+            audio_codec: None,
+            audio_rtp_payload_type: 97,
+            transcode: None,
+            playlist: None,
+            schedule: None,
+            test_pattern: None,
+            recording: None,
+            codec_pinned: false,
+            container_pinned: false,
+            media_info: None,
         }
     }
 
+    /// Serve the playlist's clips back to back instead of a single file
+    pub fn from_playlist(playlist: Playlist) -> Self {
+        let mut config = Self::new(playlist.items()[0].path().clone());
+        config.playlist = Some(playlist);
+        config
+    }
+
+    /// Serve generated video instead of a file; it is always encoded, by default to H.264
+    pub fn from_test_pattern(pattern: TestPattern) -> Self {
+        let mut config = Self::new(PathBuf::new());
+        config.test_pattern = Some(pattern);
+        config.transcode = Some(TranscodeProfile::new());
+        config
+    }
+
     pub fn with_codec(mut self, codec: VideoCodec) -> Self {
         self.codec = codec;
+        self.codec_pinned = true;
         self
     }
 
     pub fn with_container(mut self, container: ContainerFormat) -> Self {
         self.container = container;
+        self.container_pinned = true;
         self
     }
 
     pub fn with_rtp_payload_type(mut self, pt: u8) -> Self {
-        self.rtp_payload_type = pt;
+        self.rtp_payload_type = Some(pt);
+        self
+    }
+
+    /// Serve the file's audio track as a second RTP stream
+    pub fn with_audio_codec(mut self, codec: AudioCodec) -> Self {
+        self.audio_codec = Some(codec);
+        self
+    }
+
+    pub fn with_audio_rtp_payload_type(mut self, pt: u8) -> Self {
+        self.audio_rtp_payload_type = pt;
+        self
+    }
+
+    /// Re-encode the video instead of passing the source bitstream through
+    pub fn with_transcode(mut self, profile: TranscodeProfile) -> Self {
+        self.transcode = Some(profile);
+        self
+    }
+
+    /// Play other files during daily time windows; the source path fills the gaps
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    /// Keep a segmented MP4 copy of the served stream
+    pub fn with_recording(mut self, recording: RecordingConfig) -> Self {
+        self.recording = Some(recording);
         self
     }
 
@@ -48,30 +119,325 @@ impl StreamConfig {
         &self.container
     }
 
+    /// Configured payload type, or the output codec's default
     pub fn rtp_payload_type(&self) -> u8 {
         self.rtp_payload_type
+            .unwrap_or_else(|| self.output_codec().default_payload_type())
+    }
+
+    pub fn transcode(&self) -> Option<&TranscodeProfile> {
+        self.transcode.as_ref()
+    }
+
+    pub fn playlist(&self) -> Option<&Playlist> {
+        self.playlist.as_ref()
+    }
+
+    pub fn test_pattern(&self) -> Option<&TestPattern> {
+        self.test_pattern.as_ref()
+    }
+
+    pub fn source_kind(&self) -> SourceKind {
+        match self.test_pattern {
+            Some(_) => SourceKind::TestPattern,
+            None => SourceKind::File,
+        }
+    }
+
+    pub fn schedule(&self) -> Option<&Schedule> {
+        self.schedule.as_ref()
+    }
+
+    /// File the schedule selects right now, or the source path outside its windows
+    pub fn scheduled_source(&self) -> &Path {
+        self.schedule
+            .as_ref()
+            .and_then(|schedule| schedule.source_at(schedule.now()))
+            .unwrap_or(&self.source_path)
+    }
+
+    pub fn recording(&self) -> Option<&RecordingConfig> {
+        self.recording.as_ref()
+    }
+
+    /// Codec sent to clients: the transcode target, or the source codec
+    pub fn output_codec(&self) -> &VideoCodec {
+        self.transcode
+            .as_ref()
+            .map_or(&self.codec, |profile| profile.codec())
+    }
+
+    pub fn audio_codec(&self) -> Option<&AudioCodec> {
+        self.audio_codec.as_ref()
+    }
+
+    pub fn audio_rtp_payload_type(&self) -> u8 {
+        self.audio_rtp_payload_type
+    }
+
+    /// Properties detected by the last probe, if any
+    pub fn media_info(&self) -> Option<&MediaInfo> {
+        self.media_info.as_ref()
     }
 
     /// Pure validation logic (domain concern)
     pub fn validate(&self) -> Result<()> {
-        if !self.source_path.exists() {
-            return Err(DomainError::InvalidPath(self.source_path.clone()));
+        self.check_recording()?;
+        if let Some(pattern) = &self.test_pattern {
+            return self.validate_test_pattern(pattern);
+        }
+
+        match &self.playlist {
+            Some(playlist) => {
+                for item in playlist.items() {
+                    Self::validate_path(item.path())?;
+                }
+            }
+            None => Self::validate_path(&self.source_path)?,
+        }
+
+        if let Some(schedule) = &self.schedule {
+            if self.playlist.is_some() {
+                return Err(DomainError::InvalidSchedule(
+                    "a mount cannot have both a playlist and a schedule".to_string(),
+                ));
+            }
+            for window in schedule.windows() {
+                Self::validate_path(window.source_path())?;
+            }
         }
 
-        if !self.source_path.is_file() {
-            return Err(DomainError::PathNotFile(self.source_path.clone()));
+        if let Some(profile) = &self.transcode {
+            profile.validate()?;
+        }
+
+        if self.audio_codec.is_some() && !self.container.supports_audio() {
+            return Err(DomainError::UnsupportedContainer(format!(
+                "{} cannot carry audio",
+                self.container.as_str()
+            )));
+        }
+
+        // Without a configured codec, the probe reports what the file holds
+        if self.codec_pinned && !self.container.carries(self.codec) {
+            return Err(DomainError::UnsupportedCodec(format!(
+                "{} cannot carry {}",
+                self.container.as_str(),
+                self.codec.as_str()
+            )));
         }
 
         Ok(())
     }
+
+    /// Generated video has no file, audio or schedule to check
+    fn validate_test_pattern(&self, pattern: &TestPattern) -> Result<()> {
+        pattern.validate()?;
+        if let Some(profile) = &self.transcode {
+            profile.validate()?;
+        }
+        if self.audio_codec.is_some() {
+            return Err(DomainError::InvalidTestPattern(
+                "test patterns have no audio".to_string(),
+            ));
+        }
+        if self.schedule.is_some() {
+            return Err(DomainError::InvalidTestPattern(
+                "test patterns cannot be scheduled".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Validate and fill in codec, container and stream properties from the file itself
+    pub fn validate_with(self, probe: &dyn MediaProbe) -> Result<Self> {
+        self.validate()?;
+        if self.test_pattern.is_some() {
+            return Ok(self);
+        }
+        if self.playlist.is_some() {
+            let config = self.apply_playlist_info(probe)?;
+            config.check_recording()?;
+            return Ok(config);
+        }
+
+        let info = probe.probe(&self.source_path)?;
+        let config = self.apply_media_info(info)?;
+        config.check_schedule(probe)?;
+        config.check_recording()?;
+        Ok(config)
+    }
+
+    /// Recordings are split at keyframes found by the parser and muxed to MP4
+    fn check_recording(&self) -> Result<()> {
+        let Some(recording) = &self.recording else {
+            return Ok(());
+        };
+        recording.validate()?;
+        match self.output_codec() {
+            VideoCodec::H264 | VideoCodec::H265 => Ok(()),
+            codec => Err(DomainError::InvalidRecording(format!(
+                "{} cannot be recorded, only H.264 and H.265",
+                codec.as_str()
+            ))),
+        }
+    }
+
+    /// Scheduled files are swapped into the running pipeline, so they must match the mount
+    fn check_schedule(&self, probe: &dyn MediaProbe) -> Result<()> {
+        let (Some(schedule), Some(mount)) = (&self.schedule, &self.media_info) else {
+            return Ok(());
+        };
+
+        for window in schedule.windows() {
+            let info = probe.probe(window.source_path())?;
+            self.check_media_info(&info)?;
+            if info.container() != mount.container() {
+                return Err(DomainError::UnsupportedContainer(format!(
+                    "{} is {} but the mount plays {}",
+                    window.source_path().display(),
+                    info.container().as_str(),
+                    mount.container().as_str()
+                )));
+            }
+            if info.video_codec() != mount.video_codec() {
+                return Err(DomainError::UnsupportedCodec(format!(
+                    "{} contains {} but the mount plays {}",
+                    window.source_path().display(),
+                    codec_name(info.video_codec()),
+                    codec_name(mount.video_codec())
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Probe every clip; clips with different codecs can only be joined once re-encoded
+    fn apply_playlist_info(mut self, probe: &dyn MediaProbe) -> Result<Self> {
+        let Some(playlist) = self.playlist.take() else {
+            return Ok(self);
+        };
+
+        let mut infos = Vec::with_capacity(playlist.items().len());
+        for item in playlist.items() {
+            let info = probe.probe(item.path())?;
+            self.check_media_info(&info)?;
+            infos.push(Some(info));
+        }
+
+        let mut codecs = infos.iter().flatten().map(MediaInfo::video_codec);
+        let first_codec = codecs.next().flatten();
+        let mixed_codecs = codecs.any(|codec| codec != first_codec);
+        if mixed_codecs && self.transcode.is_none() {
+            self.transcode = Some(TranscodeProfile::new());
+        }
+
+        // The first clip describes the mount
+        if let Some(info) = infos.first().cloned().flatten() {
+            if let Some(codec) = info.video_codec() {
+                self.codec = *codec;
+            }
+            self.container = *info.container();
+            self.media_info = Some(info);
+        }
+        self.playlist = Some(playlist.with_media_info(infos));
+
+        Ok(self)
+    }
+
+    fn apply_media_info(mut self, info: MediaInfo) -> Result<Self> {
+        self.check_media_info(&info)?;
+
+        if let Some(codec) = info.video_codec() {
+            self.codec = *codec;
+        }
+        self.container = *info.container();
+        self.media_info = Some(info);
+
+        Ok(self)
+    }
+
+    /// Explicitly configured codec, container and audio must match what was probed
+    fn check_media_info(&self, info: &MediaInfo) -> Result<()> {
+        match info.video_codec() {
+            // Any decodable video will do when it gets re-encoded anyway
+            None if self.transcode.is_none() => {
+                return Err(DomainError::UnsupportedCodec(
+                    "the file's video codec can only be transcoded".to_string(),
+                ));
+            }
+            Some(codec) if self.codec_pinned && self.codec != *codec => {
+                return Err(DomainError::UnsupportedCodec(format!(
+                    "{} configured but file contains {}",
+                    self.codec.as_str(),
+                    codec.as_str()
+                )));
+            }
+            _ => {}
+        }
+
+        if self.container_pinned && self.container != *info.container() {
+            return Err(DomainError::UnsupportedContainer(format!(
+                "{} configured but file is {}",
+                self.container.as_str(),
+                info.container().as_str()
+            )));
+        }
+
+        if let Some(audio_codec) = &self.audio_codec {
+            if info.audio_codec() != Some(audio_codec) {
+                return Err(DomainError::UnsupportedCodec(format!(
+                    "{} audio configured but file has {}",
+                    audio_codec.as_str(),
+                    info.audio_codec()
+                        .map_or("no supported audio", |c| c.as_str())
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_path(path: &Path) -> Result<()> {
+        if !path.exists() {
+            return Err(DomainError::InvalidPath(path.to_path_buf()));
+        }
+        if !path.is_file() {
+            return Err(DomainError::PathNotFile(path.to_path_buf()));
+        }
+        Ok(())
+    }
+}
+
+/// Name of a probed codec for error messages
+fn codec_name(codec: Option<&VideoCodec>) -> &'static str {
+    codec.map_or("an unsupported codec", VideoCodec::as_str)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::value_objects::PlaylistItem;
     use std::io::Write;
+    use std::path::Path;
+    use std::time::Duration;
     use tempfile::NamedTempFile;
 
+    struct FixedProbe(MediaInfo);
+
+    impl MediaProbe for FixedProbe {
+        fn probe(&self, _path: &Path) -> Result<MediaInfo> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn temp_video() -> NamedTempFile {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "test content").unwrap();
+        temp_file
+    }
+
     #[test]
     fn test_default_config() {
         let config = StreamConfig::new(PathBuf::from("/test/video.mp4"));
@@ -79,6 +445,17 @@ mod tests {
         assert_eq!(*config.codec(), VideoCodec::H264);
         assert_eq!(*config.container(), ContainerFormat::MP4);
         assert_eq!(config.rtp_payload_type(), 96);
+        assert_eq!(config.audio_codec(), None);
+    }
+
+    #[test]
+    fn test_with_audio_codec() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.mp4"))
+            .with_audio_codec(AudioCodec::Opus)
+            .with_audio_rtp_payload_type(98);
+
+        assert_eq!(config.audio_codec(), Some(&AudioCodec::Opus));
+        assert_eq!(config.audio_rtp_payload_type(), 98);
     }
 
     #[test]
@@ -89,6 +466,16 @@ mod tests {
         assert_eq!(*config.codec(), VideoCodec::H265);
     }
 
+    #[test]
+    fn test_payload_type_follows_codec_unless_set() {
+        let config =
+            StreamConfig::new(PathBuf::from("/test/video.mjpeg")).with_codec(VideoCodec::MJPEG);
+        assert_eq!(config.rtp_payload_type(), 26);
+
+        let config = config.with_rtp_payload_type(100);
+        assert_eq!(config.rtp_payload_type(), 100);
+    }
+
     #[test]
     fn test_validate_nonexistent_path() {
         let config = StreamConfig::new(PathBuf::from("/nonexistent/video.mp4"));
@@ -108,4 +495,352 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_validate_with_fills_in_probed_values() {
+        let temp_file = temp_video();
+        let probe = FixedProbe(
+            MediaInfo::new(ContainerFormat::MKV, VideoCodec::H265)
+                .with_resolution(1920, 1080)
+                .with_framerate(25, 1)
+                .with_duration(Duration::from_secs(60)),
+        );
+
+        let config = StreamConfig::new(temp_file.path().to_path_buf())
+            .validate_with(&probe)
+            .unwrap();
+
+        assert_eq!(*config.codec(), VideoCodec::H265);
+        assert_eq!(*config.container(), ContainerFormat::MKV);
+        let info = config.media_info().unwrap();
+        assert_eq!(info.resolution(), Some((1920, 1080)));
+        assert_eq!(info.framerate(), Some(25.0));
+        assert_eq!(info.duration(), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_validate_with_rejects_codec_mismatch() {
+        let temp_file = temp_video();
+        let probe = FixedProbe(MediaInfo::new(ContainerFormat::MP4, VideoCodec::H265));
+
+        let result = StreamConfig::new(temp_file.path().to_path_buf())
+            .with_codec(VideoCodec::H264)
+            .validate_with(&probe);
+
+        assert!(matches!(
+            result.unwrap_err(),
+            DomainError::UnsupportedCodec(_)
+        ));
+    }
+
+    #[test]
+    fn test_validate_with_rejects_container_mismatch() {
+        let temp_file = temp_video();
+        let probe = FixedProbe(MediaInfo::new(ContainerFormat::MKV, VideoCodec::H264));
+
+        let result = StreamConfig::new(temp_file.path().to_path_buf())
+            .with_container(ContainerFormat::MP4)
+            .validate_with(&probe);
+
+        assert!(matches!(
+            result.unwrap_err(),
+            DomainError::UnsupportedContainer(_)
+        ));
+    }
+
+    #[test]
+    fn test_validate_with_rejects_missing_audio() {
+        let temp_file = temp_video();
+        let probe = FixedProbe(MediaInfo::new(ContainerFormat::MP4, VideoCodec::H264));
+
+        let result = StreamConfig::new(temp_file.path().to_path_buf())
+            .with_audio_codec(AudioCodec::AAC)
+            .validate_with(&probe);
+
+        assert!(matches!(
+            result.unwrap_err(),
+            DomainError::UnsupportedCodec(_)
+        ));
+    }
+
+    #[test]
+    fn test_rejects_audio_in_raw_elementary_stream() {
+        let temp_file = temp_video();
+
+        let result = StreamConfig::new(temp_file.path().to_path_buf())
+            .with_container(ContainerFormat::RawElementaryStream)
+            .with_audio_codec(AudioCodec::AAC)
+            .validate();
+
+        assert!(matches!(
+            result.unwrap_err(),
+            DomainError::UnsupportedContainer(_)
+        ));
+    }
+
+    #[test]
+    fn test_rejects_codec_the_container_cannot_carry() {
+        let temp_file = temp_video();
+        let config = |codec, container| {
+            StreamConfig::new(temp_file.path().to_path_buf())
+                .with_codec(codec)
+                .with_container(container)
+                .validate()
+        };
+
+        for (codec, container) in [
+            (VideoCodec::VP8, ContainerFormat::RawElementaryStream),
+            (VideoCodec::H264, ContainerFormat::WebM),
+            (VideoCodec::VP9, ContainerFormat::MpegTs),
+            (VideoCodec::H265, ContainerFormat::Flv),
+        ] {
+            assert!(matches!(
+                config(codec, container).unwrap_err(),
+                DomainError::UnsupportedCodec(_)
+            ));
+        }
+        assert!(config(VideoCodec::H265, ContainerFormat::RawElementaryStream).is_ok());
+        assert!(config(VideoCodec::H264, ContainerFormat::Flv).is_ok());
+        assert!(config(VideoCodec::VP9, ContainerFormat::WebM).is_ok());
+    }
+
+    #[test]
+    fn test_transcode_sets_output_codec_and_payload_type() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.mjpeg"))
+            .with_codec(VideoCodec::MJPEG)
+            .with_transcode(TranscodeProfile::new());
+
+        assert_eq!(*config.codec(), VideoCodec::MJPEG);
+        assert_eq!(*config.output_codec(), VideoCodec::H264);
+        assert_eq!(config.rtp_payload_type(), 96);
+    }
+
+    #[test]
+    fn test_validate_with_tolerates_unknown_codec_when_transcoding() {
+        let temp_file = temp_video();
+        let probe = FixedProbe(
+            MediaInfo::with_unsupported_codec(ContainerFormat::MKV, "video/x-theora")
+                .with_audio_codec(AudioCodec::Opus),
+        );
+
+        let config = StreamConfig::new(temp_file.path().to_path_buf());
+        assert!(matches!(
+            config.clone().validate_with(&probe).unwrap_err(),
+            DomainError::UnsupportedCodec(_)
+        ));
+
+        // The container and audio probed are kept, only the codec goes unchecked
+        let config = config
+            .with_audio_codec(AudioCodec::Opus)
+            .with_transcode(TranscodeProfile::new())
+            .validate_with(&probe)
+            .unwrap();
+        assert_eq!(*config.container(), ContainerFormat::MKV);
+        assert_eq!(
+            config.media_info().unwrap().audio_codec(),
+            Some(&AudioCodec::Opus)
+        );
+    }
+
+    #[test]
+    fn test_validate_with_transcodes_mkv_vp9() {
+        let temp_file = temp_video();
+        let probe = FixedProbe(MediaInfo::new(ContainerFormat::MKV, VideoCodec::VP9));
+        let recording = RecordingConfig::new("/recordings/{mount}", "/cam1").unwrap();
+        let config = StreamConfig::new(temp_file.path().to_path_buf()).with_recording(recording);
+
+        // VP9 cannot be recorded as is, but its H.264 transcode can
+        assert!(matches!(
+            config.clone().validate_with(&probe).unwrap_err(),
+            DomainError::InvalidRecording(_)
+        ));
+        let config = config
+            .with_transcode(TranscodeProfile::new())
+            .validate_with(&probe)
+            .unwrap();
+        assert_eq!(*config.container(), ContainerFormat::MKV);
+        assert_eq!(*config.codec(), VideoCodec::VP9);
+        assert_eq!(*config.output_codec(), VideoCodec::H264);
+    }
+
+    /// Probe answering per file, for playlists mixing clips
+    struct PathProbe(Vec<(PathBuf, MediaInfo)>);
+
+    impl MediaProbe for PathProbe {
+        fn probe(&self, path: &Path) -> Result<MediaInfo> {
+            self.0
+                .iter()
+                .find(|(known, _)| known == path)
+                .map(|(_, info)| info.clone())
+                .ok_or_else(|| DomainError::MediaProbeFailed(path.display().to_string()))
+        }
+    }
+
+    fn playlist(paths: &[&NamedTempFile]) -> Playlist {
+        Playlist::new(
+            paths
+                .iter()
+                .map(|file| PlaylistItem::new(file.path().to_path_buf()))
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_playlist_requires_every_item() {
+        let first = temp_video();
+        let items = vec![
+            PlaylistItem::new(first.path().to_path_buf()),
+            PlaylistItem::new(PathBuf::from("/nonexistent/night.mp4")),
+        ];
+        let config = StreamConfig::from_playlist(Playlist::new(items).unwrap());
+
+        assert_eq!(config.source_path(), first.path());
+        assert!(matches!(
+            config.validate().unwrap_err(),
+            DomainError::InvalidPath(_)
+        ));
+    }
+
+    #[test]
+    fn test_playlist_with_matching_codecs_passes_through() {
+        let (day, night) = (temp_video(), temp_video());
+        let probe = PathProbe(vec![
+            (
+                day.path().to_path_buf(),
+                MediaInfo::new(ContainerFormat::MP4, VideoCodec::H264),
+            ),
+            (
+                night.path().to_path_buf(),
+                MediaInfo::new(ContainerFormat::MKV, VideoCodec::H264),
+            ),
+        ]);
+
+        let config = StreamConfig::from_playlist(playlist(&[&day, &night]))
+            .validate_with(&probe)
+            .unwrap();
+
+        assert!(config.transcode().is_none());
+        let items = config.playlist().unwrap().items();
+        assert_eq!(
+            *items[1].media_info().unwrap().container(),
+            ContainerFormat::MKV
+        );
+        assert_eq!(*config.container(), ContainerFormat::MP4);
+    }
+
+    #[test]
+    fn test_playlist_with_mixed_codecs_enables_transcode() {
+        let (day, night) = (temp_video(), temp_video());
+        let probe = PathProbe(vec![
+            (
+                day.path().to_path_buf(),
+                MediaInfo::new(ContainerFormat::MP4, VideoCodec::H264),
+            ),
+            (
+                night.path().to_path_buf(),
+                MediaInfo::new(ContainerFormat::MP4, VideoCodec::H265),
+            ),
+        ]);
+
+        let config = StreamConfig::from_playlist(playlist(&[&day, &night]))
+            .validate_with(&probe)
+            .unwrap();
+        assert_eq!(*config.output_codec(), VideoCodec::H264);
+        assert!(config.transcode().is_some());
+
+        // A pinned codec cannot be satisfied by every clip
+        let pinned = StreamConfig::from_playlist(playlist(&[&day, &night]))
+            .with_codec(VideoCodec::H264)
+            .validate_with(&probe);
+        assert!(matches!(
+            pinned.unwrap_err(),
+            DomainError::UnsupportedCodec(_)
+        ));
+    }
+
+    #[test]
+    fn test_test_pattern_needs_no_file() {
+        let config = StreamConfig::from_test_pattern(TestPattern::new());
+        assert_eq!(config.source_kind(), SourceKind::TestPattern);
+        assert_eq!(*config.output_codec(), VideoCodec::H264);
+
+        let probe = FixedProbe(MediaInfo::new(ContainerFormat::MKV, VideoCodec::VP9));
+        let config = config.validate_with(&probe).unwrap();
+        assert!(config.media_info().is_none());
+
+        let with_audio = StreamConfig::from_test_pattern(TestPattern::new())
+            .with_audio_codec(AudioCodec::AAC)
+            .validate();
+        assert!(matches!(
+            with_audio.unwrap_err(),
+            DomainError::InvalidTestPattern(_)
+        ));
+    }
+
+    fn schedule(night: &NamedTempFile) -> Schedule {
+        let window = format!("22:00-06:00={}", night.path().display());
+        Schedule::new(vec![window.parse().unwrap()]).unwrap()
+    }
+
+    #[test]
+    fn test_schedule_requires_matching_files() {
+        let (day, night) = (temp_video(), temp_video());
+        let probe = PathProbe(vec![
+            (
+                day.path().to_path_buf(),
+                MediaInfo::new(ContainerFormat::MP4, VideoCodec::H264),
+            ),
+            (
+                night.path().to_path_buf(),
+                MediaInfo::new(ContainerFormat::MKV, VideoCodec::H264),
+            ),
+        ]);
+
+        let result = StreamConfig::new(day.path().to_path_buf())
+            .with_schedule(schedule(&night))
+            .validate_with(&probe);
+        assert!(matches!(
+            result.unwrap_err(),
+            DomainError::UnsupportedContainer(_)
+        ));
+
+        let probe = FixedProbe(MediaInfo::new(ContainerFormat::MP4, VideoCodec::H264));
+        let config = StreamConfig::new(day.path().to_path_buf())
+            .with_schedule(schedule(&night))
+            .validate_with(&probe)
+            .unwrap();
+        assert!(config.schedule().is_some());
+    }
+
+    #[test]
+    fn test_schedule_excludes_playlist() {
+        let (day, night) = (temp_video(), temp_video());
+
+        let result = StreamConfig::from_playlist(playlist(&[&day]))
+            .with_schedule(schedule(&night))
+            .validate();
+        assert!(matches!(
+            result.unwrap_err(),
+            DomainError::InvalidSchedule(_)
+        ));
+    }
+
+    #[test]
+    fn test_recording_needs_h264_or_h265() {
+        let temp_file = temp_video();
+        let recording = RecordingConfig::new("/recordings/{mount}", "/cam1").unwrap();
+        let config = StreamConfig::new(temp_file.path().to_path_buf()).with_recording(recording);
+
+        let h265 = FixedProbe(MediaInfo::new(ContainerFormat::MKV, VideoCodec::H265));
+        assert!(config.clone().validate_with(&h265).is_ok());
+
+        // The default codec passes until the probe finds VP8
+        let vp8 = FixedProbe(MediaInfo::new(ContainerFormat::WebM, VideoCodec::VP8));
+        assert!(config.validate().is_ok());
+        assert!(matches!(
+            config.validate_with(&vp8).unwrap_err(),
+            DomainError::InvalidRecording(_)
+        ));
+    }
 }
@@ -0,0 +1,206 @@
+use std::str::FromStr;
+
+use crate::domain::errors::{DomainError, Result};
+
+/// `videotestsrc` patterns by their nick
+const PATTERNS: &[&str] = &[
+    "smpte",
+    "snow",
+    "black",
+    "white",
+    "red",
+    "green",
+    "blue",
+    "checkers-1",
+    "checkers-2",
+    "checkers-4",
+    "checkers-8",
+    "circular",
+    "blink",
+    "smpte75",
+    "zone-plate",
+    "gamut",
+    "chroma-zone-plate",
+    "solid-color",
+    "ball",
+    "smpte100",
+    "bar",
+    "pinwheel",
+    "spokes",
+    "gradient",
+    "colors",
+];
+
+/// Text burned into generated frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternOverlay {
+    /// Wall-clock time (`clockoverlay`)
+    Clock,
+    /// Stream running time (`timeoverlay`)
+    Time,
+}
+
+impl PatternOverlay {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PatternOverlay::Clock => "clock",
+            PatternOverlay::Time => "time",
+        }
+    }
+}
+
+impl FromStr for PatternOverlay {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "clock" | "clockoverlay" => Ok(PatternOverlay::Clock),
+            "time" | "timeoverlay" => Ok(PatternOverlay::Time),
+            _ => Err(DomainError::InvalidTestPattern(format!(
+                "unknown overlay {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Generated video for running without media files
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestPattern {
+    pattern: String,
+    width: u32,
+    height: u32,
+    fps: u32,
+    overlay: Option<PatternOverlay>,
+}
+
+impl TestPattern {
+    pub fn new() -> Self {
+        Self {
+            pattern: "smpte".to_string(),
+            width: 1280,
+            height: 720,
+            fps: 30,
+            overlay: None,
+        }
+    }
+
+    pub fn with_pattern(mut self, pattern: &str) -> Result<Self> {
+        let pattern = pattern.trim().to_ascii_lowercase();
+        if !PATTERNS.contains(&pattern.as_str()) {
+            return Err(DomainError::InvalidTestPattern(format!(
+                "unknown pattern {}",
+                pattern
+            )));
+        }
+        self.pattern = pattern;
+        Ok(self)
+    }
+
+    pub fn with_resolution(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn with_fps(mut self, fps: u32) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    pub fn with_overlay(mut self, overlay: PatternOverlay) -> Self {
+        self.overlay = Some(overlay);
+        self
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Width and height in pixels
+    pub fn resolution(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn fps(&self) -> u32 {
+        self.fps
+    }
+
+    pub fn overlay(&self) -> Option<PatternOverlay> {
+        self.overlay
+    }
+
+    /// Dimensions must be even for 4:2:0 encoding
+    pub fn validate(&self) -> Result<()> {
+        if self.width == 0 || self.height == 0 || (self.width | self.height) & 1 != 0 {
+            return Err(DomainError::InvalidTestPattern(format!(
+                "resolution {}x{} must be non-zero and even",
+                self.width, self.height
+            )));
+        }
+        if !(1..=120).contains(&self.fps) {
+            return Err(DomainError::InvalidTestPattern(format!(
+                "{} fps is outside 1-120",
+                self.fps
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for TestPattern {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let pattern = TestPattern::new();
+        assert_eq!(pattern.pattern(), "smpte");
+        assert_eq!(pattern.resolution(), (1280, 720));
+        assert_eq!(pattern.fps(), 30);
+        assert!(pattern.overlay().is_none());
+        assert!(pattern.validate().is_ok());
+    }
+
+    #[test]
+    fn test_rejects_unknown_pattern() {
+        assert!(TestPattern::new().with_pattern("Ball").is_ok());
+        assert!(matches!(
+            TestPattern::new().with_pattern("plasma").unwrap_err(),
+            DomainError::InvalidTestPattern(_)
+        ));
+    }
+
+    #[test]
+    fn test_validate_dimensions_and_fps() {
+        assert!(TestPattern::new()
+            .with_resolution(641, 480)
+            .validate()
+            .is_err());
+        assert!(TestPattern::new()
+            .with_resolution(0, 480)
+            .validate()
+            .is_err());
+        assert!(TestPattern::new().with_fps(0).validate().is_err());
+        assert!(TestPattern::new().with_fps(240).validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_overlay() {
+        assert_eq!(
+            "clock".parse::<PatternOverlay>().unwrap(),
+            PatternOverlay::Clock
+        );
+        assert_eq!(
+            "timeoverlay".parse::<PatternOverlay>().unwrap(),
+            PatternOverlay::Time
+        );
+        assert!("date".parse::<PatternOverlay>().is_err());
+    }
+}
@@ -0,0 +1,67 @@
+use std::path::{Path, PathBuf};
+
+use crate::domain::errors::{DomainError, Result};
+
+/// PEM certificate and private key used to serve `rtsps://`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TlsConfig {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+}
+
+impl TlsConfig {
+    pub fn new(cert_path: PathBuf, key_path: PathBuf) -> Result<Self> {
+        Self::validate_file(&cert_path)?;
+        Self::validate_file(&key_path)?;
+
+        Ok(Self {
+            cert_path,
+            key_path,
+        })
+    }
+
+    pub fn cert_path(&self) -> &Path {
+        &self.cert_path
+    }
+
+    pub fn key_path(&self) -> &Path {
+        &self.key_path
+    }
+
+    fn validate_file(path: &Path) -> Result<()> {
+        if !path.exists() {
+            return Err(DomainError::InvalidPath(path.to_path_buf()));
+        }
+        if !path.is_file() {
+            return Err(DomainError::PathNotFile(path.to_path_buf()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_accepts_existing_files() {
+        let cert = NamedTempFile::new().unwrap();
+        let key = NamedTempFile::new().unwrap();
+
+        let tls = TlsConfig::new(cert.path().to_path_buf(), key.path().to_path_buf()).unwrap();
+        assert_eq!(tls.cert_path(), cert.path());
+        assert_eq!(tls.key_path(), key.path());
+    }
+
+    #[test]
+    fn test_rejects_missing_key() {
+        let cert = NamedTempFile::new().unwrap();
+
+        let result = TlsConfig::new(
+            cert.path().to_path_buf(),
+            PathBuf::from("/nonexistent/key.pem"),
+        );
+        assert!(matches!(result.unwrap_err(), DomainError::InvalidPath(_)));
+    }
+}
@@ -0,0 +1,180 @@
+use std::str::FromStr;
+
+use super::VideoCodec;
+use crate::domain::errors::{DomainError, Result};
+
+/// Encoder profile of the transcoded output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncoderProfile {
+    Baseline,
+    Main,
+    High,
+}
+
+impl EncoderProfile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EncoderProfile::Baseline => "baseline",
+            EncoderProfile::Main => "main",
+            EncoderProfile::High => "high",
+        }
+    }
+}
+
+impl FromStr for EncoderProfile {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "baseline" => Ok(EncoderProfile::Baseline),
+            "main" => Ok(EncoderProfile::Main),
+            "high" => Ok(EncoderProfile::High),
+            _ => Err(DomainError::InvalidTranscodeProfile(format!(
+                "unknown profile {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Software re-encode applied before payloading
+///
+/// The output never contains B-frames, so it is safe for WebRTC and HLS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscodeProfile {
+    codec: VideoCodec,
+    profile: EncoderProfile,
+    gop_length: u32,
+    bitrate_kbps: u32,
+}
+
+impl TranscodeProfile {
+    pub fn new() -> Self {
+        Self {
+            codec: VideoCodec::H264,
+            profile: EncoderProfile::Baseline,
+            gop_length: 60,
+            bitrate_kbps: 2000,
+        }
+    }
+
+    pub fn with_codec(mut self, codec: VideoCodec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    pub fn with_profile(mut self, profile: EncoderProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Maximum distance between keyframes, in frames
+    pub fn with_gop_length(mut self, frames: u32) -> Self {
+        self.gop_length = frames;
+        self
+    }
+
+    pub fn with_bitrate_kbps(mut self, kbps: u32) -> Self {
+        self.bitrate_kbps = kbps;
+        self
+    }
+
+    pub fn codec(&self) -> &VideoCodec {
+        &self.codec
+    }
+
+    pub fn profile(&self) -> &EncoderProfile {
+        &self.profile
+    }
+
+    pub fn gop_length(&self) -> u32 {
+        self.gop_length
+    }
+
+    pub fn bitrate_kbps(&self) -> u32 {
+        self.bitrate_kbps
+    }
+
+    /// Pure validation logic (domain concern)
+    pub fn validate(&self) -> Result<()> {
+        match (self.codec, self.profile) {
+            (VideoCodec::H264, _) | (VideoCodec::H265, EncoderProfile::Main) => {}
+            (VideoCodec::H265, profile) => {
+                return Err(DomainError::InvalidTranscodeProfile(format!(
+                    "H.265 output only supports the main profile, not {}",
+                    profile.as_str()
+                )));
+            }
+            (codec, _) => {
+                return Err(DomainError::InvalidTranscodeProfile(format!(
+                    "cannot encode {}, only H.264 and H.265",
+                    codec.as_str()
+                )));
+            }
+        }
+
+        if self.gop_length == 0 {
+            return Err(DomainError::InvalidTranscodeProfile(
+                "GOP length must be at least 1 frame".to_string(),
+            ));
+        }
+
+        if self.bitrate_kbps == 0 {
+            return Err(DomainError::InvalidTranscodeProfile(
+                "bitrate must be greater than zero".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for TranscodeProfile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_profile_is_valid_baseline_h264() {
+        let profile = TranscodeProfile::new();
+
+        assert_eq!(*profile.codec(), VideoCodec::H264);
+        assert_eq!(*profile.profile(), EncoderProfile::Baseline);
+        assert!(profile.validate().is_ok());
+    }
+
+    #[test]
+    fn test_rejects_unencodable_codec() {
+        let profile = TranscodeProfile::new().with_codec(VideoCodec::VP8);
+        assert!(matches!(
+            profile.validate().unwrap_err(),
+            DomainError::InvalidTranscodeProfile(_)
+        ));
+    }
+
+    #[test]
+    fn test_rejects_h265_baseline() {
+        let profile = TranscodeProfile::new().with_codec(VideoCodec::H265);
+        assert!(profile.validate().is_err());
+
+        let profile = profile.with_profile(EncoderProfile::Main);
+        assert!(profile.validate().is_ok());
+    }
+
+    #[test]
+    fn test_rejects_zero_gop_and_bitrate() {
+        assert!(TranscodeProfile::new()
+            .with_gop_length(0)
+            .validate()
+            .is_err());
+        assert!(TranscodeProfile::new()
+            .with_bitrate_kbps(0)
+            .validate()
+            .is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use std::str::FromStr;
+
+use crate::domain::errors::DomainError;
+
+/// Lower transports a mount accepts during SETUP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportPolicy {
+    Both,
+    TcpOnly,
+    UdpOnly,
+}
+
+impl TransportPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransportPolicy::Both => "both",
+            TransportPolicy::TcpOnly => "tcp",
+            TransportPolicy::UdpOnly => "udp",
+        }
+    }
+
+    pub fn allows_tcp(&self) -> bool {
+        !matches!(self, TransportPolicy::UdpOnly)
+    }
+
+    /// Whether unicast and multicast UDP are allowed
+    pub fn allows_udp(&self) -> bool {
+        !matches!(self, TransportPolicy::TcpOnly)
+    }
+}
+
+impl Default for TransportPolicy {
+    fn default() -> Self {
+        TransportPolicy::Both
+    }
+}
+
+impl FromStr for TransportPolicy {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "both" | "any" | "tcp,udp" | "udp,tcp" => Ok(TransportPolicy::Both),
+            "tcp" | "tcp-only" => Ok(TransportPolicy::TcpOnly),
+            "udp" | "udp-only" => Ok(TransportPolicy::UdpOnly),
+            _ => Err(DomainError::InvalidTransportConfig(format!(
+                "unknown transport policy {}",
+                s
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_policies() {
+        assert_eq!(
+            "both".parse::<TransportPolicy>().unwrap(),
+            TransportPolicy::Both
+        );
+        assert_eq!(
+            "TCP".parse::<TransportPolicy>().unwrap(),
+            TransportPolicy::TcpOnly
+        );
+        assert_eq!(
+            "udp-only".parse::<TransportPolicy>().unwrap(),
+            TransportPolicy::UdpOnly
+        );
+        assert!("sctp".parse::<TransportPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_allowed_transports() {
+        assert!(TransportPolicy::Both.allows_tcp() && TransportPolicy::Both.allows_udp());
+        assert!(!TransportPolicy::TcpOnly.allows_udp());
+        assert!(!TransportPolicy::UdpOnly.allows_tcp());
+    }
+}
@@ -1,7 +1,15 @@
+use std::str::FromStr;
+
+use crate::domain::errors::DomainError;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VideoCodec {
     H264,
     H265,
+    VP8,
+    VP9,
+    AV1,
+    MJPEG,
 }
 
 impl VideoCodec {
@@ -9,6 +17,20 @@ impl VideoCodec {
         match self {
             VideoCodec::H264 => "H.264",
             VideoCodec::H265 => "H.265",
+            VideoCodec::VP8 => "VP8",
+            VideoCodec::VP9 => "VP9",
+            VideoCodec::AV1 => "AV1",
+            VideoCodec::MJPEG => "MJPEG",
+        }
+    }
+
+    /// RTP payload type used unless one is configured explicitly
+    ///
+    /// JPEG has the static payload type 26 (RFC 3551), all others are dynamic.
+    pub fn default_payload_type(&self) -> u8 {
+        match self {
+            VideoCodec::MJPEG => 26,
+            _ => 96,
         }
     }
 }
@@ -18,3 +40,51 @@ impl Default for VideoCodec {
         VideoCodec::H264
     }
 }
+
+impl FromStr for VideoCodec {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "h264" | "h.264" | "avc" => Ok(VideoCodec::H264),
+            "h265" | "h.265" | "hevc" => Ok(VideoCodec::H265),
+            "vp8" => Ok(VideoCodec::VP8),
+            "vp9" => Ok(VideoCodec::VP9),
+            "av1" => Ok(VideoCodec::AV1),
+            "mjpeg" | "mjpg" | "jpeg" => Ok(VideoCodec::MJPEG),
+            _ => Err(DomainError::UnsupportedCodec(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_codec_aliases() {
+        assert_eq!("h264".parse::<VideoCodec>().unwrap(), VideoCodec::H264);
+        assert_eq!("AVC".parse::<VideoCodec>().unwrap(), VideoCodec::H264);
+        assert_eq!("hevc".parse::<VideoCodec>().unwrap(), VideoCodec::H265);
+        assert_eq!("VP8".parse::<VideoCodec>().unwrap(), VideoCodec::VP8);
+        assert_eq!("vp9".parse::<VideoCodec>().unwrap(), VideoCodec::VP9);
+        assert_eq!("av1".parse::<VideoCodec>().unwrap(), VideoCodec::AV1);
+        assert_eq!("mjpg".parse::<VideoCodec>().unwrap(), VideoCodec::MJPEG);
+    }
+
+    #[test]
+    fn test_default_payload_types() {
+        assert_eq!(VideoCodec::H264.default_payload_type(), 96);
+        assert_eq!(VideoCodec::VP8.default_payload_type(), 96);
+        assert_eq!(VideoCodec::MJPEG.default_payload_type(), 26);
+    }
+
+    #[test]
+    fn test_parse_unknown_codec() {
+        let result = "mpeg2".parse::<VideoCodec>();
+        assert!(matches!(
+            result.unwrap_err(),
+            DomainError::UnsupportedCodec(_)
+        ));
+    }
+}
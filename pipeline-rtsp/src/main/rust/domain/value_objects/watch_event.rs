@@ -0,0 +1,56 @@
+use std::path::{Path, PathBuf};
+
+use crate::domain::errors::{DomainError, Result};
+
+/// Mount points of watched clips live under this prefix
+pub const CLIP_MOUNT_PREFIX: &str = "/clips/";
+
+/// Change to a file in the watched directory
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchEvent {
+    /// A file finished writing or was moved in
+    Added(PathBuf),
+    /// A file was deleted or moved out
+    Removed(PathBuf),
+}
+
+impl WatchEvent {
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Added(path) | Self::Removed(path) => path,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Added(_) => "added",
+            Self::Removed(_) => "removed",
+        }
+    }
+
+    /// `/clips/<stem>`, the mount the file is published on
+    pub fn mount_point(&self) -> Result<String> {
+        self.path()
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .filter(|stem| !stem.is_empty())
+            .map(|stem| format!("{}{}", CLIP_MOUNT_PREFIX, stem))
+            .ok_or_else(|| DomainError::InvalidMountPoint(self.path().display().to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mount_point_uses_file_stem() {
+        let added = WatchEvent::Added(PathBuf::from("/watch/lobby-day.mp4"));
+        assert_eq!(added.mount_point().unwrap(), "/clips/lobby-day");
+        assert_eq!(added.kind(), "added");
+
+        let removed = WatchEvent::Removed(PathBuf::from("/watch/lobby-day.mkv"));
+        assert_eq!(removed.mount_point().unwrap(), "/clips/lobby-day");
+        assert!(WatchEvent::Added(PathBuf::from("/")).mount_point().is_err());
+    }
+}
@@ -0,0 +1,538 @@
+use std::collections::BTreeMap;
+use std::ffi::CStr;
+use std::sync::{Arc, Mutex};
+
+use glib::translate::{from_glib, from_glib_none, ToGlibPtr};
+use gstreamer::prelude::*;
+use gstreamer_rtsp_server as gst_rtsp;
+use gstreamer_rtsp_server::gst_rtsp::{ffi as rtsp_ffi, RTSPLowerTrans, RTSPProfile};
+use gstreamer_rtsp_server::prelude::*;
+
+use crate::domain::entities::{ClientSession, StreamSession};
+use crate::domain::ports::MetricsReporter;
+use crate::domain::value_objects::{ClientTransport, RtpProfile, SharingMode};
+
+/// Sessions keyed by mount point, shared with GStreamer signal handlers
+pub type SharedSessions = Arc<Mutex<BTreeMap<String, StreamSession>>>;
+
+/// Connected clients keyed by client id
+type SharedClients = Arc<Mutex<BTreeMap<String, ClientSession>>>;
+
+/// Client id that last set up each media, keyed by media address
+type MediaOwners = Arc<Mutex<BTreeMap<usize, String>>>;
+
+/// Feeds RTSP client and payloader activity into the live sessions and metrics
+///
+/// Lock order is clients, then sessions, then media owners.
+#[derive(Clone)]
+pub struct ClientTracker {
+    sessions: SharedSessions,
+    clients: SharedClients,
+    media_owners: MediaOwners,
+    metrics: Arc<dyn MetricsReporter>,
+}
+
+impl ClientTracker {
+    pub fn new(sessions: SharedSessions, metrics: Arc<dyn MetricsReporter>) -> Self {
+        Self {
+            sessions,
+            clients: Arc::new(Mutex::new(BTreeMap::new())),
+            media_owners: Arc::new(Mutex::new(BTreeMap::new())),
+            metrics,
+        }
+    }
+
+    /// Track every client the server accepts until its connection closes
+    pub fn attach(&self, server: &gst_rtsp::RTSPServer) {
+        let tracker = self.clone();
+        server.connect_client_connected(move |_server, client| {
+            tracker.track_client(client);
+        });
+    }
+
+    /// Snapshots of all connected clients
+    pub fn clients(&self) -> Vec<ClientSession> {
+        self.clients.lock().unwrap().values().cloned().collect()
+    }
+
+    /// Keep the mount's count of prepared media pipelines up to date
+    pub fn track_media(&self, factory: &gst_rtsp::RTSPMediaFactory, mount_point: &str) {
+        let tracker = self.clone();
+        let mount_point = mount_point.to_string();
+
+        factory.connect_media_configure(move |_factory, media| {
+            if let Some(session) = tracker.sessions.lock().unwrap().get_mut(&mount_point) {
+                session.add_media();
+            }
+
+            let tracker = tracker.clone();
+            let mount_point = mount_point.clone();
+            media.connect_unprepared(move |media| {
+                if let Some(session) = tracker.sessions.lock().unwrap().get_mut(&mount_point) {
+                    session.remove_media();
+                }
+                tracker
+                    .media_owners
+                    .lock()
+                    .unwrap()
+                    .remove(&media_key(media));
+            });
+        });
+    }
+
+    /// Count bytes leaving each of the mount's payloaders
+    ///
+    /// Shared media fans out to every client of the mount, while a per-client
+    /// media only carries the stream of the client that set it up.
+    pub fn count_bytes(
+        &self,
+        factory: &gst_rtsp::RTSPMediaFactory,
+        mount_point: &str,
+        sharing_mode: SharingMode,
+    ) {
+        let tracker = self.clone();
+        let mount_point = mount_point.to_string();
+
+        factory.connect_media_configure(move |_factory, media| {
+            let owner_key = (!sharing_mode.is_shared()).then(|| media_key(media));
+            let element = media.element();
+            let Some(bin) = element.downcast_ref::<gstreamer::Bin>() else {
+                return;
+            };
+
+            // Payloaders are named pay0 (video), pay1 (audio), ...
+            let payloaders = (0..).map_while(|index| bin.by_name(&format!("pay{}", index)));
+            for src_pad in payloaders.filter_map(|payloader| payloader.static_pad("src")) {
+                let tracker = tracker.clone();
+                let mount_point = mount_point.clone();
+                src_pad.add_probe(
+                    gstreamer::PadProbeType::BUFFER | gstreamer::PadProbeType::BUFFER_LIST,
+                    move |_pad, info| {
+                        let bytes = match &info.data {
+                            Some(gstreamer::PadProbeData::Buffer(buffer)) => buffer.size(),
+                            Some(gstreamer::PadProbeData::BufferList(list)) => {
+                                list.calculate_size()
+                            }
+                            _ => 0,
+                        };
+                        tracker.record_bytes(&mount_point, owner_key, bytes as u64);
+                        gstreamer::PadProbeReturn::Ok
+                    },
+                );
+            }
+        });
+    }
+
+    fn track_client(&self, client: &gst_rtsp::RTSPClient) {
+        let session = ClientSession::new(remote_ip(client));
+        let client_id = session.id().to_string();
+        self.clients
+            .lock()
+            .unwrap()
+            .insert(client_id.clone(), session);
+
+        // A client only belongs to a mount once it asks for one
+        let (tracker, id) = (self.clone(), client_id.clone());
+        client.connect_describe_request(move |_client, ctx| {
+            tracker.assign_mount(&id, ctx);
+        });
+
+        let (tracker, id) = (self.clone(), client_id.clone());
+        client.connect_setup_request(move |_client, ctx| {
+            tracker.assign_mount(&id, ctx);
+            tracker.assign_transport(&id, ctx);
+        });
+
+        // Shared media reach a client only between PLAY and PAUSE or TEARDOWN
+        let (tracker, id) = (self.clone(), client_id.clone());
+        client.connect_play_request(move |_client, _ctx| {
+            tracker.set_playing(&id, true);
+        });
+        let (tracker, id) = (self.clone(), client_id.clone());
+        client.connect_pause_request(move |_client, _ctx| {
+            tracker.set_playing(&id, false);
+        });
+        let (tracker, id) = (self.clone(), client_id.clone());
+        client.connect_teardown_request(move |_client, _ctx| {
+            tracker.set_playing(&id, false);
+        });
+
+        // RTSPAuth has no failure signal, so watch for 401 responses instead.
+        // Clients ask without credentials first to learn the scheme; only
+        // rejected credentials count as a failed attempt.
+        let tracker = self.clone();
+        client.connect("send-message", false, move |args| {
+            let message = args.get(2)?.get::<glib::Pointer>().ok()?;
+            if response_status(message) == Some(rtsp_ffi::GST_RTSP_STS_UNAUTHORIZED)
+                && gst_rtsp::RTSPContext::with_current_context(carries_credentials).unwrap_or(false)
+            {
+                tracker.metrics.report_unauthenticated_attempt();
+            }
+            None
+        });
+
+        let tracker = self.clone();
+        client.connect_closed(move |_client| {
+            tracker.release_client(&client_id);
+        });
+    }
+
+    fn assign_mount(&self, client_id: &str, ctx: &gst_rtsp::RTSPContext) {
+        let mut clients = self.clients.lock().unwrap();
+        let Some(client) = clients.get_mut(client_id) else {
+            return;
+        };
+        if client.mount_point().is_some() {
+            return;
+        }
+
+        let Some(uri) = ctx.uri().map(|url| url.request_uri()) else {
+            return;
+        };
+
+        let mut sessions = self.sessions.lock().unwrap();
+        let Some(mount_point) = mount_for_path(request_path(&uri), sessions.keys()).cloned() else {
+            return;
+        };
+
+        if let Some(session) = sessions.get_mut(&mount_point) {
+            session.add_client();
+        }
+        self.metrics.report_client_connected(&mount_point);
+        tracing::debug!(
+            client_id = %client_id,
+            mount_point = %mount_point,
+            "RTSP client connected"
+        );
+
+        client.assign_mount(mount_point);
+    }
+
+    fn assign_transport(&self, client_id: &str, ctx: &gst_rtsp::RTSPContext) {
+        // The stream transport is set once SETUP has been handled
+        let Some(transport) = context_transport(ctx) else {
+            return;
+        };
+        if let Some(media) = context_media(ctx) {
+            self.media_owners
+                .lock()
+                .unwrap()
+                .insert(media_key(&media), client_id.to_string());
+        }
+
+        let mut clients = self.clients.lock().unwrap();
+        let Some(client) = clients.get_mut(client_id) else {
+            return;
+        };
+        // Report the transport once, on the first SETUP of the client
+        let lower_transport = client_transport(transport.lower_transport);
+        if client.transport() == ClientTransport::Unknown {
+            if let Some(mount_point) = client.mount_point() {
+                self.metrics
+                    .report_client_transport(mount_point, lower_transport);
+                tracing::debug!(
+                    client_id = %client_id,
+                    mount_point = %mount_point,
+                    transport = lower_transport.as_str(),
+                    destination = transport.destination.as_deref().unwrap_or("-"),
+                    "RTSP client transport negotiated"
+                );
+            }
+        }
+        client.set_transport(lower_transport);
+
+        let Some(profile) = rtp_profile(transport.profile) else {
+            return;
+        };
+        // Each stream is set up separately; count the client once
+        let newly_secure = profile.is_secure() && !client.uses_srtp();
+        client.set_rtp_profile(profile);
+        if let (true, Some(mount_point)) = (newly_secure, client.mount_point()) {
+            self.metrics.report_srtp_negotiated(mount_point);
+            tracing::debug!(
+                client_id = %client_id,
+                mount_point = %mount_point,
+                profile = profile.as_str(),
+                "RTSP client negotiated SRTP"
+            );
+        }
+    }
+
+    fn set_playing(&self, client_id: &str, playing: bool) {
+        if let Some(client) = self.clients.lock().unwrap().get_mut(client_id) {
+            client.set_playing(playing);
+        }
+    }
+
+    fn release_client(&self, client_id: &str) {
+        let Some(client) = self.clients.lock().unwrap().remove(client_id) else {
+            return;
+        };
+        let Some(mount_point) = client.mount_point() else {
+            return;
+        };
+
+        // Clients of a removed mount were already accounted for when it stopped
+        let mut sessions = self.sessions.lock().unwrap();
+        if let Some(session) = sessions.get_mut(mount_point) {
+            session.remove_client();
+            self.metrics.report_client_disconnected(mount_point);
+            tracing::debug!(
+                client_id = %client_id,
+                mount_point = %mount_point,
+                "RTSP client disconnected"
+            );
+        }
+    }
+
+    fn record_bytes(&self, mount_point: &str, owner_key: Option<usize>, bytes: u64) {
+        let mut clients = self.clients.lock().unwrap();
+        let mut sessions = self.sessions.lock().unwrap();
+        let Some(session) = sessions.get_mut(mount_point) else {
+            return;
+        };
+
+        if let Some(key) = owner_key {
+            let owner = self.media_owners.lock().unwrap().get(&key).cloned();
+            let Some(client) = owner.and_then(|id| clients.get_mut(&id)) else {
+                return;
+            };
+            client.add_bytes_sent(bytes);
+            session.add_bytes_sent(bytes);
+            self.metrics.report_bytes_sent(mount_point, bytes);
+            return;
+        }
+
+        // Shared media fans every payloaded buffer out to each playing client of the mount
+        let playing: Vec<_> = clients
+            .values_mut()
+            .filter(|client| client.mount_point() == Some(mount_point) && client.is_playing())
+            .collect();
+        let sent = bytes * playing.len() as u64;
+        if sent == 0 {
+            return;
+        }
+
+        session.add_bytes_sent(sent);
+        for client in playing {
+            client.add_bytes_sent(bytes);
+        }
+        self.metrics.report_bytes_sent(mount_point, sent);
+    }
+}
+
+/// Identity of a media object for as long as it is prepared
+fn media_key(media: &gst_rtsp::RTSPMedia) -> usize {
+    media.as_ptr() as usize
+}
+
+/// Remote IP of a client; the bindings leave `gst_rtsp_client_get_connection` out
+fn remote_ip(client: &gst_rtsp::RTSPClient) -> Option<String> {
+    // SAFETY: the connection and its IP string belong to the client, which outlives this call
+    unsafe {
+        let connection = gst_rtsp::ffi::gst_rtsp_client_get_connection(client.to_glib_none().0);
+        if connection.is_null() {
+            return None;
+        }
+        let ip = rtsp_ffi::gst_rtsp_connection_get_ip(connection);
+        (!ip.is_null()).then(|| CStr::from_ptr(ip).to_string_lossy().into_owned())
+    }
+}
+
+/// Media a request addressed; `RTSPContext` only wraps some of its fields
+fn context_media(ctx: &gst_rtsp::RTSPContext) -> Option<gst_rtsp::RTSPMedia> {
+    // SAFETY: the context is valid for the duration of the signal handler
+    unsafe {
+        let ctx: *mut gst_rtsp::ffi::GstRTSPContext = ctx.to_glib_none().0;
+        from_glib_none((*ctx).media)
+    }
+}
+
+/// Transport a SETUP request negotiated
+struct NegotiatedTransport {
+    lower_transport: RTSPLowerTrans,
+    profile: RTSPProfile,
+    destination: Option<String>,
+}
+
+/// Transport of the stream a SETUP request configured, None before SETUP
+///
+/// Neither the context's stream transport nor its `GstRTSPTransport` are
+/// exposed by the bindings.
+fn context_transport(ctx: &gst_rtsp::RTSPContext) -> Option<NegotiatedTransport> {
+    // SAFETY: the context and its stream transport are valid for the duration
+    // of the signal handler, and the transport is owned by the stream transport
+    unsafe {
+        let ctx: *mut gst_rtsp::ffi::GstRTSPContext = ctx.to_glib_none().0;
+        if (*ctx).trans.is_null() {
+            return None;
+        }
+        let transport = gst_rtsp::ffi::gst_rtsp_stream_transport_get_transport((*ctx).trans);
+        if transport.is_null() {
+            return None;
+        }
+        let destination = (*transport).destination;
+        Some(NegotiatedTransport {
+            lower_transport: from_glib((*transport).lower_transport),
+            profile: from_glib((*transport).profile),
+            destination: (!destination.is_null())
+                .then(|| CStr::from_ptr(destination).to_string_lossy().into_owned()),
+        })
+    }
+}
+
+/// Status code of an RTSP response, None for requests
+fn response_status(message: glib::Pointer) -> Option<rtsp_ffi::GstRTSPStatusCode> {
+    if message.is_null() {
+        return None;
+    }
+
+    let mut code = rtsp_ffi::GST_RTSP_STS_INVALID;
+    // SAFETY: send-message always carries a valid GstRTSPMessage
+    let result = unsafe {
+        rtsp_ffi::gst_rtsp_message_parse_response(
+            message as *mut rtsp_ffi::GstRTSPMessage,
+            &mut code,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    (result == rtsp_ffi::GST_RTSP_OK).then_some(code)
+}
+
+/// Whether the request being answered carried an `Authorization` header
+fn carries_credentials(ctx: &gst_rtsp::RTSPContext) -> bool {
+    // SAFETY: the current context and its request are valid while the request is handled
+    unsafe {
+        let ctx: *mut gst_rtsp::ffi::GstRTSPContext = ctx.to_glib_none().0;
+        let request = (*ctx).request;
+        if request.is_null() {
+            return false;
+        }
+        let mut value = std::ptr::null_mut();
+        rtsp_ffi::gst_rtsp_message_get_header(
+            request,
+            rtsp_ffi::GST_RTSP_HDR_AUTHORIZATION,
+            &mut value,
+            0,
+        ) == rtsp_ffi::GST_RTSP_OK
+    }
+}
+
+fn client_transport(lower_transport: RTSPLowerTrans) -> ClientTransport {
+    if lower_transport.contains(RTSPLowerTrans::TCP) {
+        ClientTransport::Tcp
+    } else if lower_transport.contains(RTSPLowerTrans::UDP_MCAST) {
+        ClientTransport::UdpMulticast
+    } else if lower_transport.contains(RTSPLowerTrans::UDP) {
+        ClientTransport::Udp
+    } else {
+        ClientTransport::Unknown
+    }
+}
+
+fn rtp_profile(profile: RTSPProfile) -> Option<RtpProfile> {
+    if profile.contains(RTSPProfile::SAVPF) {
+        Some(RtpProfile::Savpf)
+    } else if profile.contains(RTSPProfile::SAVP) {
+        Some(RtpProfile::Savp)
+    } else if profile.contains(RTSPProfile::AVPF) {
+        Some(RtpProfile::Avpf)
+    } else if profile.contains(RTSPProfile::AVP) {
+        Some(RtpProfile::Avp)
+    } else {
+        None
+    }
+}
+
+/// Path component of an RTSP request URI, without query string
+fn request_path(uri: &str) -> &str {
+    let without_scheme = uri.split_once("://").map_or(uri, |(_, rest)| rest);
+    let path = without_scheme
+        .find('/')
+        .map_or("/", |start| &without_scheme[start..]);
+    path.split('?').next().unwrap_or(path)
+}
+
+/// Longest mount point serving a request path (SETUP adds a `/stream=N` suffix)
+fn mount_for_path<'a>(
+    path: &str,
+    mounts: impl IntoIterator<Item = &'a String>,
+) -> Option<&'a String> {
+    mounts
+        .into_iter()
+        .filter(|mount| {
+            path == mount.as_str()
+                || path
+                    .strip_prefix(mount.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+        .max_by_key(|mount| mount.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::domain::value_objects::{ServerConfig, StreamConfig};
+    use crate::infrastructure::metrics::PrometheusReporter;
+
+    #[test]
+    fn test_shared_bytes_count_only_playing_clients() {
+        let stream_config = StreamConfig::new(PathBuf::from("/test/video.mp4"));
+        let server_config = ServerConfig::new(8554, "/cam1".to_string()).unwrap();
+        let sessions: SharedSessions = Arc::new(Mutex::new(BTreeMap::from([(
+            "/cam1".to_string(),
+            StreamSession::new(stream_config, server_config),
+        )])));
+        let tracker = ClientTracker::new(sessions.clone(), Arc::new(PrometheusReporter::new()));
+
+        for playing in [true, false] {
+            let mut client = ClientSession::new(None);
+            client.assign_mount("/cam1".to_string());
+            client.set_playing(playing);
+            tracker
+                .clients
+                .lock()
+                .unwrap()
+                .insert(client.id().to_string(), client);
+        }
+        tracker.record_bytes("/cam1", None, 1000);
+
+        assert_eq!(sessions.lock().unwrap()["/cam1"].bytes_sent(), 1000);
+        let mut sent: Vec<_> = tracker.clients().iter().map(|c| c.bytes_sent()).collect();
+        sent.sort();
+        assert_eq!(sent, vec![0, 1000]);
+    }
+
+    #[test]
+    fn test_request_path_strips_host_and_query() {
+        assert_eq!(request_path("rtsp://127.0.0.1:8554/cam1"), "/cam1");
+        assert_eq!(
+            request_path("rtsp://host:8554/cam1/stream=0?token=x"),
+            "/cam1/stream=0"
+        );
+        assert_eq!(request_path("rtsp://host:8554"), "/");
+    }
+
+    #[test]
+    fn test_mount_for_path_matches_setup_suffix() {
+        let mounts = vec!["/cam1".to_string(), "/cam10".to_string()];
+
+        assert_eq!(mount_for_path("/cam1", &mounts), Some(&mounts[0]));
+        assert_eq!(mount_for_path("/cam1/stream=0", &mounts), Some(&mounts[0]));
+        assert_eq!(mount_for_path("/cam10/stream=0", &mounts), Some(&mounts[1]));
+        assert_eq!(mount_for_path("/cam2", &mounts), None);
+    }
+
+    #[test]
+    fn test_mount_for_path_prefers_longest_mount() {
+        let mounts = vec!["/clips".to_string(), "/clips/lobby".to_string()];
+
+        assert_eq!(
+            mount_for_path("/clips/lobby/stream=0", &mounts),
+            Some(&mounts[1])
+        );
+    }
+}
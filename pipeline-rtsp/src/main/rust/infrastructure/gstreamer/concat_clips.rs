@@ -0,0 +1,265 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use gstreamer::prelude::*;
+use gstreamer_rtsp_server as gst_rtsp;
+use gstreamer_rtsp_server::prelude::*;
+
+use super::client_tracker::SharedSessions;
+use crate::domain::errors::{DomainError, Result};
+use crate::domain::ports::MetricsReporter;
+use crate::domain::value_objects::ServerConfig;
+
+/// Time a finished clip gets to drain into `concat` before it is torn down
+const CLIP_RETIRE_DELAY: Duration = Duration::from_secs(2);
+
+/// Names of the `concat` elements of a concat launch string
+const CONCATS: [&str; 2] = ["vcat", "acat"];
+
+/// Add a clip bin to a media's launch bin and queue it on the `concat` elements
+///
+/// The bin's `vout` and `aout` elements get ghosted and linked to new request
+/// pads, which `concat` plays after its current ones. Returns each `concat`
+/// with the pad it got.
+pub(crate) fn attach_clip(
+    pipeline: &gstreamer::Bin,
+    description: &str,
+) -> Result<Vec<(gstreamer::Element, gstreamer::Pad)>> {
+    let switch_failed = |e: &dyn std::fmt::Display| DomainError::SourceSwitchFailed(e.to_string());
+
+    let clip = gstreamer::parse::bin_from_description(description, false)
+        .map_err(|e| switch_failed(&e))?;
+    pipeline.add(&clip).map_err(|e| switch_failed(&e))?;
+
+    let mut linked = Vec::new();
+    for (concat_name, tail_name) in CONCATS.into_iter().zip(["vout", "aout"]) {
+        let (Some(concat), Some(tail)) = (pipeline.by_name(concat_name), clip.by_name(tail_name))
+        else {
+            continue;
+        };
+        let target = tail
+            .static_pad("src")
+            .ok_or_else(|| switch_failed(&format!("{} has no source pad", tail_name)))?;
+        let ghost = gstreamer::GhostPad::with_target(&target).map_err(|e| switch_failed(&e))?;
+        clip.add_pad(&ghost).map_err(|e| switch_failed(&e))?;
+        let next = concat
+            .request_pad_simple("sink_%u")
+            .ok_or_else(|| switch_failed(&format!("{} refused a pad", concat_name)))?;
+        ghost.link(&next).map_err(|e| switch_failed(&e))?;
+        linked.push((concat, next));
+    }
+    clip.sync_state_with_parent()
+        .map_err(|e| switch_failed(&e))?;
+
+    Ok(linked)
+}
+
+/// Release `concat` pads once their clips drained, and remove what fed them
+///
+/// Appended clips are bins of their own, while clips of the launch string are
+/// loose elements of the launch bin; both are found by walking upstream from
+/// the pad. A pad that was released in the meantime is skipped.
+pub(crate) fn retire_clips(
+    pipeline: gstreamer::Bin,
+    finished: Vec<(gstreamer::Element, gstreamer::Pad)>,
+) {
+    glib::timeout_add_once(CLIP_RETIRE_DELAY, move || {
+        let mut feeding = Vec::new();
+        for (concat, pad) in &finished {
+            if pad.parent_element().as_ref() != Some(concat) {
+                continue;
+            }
+            if let Some(element) = pad
+                .peer()
+                .and_then(|peer| top_level_element(&pipeline, &peer))
+            {
+                collect_upstream(&pipeline, element, &mut feeding);
+            }
+            concat.release_request_pad(pad);
+        }
+        for element in feeding {
+            let _ = element.set_state(gstreamer::State::Null);
+            let _ = pipeline.remove(&element);
+        }
+    });
+}
+
+/// The child of `pipeline` a pad belongs to, directly or through nested bins
+fn top_level_element(
+    pipeline: &gstreamer::Bin,
+    pad: &gstreamer::Pad,
+) -> Option<gstreamer::Element> {
+    let mut element = pad.parent_element()?;
+    loop {
+        let parent = element.parent()?;
+        if &parent == pipeline.upcast_ref::<gstreamer::Object>() {
+            return Some(element);
+        }
+        element = parent.downcast().ok()?;
+    }
+}
+
+/// Add an element and everything upstream of it, stopping at the pipeline's edge
+fn collect_upstream(
+    pipeline: &gstreamer::Bin,
+    element: gstreamer::Element,
+    collected: &mut Vec<gstreamer::Element>,
+) {
+    if collected.contains(&element) {
+        return;
+    }
+    let upstream: Vec<_> = element
+        .sink_pads()
+        .iter()
+        .filter_map(|pad| pad.peer())
+        .filter_map(|peer| top_level_element(pipeline, &peer))
+        .collect();
+    collected.push(element);
+    for element in upstream {
+        collect_upstream(pipeline, element, collected);
+    }
+}
+
+/// Loop a playlist by queueing its clips again while the last one plays
+///
+/// `concat` only forwards seeks to the clip it plays, so segment seeks would
+/// repeat the last clip. Instead, every time the last clip becomes active the
+/// whole playlist is appended behind it, and clips that finished are retired.
+pub(crate) fn setup_playlist_looping(
+    factory: &gst_rtsp::RTSPMediaFactory,
+    server_config: &ServerConfig,
+    clips: Vec<String>,
+    sessions: SharedSessions,
+    metrics: Arc<dyn MetricsReporter>,
+    draining: Arc<AtomicBool>,
+) {
+    let server_config = server_config.clone();
+    factory.connect_media_configure(move |_factory, media| {
+        let element = media.element();
+        let Some(pipeline) = element.downcast_ref::<gstreamer::Bin>() else {
+            return;
+        };
+
+        let playlist_loop = Arc::new(PlaylistLoop {
+            pipeline: pipeline.downgrade(),
+            server_config: server_config.clone(),
+            clips: clips.clone(),
+            sessions: sessions.clone(),
+            metrics: metrics.clone(),
+            draining: draining.clone(),
+            state: Mutex::new(LoopState::default()),
+        });
+        for concat in CONCATS
+            .into_iter()
+            .filter_map(|name| pipeline.by_name(name))
+        {
+            let playlist_loop = playlist_loop.clone();
+            // Notified from a streaming thread, which must not change the pipeline
+            concat.connect_notify(Some("active-pad"), move |_concat, _pspec| {
+                let playlist_loop = playlist_loop.clone();
+                glib::idle_add_once(move || playlist_loop.advance());
+            });
+        }
+    });
+}
+
+/// Clips and counters of one looping playlist media
+struct PlaylistLoop {
+    pipeline: glib::WeakRef<gstreamer::Bin>,
+    server_config: ServerConfig,
+    clips: Vec<String>,
+    sessions: SharedSessions,
+    metrics: Arc<dyn MetricsReporter>,
+    draining: Arc<AtomicBool>,
+    state: Mutex<LoopState>,
+}
+
+#[derive(Default)]
+struct LoopState {
+    loops: u32,
+    /// `vcat` pad of the first clip of the last appended iteration
+    next_iteration: Option<gstreamer::Pad>,
+}
+
+impl PlaylistLoop {
+    /// React to a `concat` switching clips: count iterations, retire and append clips
+    fn advance(&self) {
+        let Some(pipeline) = self.pipeline.upgrade() else {
+            return;
+        };
+        let concats: Vec<_> = CONCATS
+            .into_iter()
+            .filter_map(|name| pipeline.by_name(name))
+            .collect();
+        let Some(vcat) = concats.first() else {
+            return;
+        };
+        let Some(active) = vcat.property::<Option<gstreamer::Pad>>("active-pad") else {
+            return;
+        };
+        let mount_point = self.server_config.mount_point();
+        let mut state = self.state.lock().unwrap();
+
+        if state.next_iteration.as_ref() == Some(&active) {
+            state.next_iteration = None;
+            state.loops += 1;
+            if let Some(session) = self.sessions.lock().unwrap().get_mut(mount_point) {
+                session.add_loop();
+            }
+            self.metrics.report_loop_completed(mount_point);
+        }
+
+        // Pads of one clip share their position on every concat, and the clip's
+        // demuxer feeds all of them, so it goes once every concat moved past it
+        if let Some(played) = concats.iter().map(active_position).min().flatten() {
+            let finished: Vec<_> = concats
+                .iter()
+                .flat_map(|concat| {
+                    concat
+                        .sink_pads()
+                        .into_iter()
+                        .take(played)
+                        .map(move |pad| (concat.clone(), pad))
+                })
+                .collect();
+            if !finished.is_empty() {
+                retire_clips(pipeline.clone(), finished);
+            }
+        }
+
+        let playing_last = vcat.sink_pads().last() == Some(&active);
+        if !playing_last || self.draining.load(Ordering::Relaxed) {
+            return;
+        }
+        if !self.server_config.continue_looping(state.loops) {
+            tracing::info!(
+                mount_point = %mount_point,
+                loops = state.loops,
+                "Maximum loop count reached, ending stream"
+            );
+            return;
+        }
+        for (index, clip) in self.clips.iter().enumerate() {
+            match attach_clip(&pipeline, clip) {
+                Ok(linked) if index == 0 => {
+                    state.next_iteration = linked
+                        .into_iter()
+                        .find(|(concat, _)| concat == vcat)
+                        .map(|(_, pad)| pad);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(mount_point = %mount_point, "Playlist loop failed: {}", e);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Index of the sink pad a `concat` plays, None before it started
+fn active_position(concat: &gstreamer::Element) -> Option<usize> {
+    let active = concat.property::<Option<gstreamer::Pad>>("active-pad")?;
+    concat.sink_pads().iter().position(|pad| pad == &active)
+}
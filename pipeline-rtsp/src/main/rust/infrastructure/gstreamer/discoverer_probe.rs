@@ -0,0 +1,195 @@
+use std::path::Path;
+use std::time::Duration;
+
+use gstreamer_pbutils as gst_pbutils;
+use gstreamer_pbutils::prelude::*;
+
+use crate::domain::errors::{DomainError, Result};
+use crate::domain::ports::MediaProbe;
+use crate::domain::value_objects::{AudioCodec, ContainerFormat, MediaInfo, VideoCodec};
+
+/// Media probe backed by GstDiscoverer
+pub struct DiscovererProbe {
+    timeout: Duration,
+}
+
+impl DiscovererProbe {
+    pub fn new() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl Default for DiscovererProbe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MediaProbe for DiscovererProbe {
+    fn probe(&self, path: &Path) -> Result<MediaInfo> {
+        let probe_error = |e: glib::Error| DomainError::MediaProbeFailed(e.to_string());
+
+        let path = path
+            .canonicalize()
+            .map_err(|_| DomainError::InvalidPath(path.to_path_buf()))?;
+        let uri = glib::filename_to_uri(&path, None).map_err(probe_error)?;
+
+        let discoverer = gst_pbutils::Discoverer::new(gstreamer::ClockTime::from_nseconds(
+            self.timeout.as_nanos() as u64,
+        ))
+        .map_err(probe_error)?;
+        let info = discoverer.discover_uri(&uri).map_err(probe_error)?;
+
+        let container_caps = info
+            .stream_info()
+            .and_then(|stream| stream.caps())
+            .ok_or_else(|| DomainError::MediaProbeFailed("no container detected".to_string()))?;
+        let container = container_for_caps(caps_name(&container_caps))?;
+
+        let video = info
+            .video_streams()
+            .into_iter()
+            .next()
+            .ok_or_else(|| DomainError::MediaProbeFailed("no video stream".to_string()))?;
+        let video_caps = video
+            .caps()
+            .ok_or_else(|| DomainError::MediaProbeFailed("video stream has no caps".to_string()))?;
+        // Other codecs can still be decoded when the mount transcodes
+        let media_info = match video_codec_for_caps(caps_name(&video_caps)) {
+            Ok(video_codec) => MediaInfo::new(container, video_codec),
+            Err(DomainError::UnsupportedCodec(_)) => {
+                MediaInfo::with_unsupported_codec(container, caps_name(&video_caps))
+            }
+            Err(e) => return Err(e),
+        };
+
+        let framerate = video.framerate();
+        let mut media_info = media_info
+            .with_resolution(video.width(), video.height())
+            .with_framerate(framerate.numer() as u32, framerate.denom() as u32);
+
+        // Only the first supported audio track can be served
+        let audio_codec = info.audio_streams().iter().find_map(|audio| {
+            let caps = audio.caps()?;
+            let structure = caps.structure(0)?;
+            audio_codec_for_caps(structure.name(), structure.get::<i32>("mpegversion").ok())
+        });
+        if let Some(audio_codec) = audio_codec {
+            media_info = media_info.with_audio_codec(audio_codec);
+        }
+
+        if let Some(duration) = info.duration() {
+            media_info = media_info.with_duration(Duration::from_nanos(duration.nseconds()));
+        }
+
+        Ok(media_info)
+    }
+}
+
+fn caps_name(caps: &gstreamer::Caps) -> &str {
+    caps.structure(0)
+        .map_or("", |structure| structure.name().as_str())
+}
+
+fn container_for_caps(name: &str) -> Result<ContainerFormat> {
+    match name {
+        "video/quicktime" => Ok(ContainerFormat::MP4),
+        "video/x-matroska" => Ok(ContainerFormat::MKV),
+        "video/mpegts" => Ok(ContainerFormat::MpegTs),
+        "video/webm" => Ok(ContainerFormat::WebM),
+        "video/x-flv" => Ok(ContainerFormat::Flv),
+        // Without a container the top-level stream is the bitstream itself
+        "video/x-h264" | "video/x-h265" => Ok(ContainerFormat::RawElementaryStream),
+        other => Err(DomainError::UnsupportedContainer(other.to_string())),
+    }
+}
+
+fn video_codec_for_caps(name: &str) -> Result<VideoCodec> {
+    match name {
+        "video/x-h264" => Ok(VideoCodec::H264),
+        "video/x-h265" => Ok(VideoCodec::H265),
+        "video/x-vp8" => Ok(VideoCodec::VP8),
+        "video/x-vp9" => Ok(VideoCodec::VP9),
+        "video/x-av1" => Ok(VideoCodec::AV1),
+        "image/jpeg" => Ok(VideoCodec::MJPEG),
+        other => Err(DomainError::UnsupportedCodec(other.to_string())),
+    }
+}
+
+fn audio_codec_for_caps(name: &str, mpeg_version: Option<i32>) -> Option<AudioCodec> {
+    match (name, mpeg_version) {
+        // MPEG-1 audio is MP3, MPEG-2/4 is AAC
+        ("audio/mpeg", Some(2 | 4)) => Some(AudioCodec::AAC),
+        ("audio/x-opus", _) => Some(AudioCodec::Opus),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_for_caps() {
+        assert_eq!(
+            container_for_caps("video/quicktime").unwrap(),
+            ContainerFormat::MP4
+        );
+        assert_eq!(
+            container_for_caps("video/x-matroska").unwrap(),
+            ContainerFormat::MKV
+        );
+        assert_eq!(
+            container_for_caps("video/mpegts").unwrap(),
+            ContainerFormat::MpegTs
+        );
+        assert_eq!(
+            container_for_caps("video/x-h264").unwrap(),
+            ContainerFormat::RawElementaryStream
+        );
+        assert!(matches!(
+            container_for_caps("video/x-msvideo"),
+            Err(DomainError::UnsupportedContainer(_))
+        ));
+    }
+
+    #[test]
+    fn test_video_codec_for_caps() {
+        assert_eq!(
+            video_codec_for_caps("video/x-h264").unwrap(),
+            VideoCodec::H264
+        );
+        assert_eq!(
+            video_codec_for_caps("video/x-h265").unwrap(),
+            VideoCodec::H265
+        );
+        assert_eq!(
+            video_codec_for_caps("image/jpeg").unwrap(),
+            VideoCodec::MJPEG
+        );
+        assert!(matches!(
+            video_codec_for_caps("video/x-theora"),
+            Err(DomainError::UnsupportedCodec(_))
+        ));
+    }
+
+    #[test]
+    fn test_audio_codec_for_caps() {
+        assert_eq!(
+            audio_codec_for_caps("audio/mpeg", Some(4)),
+            Some(AudioCodec::AAC)
+        );
+        assert_eq!(audio_codec_for_caps("audio/mpeg", Some(1)), None);
+        assert_eq!(
+            audio_codec_for_caps("audio/x-opus", None),
+            Some(AudioCodec::Opus)
+        );
+    }
+}
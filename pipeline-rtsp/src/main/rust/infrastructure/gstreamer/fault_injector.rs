@@ -0,0 +1,214 @@
+use std::ops::ControlFlow;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use gstreamer::prelude::*;
+use gstreamer_rtsp_server as gst_rtsp;
+use gstreamer_rtsp_server::prelude::*;
+
+use super::live_clock;
+use crate::domain::value_objects::Fault;
+
+/// What happens to the next RTP packet of a mount
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Verdict {
+    Pass,
+    Drop,
+    HoldBack(Duration),
+}
+
+/// The timed fault in effect on a mount
+#[derive(Debug, Default)]
+struct FaultState {
+    active: Option<(Fault, Instant)>,
+    /// Percent points owed towards the next dropped packet
+    drop_credit: u32,
+}
+
+impl FaultState {
+    fn verdict(&mut self, now: Instant) -> Verdict {
+        let Some((fault, until)) = self.active else {
+            return Verdict::Pass;
+        };
+        if now >= until {
+            self.active = None;
+            return Verdict::Pass;
+        }
+
+        match fault {
+            Fault::Freeze { .. } => Verdict::Drop,
+            // Spread drops evenly, so short windows still lose the requested share
+            Fault::DropPackets { percent, .. } => {
+                self.drop_credit += u32::from(percent);
+                if self.drop_credit >= 100 {
+                    self.drop_credit -= 100;
+                    Verdict::Drop
+                } else {
+                    Verdict::Pass
+                }
+            }
+            Fault::Latency { delay, .. } => Verdict::HoldBack(delay),
+            Fault::Eos | Fault::TeardownClients => Verdict::Pass,
+        }
+    }
+}
+
+/// Timed faults of one mount, applied by probes on its payloaders
+#[derive(Clone, Default)]
+pub(crate) struct FaultInjector {
+    state: Arc<Mutex<FaultState>>,
+    /// Clock waits of packets held back, so a flush or teardown can cut them short
+    waiting: Arc<Mutex<Vec<gstreamer::ClockId>>>,
+}
+
+impl FaultInjector {
+    /// Filter the RTP output of every media the factory creates
+    pub(crate) fn attach(&self, factory: &gst_rtsp::RTSPMediaFactory) {
+        let injector = self.clone();
+        factory.connect_media_configure(move |_factory, media| {
+            for pad in live_clock::payloader_pads(&media.element()) {
+                let filtering = injector.clone();
+                pad.add_probe(
+                    gstreamer::PadProbeType::BUFFER | gstreamer::PadProbeType::BUFFER_LIST,
+                    move |pad, info| filtering.filter(pad, info),
+                );
+                // Flushing waits for the streaming thread, which may be holding a packet
+                let flushing = injector.clone();
+                pad.add_probe(gstreamer::PadProbeType::EVENT_FLUSH, move |_pad, info| {
+                    if let Some(gstreamer::PadProbeData::Event(event)) = &info.data {
+                        if event.type_() == gstreamer::EventType::FlushStart {
+                            flushing.release_held();
+                        }
+                    }
+                    gstreamer::PadProbeReturn::Ok
+                });
+            }
+        });
+    }
+
+    /// Put a timed fault in effect, replacing the one running
+    pub(crate) fn start(&self, fault: Fault) {
+        let Some(duration) = fault.duration() else {
+            return;
+        };
+        let mut state = self.state.lock().unwrap();
+        state.active = Some((fault, Instant::now() + duration));
+        state.drop_credit = 0;
+    }
+
+    /// End the fault in effect and send held packets on, before the mount's media goes down
+    pub(crate) fn clear(&self) {
+        self.state.lock().unwrap().active = None;
+        self.release_held();
+    }
+
+    fn release_held(&self) {
+        for id in self.waiting.lock().unwrap().drain(..) {
+            id.unschedule();
+        }
+    }
+
+    fn verdict(&self) -> Verdict {
+        self.state.lock().unwrap().verdict(Instant::now())
+    }
+
+    fn filter(
+        &self,
+        pad: &gstreamer::Pad,
+        info: &mut gstreamer::PadProbeInfo,
+    ) -> gstreamer::PadProbeReturn {
+        match &mut info.data {
+            Some(gstreamer::PadProbeData::Buffer(buffer)) => match self.verdict() {
+                Verdict::Pass => {}
+                Verdict::Drop => return gstreamer::PadProbeReturn::Drop,
+                Verdict::HoldBack(delay) => self.hold_back(pad, buffer, delay),
+            },
+            Some(gstreamer::PadProbeData::BufferList(list)) => {
+                let mut held = false;
+                list.make_mut()
+                    .foreach_mut(|buffer, _index| match self.verdict() {
+                        Verdict::Pass => ControlFlow::Continue(Some(buffer)),
+                        Verdict::Drop => ControlFlow::Continue(None),
+                        // Packets of one list go out together, so they are held once
+                        Verdict::HoldBack(delay) => {
+                            if !held {
+                                self.hold_back(pad, &buffer, delay);
+                                held = true;
+                            }
+                            ControlFlow::Continue(Some(buffer))
+                        }
+                    });
+                if list.is_empty() {
+                    return gstreamer::PadProbeReturn::Drop;
+                }
+            }
+            _ => {}
+        }
+        gstreamer::PadProbeReturn::Ok
+    }
+
+    /// Block the streaming thread until `delay` after the buffer is due
+    ///
+    /// The sinks sync to the same clock, so every later buffer is late by the
+    /// same amount. The wait ends early once it is unscheduled.
+    fn hold_back(&self, pad: &gstreamer::Pad, buffer: &gstreamer::BufferRef, delay: Duration) {
+        let Some(due) = live_clock::clock_time(pad, buffer) else {
+            return;
+        };
+        let Some(clock) = pad.parent_element().and_then(|element| element.clock()) else {
+            return;
+        };
+        let delay = gstreamer::ClockTime::from_nseconds(delay.as_nanos() as u64);
+        let id: gstreamer::ClockId = clock.new_single_shot_id(due + delay).into();
+        self.waiting.lock().unwrap().push(id.clone());
+        let _ = id.wait();
+        self.waiting
+            .lock()
+            .unwrap()
+            .retain(|waiting| waiting != &id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_with(fault: Fault) -> (FaultState, Instant) {
+        let now = Instant::now();
+        let state = FaultState {
+            active: Some((fault, now + fault.duration().unwrap())),
+            drop_credit: 0,
+        };
+        (state, now)
+    }
+
+    #[test]
+    fn test_drops_requested_share() {
+        let fault = Fault::drop_packets(25, Duration::from_secs(1)).unwrap();
+        let (mut state, now) = state_with(fault);
+
+        let dropped = (0..100)
+            .filter(|_| state.verdict(now) == Verdict::Drop)
+            .count();
+        assert_eq!(dropped, 25);
+    }
+
+    #[test]
+    fn test_fault_expires() {
+        let fault = Fault::freeze(Duration::from_secs(1)).unwrap();
+        let (mut state, now) = state_with(fault);
+
+        assert_eq!(state.verdict(now), Verdict::Drop);
+        assert_eq!(state.verdict(now + Duration::from_secs(1)), Verdict::Pass);
+        assert!(state.active.is_none());
+    }
+
+    #[test]
+    fn test_latency_holds_packets_back() {
+        let delay = Duration::from_millis(300);
+        let fault = Fault::latency(delay, Duration::from_secs(1)).unwrap();
+        let (mut state, now) = state_with(fault);
+
+        assert_eq!(state.verdict(now), Verdict::HoldBack(delay));
+    }
+}
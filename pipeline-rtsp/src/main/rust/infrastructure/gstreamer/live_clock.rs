@@ -0,0 +1,218 @@
+use std::ops::ControlFlow;
+use std::time::Duration;
+
+use gstreamer::prelude::*;
+use gstreamer_rtp as gst_rtp;
+use gstreamer_rtp::prelude::*;
+use gstreamer_rtsp_server as gst_rtsp;
+use gstreamer_rtsp_server::prelude::*;
+
+use super::rtp_extension::{self, NTP64_EXTENSION_ID, NTP64_EXTENSION_URI, ONVIF_REPLAY_PROFILE};
+use crate::domain::value_objects::{HeaderExtension, ServerConfig};
+
+/// Clock a live mount's media against the system's wall clock
+///
+/// With a realtime clock, base time plus running time is the Unix time a frame
+/// is captured at. rtpbin reads NTP time for its sender reports from the same
+/// wall clock and maps it to RTP time by capture time, so receivers get the
+/// NTP ↔ RTP mapping the header extensions carry. Looping keeps running time
+/// increasing, so the mapping holds across iterations.
+pub(crate) fn setup_live(factory: &gst_rtsp::RTSPMediaFactory, server_config: &ServerConfig) {
+    // A clock of our own; changing the shared system clock would affect every mount
+    let clock = glib::Object::builder::<gstreamer::SystemClock>()
+        .property("clock-type", gstreamer::ClockType::Realtime)
+        .build();
+    factory.set_clock(Some(&clock));
+
+    let extension = server_config.header_extension();
+    factory.connect_media_configure(move |_factory, media| {
+        match extension {
+            Some(HeaderExtension::Ntp64) => add_ntp64(&media.element()),
+            Some(HeaderExtension::OnvifReplay) => stamp_onvif_replay(&media.element()),
+            None => {}
+        }
+        media.connect_prepared(|media| {
+            if let Some(rtpbin) = find_rtpbin(&media.element()) {
+                rtpbin.set_property_from_str("ntp-time-source", "ntp");
+                rtpbin.set_property("rtcp-sync-send-time", false);
+            }
+        });
+    });
+}
+
+/// The media pipeline's `rtpbin`, a sibling of the launch bin
+fn find_rtpbin(element: &gstreamer::Element) -> Option<gstreamer::Element> {
+    let pipeline = element.parent()?.downcast::<gstreamer::Bin>().ok()?;
+    pipeline.children().into_iter().find(|child| {
+        child
+            .factory()
+            .is_some_and(|factory| factory.name() == "rtpbin")
+    })
+}
+
+/// A media's `payN` payloaders, in stream order
+fn payloaders(element: &gstreamer::Element) -> Vec<gstreamer::Element> {
+    let Some(bin) = element.downcast_ref::<gstreamer::Bin>() else {
+        return Vec::new();
+    };
+
+    (0..)
+        .map_while(|index| bin.by_name(&format!("pay{}", index)))
+        .collect()
+}
+
+/// Source pads of a media's `payN` payloaders, in stream order
+pub(super) fn payloader_pads(element: &gstreamer::Element) -> Vec<gstreamer::Pad> {
+    payloaders(element)
+        .into_iter()
+        .filter_map(|payloader| payloader.static_pad("src"))
+        .collect()
+}
+
+/// Have every payloader write the RFC 6051 extension with `rtphdrextntp64`
+///
+/// The extension takes its time from an NTP reference timestamp on the
+/// payloader's input and adds itself to the caps, which the server turns into
+/// `a=extmap`.
+fn add_ntp64(element: &gstreamer::Element) {
+    let ntp_caps = gstreamer::Caps::new_empty_simple("timestamp/x-ntp");
+
+    for payloader in payloaders(element) {
+        let Some(payloader) = payloader.downcast_ref::<gst_rtp::RTPBasePayload>() else {
+            continue;
+        };
+        let Some(extension) = gst_rtp::RTPHeaderExtension::create_from_uri(NTP64_EXTENSION_URI)
+        else {
+            tracing::warn!("rtphdrextntp64 is not available, packets carry no NTP timestamp");
+            return;
+        };
+        extension.set_id(u32::from(NTP64_EXTENSION_ID));
+        payloader.emit_add_extension(&extension);
+
+        let Some(pad) = payloader.static_pad("sink") else {
+            continue;
+        };
+        let ntp_caps = ntp_caps.clone();
+        pad.add_probe(
+            gstreamer::PadProbeType::BUFFER | gstreamer::PadProbeType::BUFFER_LIST,
+            move |pad, info| {
+                match &mut info.data {
+                    Some(gstreamer::PadProbeData::Buffer(buffer)) => {
+                        attach_ntp_time(pad, buffer, &ntp_caps);
+                    }
+                    Some(gstreamer::PadProbeData::BufferList(list)) => {
+                        list.make_mut().foreach_mut(|mut buffer, _index| {
+                            attach_ntp_time(pad, &mut buffer, &ntp_caps);
+                            ControlFlow::Continue(Some(buffer))
+                        });
+                    }
+                    _ => {}
+                }
+                gstreamer::PadProbeReturn::Ok
+            },
+        );
+    }
+}
+
+/// Tag a buffer with its capture time since the NTP epoch
+fn attach_ntp_time(
+    pad: &gstreamer::Pad,
+    buffer: &mut gstreamer::Buffer,
+    ntp_caps: &gstreamer::Caps,
+) {
+    let Some(capture_time) = clock_time(pad, buffer) else {
+        return;
+    };
+    let ntp_time = rtp_extension::ntp_time(Duration::from_nanos(capture_time.nseconds()));
+    gstreamer::ReferenceTimestampMeta::add(
+        buffer.make_mut(),
+        ntp_caps,
+        gstreamer::ClockTime::from_nseconds(ntp_time.as_nanos() as u64),
+        gstreamer::ClockTime::NONE,
+    );
+}
+
+/// Add the ONVIF replay extension to every packet leaving the `payN` payloaders
+fn stamp_onvif_replay(element: &gstreamer::Element) {
+    for pad in payloader_pads(element) {
+        pad.add_probe(
+            gstreamer::PadProbeType::BUFFER | gstreamer::PadProbeType::BUFFER_LIST,
+            |pad, info| {
+                match &mut info.data {
+                    Some(gstreamer::PadProbeData::Buffer(buffer)) => {
+                        stamp_onvif(pad, buffer.make_mut());
+                    }
+                    Some(gstreamer::PadProbeData::BufferList(list)) => {
+                        list.make_mut().foreach_mut(|mut buffer, _index| {
+                            stamp_onvif(pad, buffer.make_mut());
+                            ControlFlow::Continue(Some(buffer))
+                        });
+                    }
+                    _ => {}
+                }
+                gstreamer::PadProbeReturn::Ok
+            },
+        );
+    }
+}
+
+/// Write the replay extension into an RTP packet
+///
+/// Packets that already carry an extension are left alone, since RTP allows
+/// only one extension block per packet.
+fn stamp_onvif(pad: &gstreamer::Pad, buffer: &mut gstreamer::BufferRef) -> Option<()> {
+    let capture_time = Duration::from_nanos(clock_time(pad, buffer)?.nseconds());
+    let flags = buffer.flags();
+    let data = rtp_extension::onvif_replay_data(
+        rtp_extension::ntp_timestamp(capture_time),
+        !flags.contains(gstreamer::BufferFlags::DELTA_UNIT),
+        flags.contains(gstreamer::BufferFlags::DISCONT),
+    );
+
+    let rtp = gst_rtp::RTPBuffer::from_buffer_writable(buffer).ok()?;
+    if rtp.is_extension() {
+        return None;
+    }
+    // SAFETY: the packet is mapped writable, and after a successful
+    // set_extension_data the extension block holds `data.len()` bytes
+    unsafe {
+        let rtp_ptr = rtp.as_mut_ptr();
+        let words = (data.len() / 4) as u16;
+        if gst_rtp::ffi::gst_rtp_buffer_set_extension_data(rtp_ptr, ONVIF_REPLAY_PROFILE, words)
+            == glib::ffi::GFALSE
+        {
+            return None;
+        }
+        let mut bits = 0u16;
+        let mut extension = std::ptr::null_mut::<u8>();
+        let mut length = 0;
+        if gst_rtp::ffi::gst_rtp_buffer_get_extension_data(
+            rtp_ptr,
+            &mut bits,
+            &mut extension as *mut *mut u8 as *mut u8,
+            &mut length,
+        ) == glib::ffi::GFALSE
+        {
+            return None;
+        }
+        std::ptr::copy_nonoverlapping(data.as_ptr(), extension, data.len());
+    }
+    Some(())
+}
+
+/// Clock time a buffer is due: the element's base time plus the buffer's running time
+///
+/// With the realtime clock of a live mount this is the buffer's Unix capture time.
+pub(super) fn clock_time(
+    pad: &gstreamer::Pad,
+    buffer: &gstreamer::BufferRef,
+) -> Option<gstreamer::ClockTime> {
+    let pts = buffer.pts()?;
+    let segment_event = pad.sticky_event::<gstreamer::event::Segment>(0)?;
+    let segment = segment_event
+        .segment()
+        .downcast_ref::<gstreamer::ClockTime>()?;
+    let running_time = segment.to_running_time(pts)?;
+    let base_time = pad.parent_element()?.base_time()?;
+    Some(base_time + running_time)
+}
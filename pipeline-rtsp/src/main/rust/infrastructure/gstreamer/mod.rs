@@ -1,5 +1,15 @@
+mod client_tracker;
+mod concat_clips;
+mod discoverer_probe;
+mod fault_injector;
+mod live_clock;
 mod pipeline_builder;
+mod recording;
+mod rtp_extension;
+mod rtsp_auth;
 mod rtsp_server_adapter;
+mod snapshot;
 
+pub use discoverer_probe::DiscovererProbe;
 pub use pipeline_builder::PipelineBuilder;
 pub use rtsp_server_adapter::GStreamerRtspServer;
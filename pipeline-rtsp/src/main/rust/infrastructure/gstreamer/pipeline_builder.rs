@@ -1,35 +1,436 @@
-use crate::domain::value_objects::{ContainerFormat, StreamConfig, VideoCodec};
+use std::path::Path;
+
+use crate::domain::value_objects::{
+    AudioCodec, ContainerFormat, MediaInfo, PatternOverlay, Playlist, StreamConfig, TestPattern,
+    TranscodeProfile, VideoCodec,
+};
 
 pub struct PipelineBuilder;
 
 impl PipelineBuilder {
     /// Convert domain config to GStreamer pipeline string
+    ///
+    /// Demuxer pads are linked by name, so the video branch never picks up
+    /// an audio pad and the audio branch is only built when requested.
+    /// Raw elementary streams skip the demuxer and go straight to the parser.
     pub fn build_launch_string(config: &StreamConfig) -> String {
-        let demuxer = Self::demuxer_for_container(config.container());
-        let parser = Self::parser_for_codec(config.codec());
-        let payloader = Self::payloader_for_codec(config.codec());
+        if let Some(pattern) = config.test_pattern() {
+            return Self::build_test_pattern_launch_string(config, pattern);
+        }
+        if let Some(playlist) = config.playlist() {
+            return Self::build_playlist_launch_string(config, playlist);
+        }
+        if config.schedule().is_some() {
+            return Self::build_scheduled_launch_string(config, config.scheduled_source());
+        }
 
-        format!(
-            "( filesrc location={} ! {} ! {} ! {} name=pay0 pt={} )",
-            config.source_path().display(),
+        let container = config.container();
+        let output_codec = config.output_codec();
+        let source = format!("filesrc location={}", Self::location(config.source_path()));
+
+        let mut video_chain = vec!["queue".to_string()];
+        if let Some(profile) = config.transcode() {
+            video_chain.push("decodebin ! videoconvert".to_string());
+            video_chain.push(Self::encoder_for_profile(profile, false));
+        }
+        if let Some(parser) = Self::parser_for_codec(output_codec) {
+            video_chain.push(parser.to_string());
+        }
+        video_chain.push(format!(
+            "{}{} name=pay0 pt={}",
+            Self::recording_tee(config, "vrec"),
+            Self::payloader_for_codec(output_codec),
+            config.rtp_payload_type()
+        ));
+        let video_branch = video_chain.join(" ! ");
+
+        let Some(demuxer) = Self::demuxer_for_container(container) else {
+            let mut launch = format!("{} ! {}", source, video_branch);
+            if let Some(recording) = Self::recording_branch(config) {
+                launch.push(' ');
+                launch.push_str(&recording);
+            }
+            return format!("( {} )", launch);
+        };
+
+        let mut launch = format!(
+            "{} ! {} name=demux {} ! {}",
+            source,
             demuxer,
-            parser,
-            payloader,
+            Self::demux_pad(
+                "demux",
+                container,
+                "video",
+                Self::source_caps(config.codec(), config.media_info())
+            ),
+            video_branch
+        );
+
+        if let Some(audio_codec) = config.audio_codec() {
+            launch.push_str(&format!(
+                " {} ! queue ! {} ! {}{} name=pay1 pt={}",
+                Self::demux_pad(
+                    "demux",
+                    container,
+                    "audio",
+                    Self::caps_for_audio_codec(audio_codec)
+                ),
+                Self::parser_for_audio_codec(audio_codec),
+                Self::recording_tee(config, "arec"),
+                Self::payloader_for_audio_codec(audio_codec),
+                config.audio_rtp_payload_type()
+            ));
+        }
+        if let Some(recording) = Self::recording_branch(config) {
+            launch.push(' ');
+            launch.push_str(&recording);
+        }
+
+        format!("( {} )", launch)
+    }
+
+    /// Live `videotestsrc`, optionally with a clock or the running time burned in
+    ///
+    /// Generated frames are raw, so they always go through the encoder.
+    fn build_test_pattern_launch_string(config: &StreamConfig, pattern: &TestPattern) -> String {
+        let output_codec = config.output_codec();
+        let (width, height) = pattern.resolution();
+
+        let mut chain = vec![
+            format!("videotestsrc is-live=true pattern={}", pattern.pattern()),
+            format!(
+                "video/x-raw,width={},height={},framerate={}/1",
+                width,
+                height,
+                pattern.fps()
+            ),
+        ];
+        if let Some(overlay) = pattern.overlay() {
+            chain.push(Self::overlay_element(overlay).to_string());
+        }
+        chain.push("videoconvert".to_string());
+        chain.push(Self::encoder_for_profile(
+            &config.transcode().cloned().unwrap_or_default(),
+            true,
+        ));
+        if let Some(parser) = Self::parser_for_codec(output_codec) {
+            chain.push(parser.to_string());
+        }
+        chain.push(format!(
+            "{}{} name=pay0 pt={}",
+            Self::recording_tee(config, "vrec"),
+            Self::payloader_for_codec(output_codec),
+            config.rtp_payload_type()
+        ));
+
+        let mut launch = chain.join(" ! ");
+        if let Some(recording) = Self::recording_branch(config) {
+            launch.push(' ');
+            launch.push_str(&recording);
+        }
+        format!("( {} )", launch)
+    }
+
+    fn overlay_element(overlay: PatternOverlay) -> &'static str {
+        match overlay {
+            PatternOverlay::Clock => "clockoverlay",
+            PatternOverlay::Time => "timeoverlay",
+        }
+    }
+
+    /// Join the playlist's clips with `concat`, which keeps timestamps running across clips
+    ///
+    /// Every play of a clip gets its own source and demuxer. Clips are parsed
+    /// before joining, or decoded when transcoding so differing codecs can share
+    /// one encoder after the join.
+    fn build_playlist_launch_string(config: &StreamConfig, playlist: &Playlist) -> String {
+        let clips = playlist
+            .play_order()
+            .into_iter()
+            .map(|item| (item.path().as_path(), item.media_info()))
+            .collect();
+        Self::build_concat_launch_string(config, clips)
+    }
+
+    /// Scheduled mounts play one clip through `concat` so later clips can be appended live
+    pub fn build_scheduled_launch_string(config: &StreamConfig, source: &Path) -> String {
+        Self::build_concat_launch_string(config, vec![(source, None)])
+    }
+
+    fn build_concat_launch_string(
+        config: &StreamConfig,
+        clips: Vec<(&Path, Option<&MediaInfo>)>,
+    ) -> String {
+        let output_codec = config.output_codec();
+
+        let mut video_chain = vec!["concat name=vcat".to_string(), "queue".to_string()];
+        if let Some(profile) = config.transcode() {
+            video_chain.push(Self::encoder_for_profile(profile, false));
+            if let Some(parser) = Self::parser_for_codec(output_codec) {
+                video_chain.push(parser.to_string());
+            }
+        }
+        video_chain.push(format!(
+            "{}{} name=pay0 pt={}",
+            Self::recording_tee(config, "vrec"),
+            Self::payloader_for_codec(output_codec),
             config.rtp_payload_type()
+        ));
+        let mut launch = vec![video_chain.join(" ! ")];
+
+        if let Some(audio_codec) = config.audio_codec() {
+            launch.push(format!(
+                "concat name=acat ! queue ! {}{} name=pay1 pt={}",
+                Self::recording_tee(config, "arec"),
+                Self::payloader_for_audio_codec(audio_codec),
+                config.audio_rtp_payload_type()
+            ));
+        }
+        launch.extend(Self::recording_branch(config));
+
+        for (index, (path, info)) in clips.into_iter().enumerate() {
+            launch.extend(Self::clip_launch(
+                config,
+                path,
+                info,
+                &format!("demux{}", index),
+                ("vcat.", "acat."),
+            ));
+        }
+
+        format!("( {} )", launch.join(" "))
+    }
+
+    /// Description of a bin playing one more clip on a scheduled mount
+    ///
+    /// The bin's branches end in `identity` elements named `vout` and `aout`,
+    /// whose source pads get linked to the running pipeline's `concat` elements.
+    pub fn build_clip_description(config: &StreamConfig, source: &Path) -> String {
+        Self::clip_description(config, source, None)
+    }
+
+    /// Descriptions of bins replaying a playlist from its first clip, in play order
+    ///
+    /// Looping playlists append these to the running `concat` elements before
+    /// the last clip ends, since `concat` only seeks within the clip it plays.
+    pub fn build_playlist_clip_descriptions(
+        config: &StreamConfig,
+        playlist: &Playlist,
+    ) -> Vec<String> {
+        playlist
+            .play_order()
+            .into_iter()
+            .map(|item| Self::clip_description(config, item.path(), item.media_info()))
+            .collect()
+    }
+
+    fn clip_description(config: &StreamConfig, path: &Path, info: Option<&MediaInfo>) -> String {
+        Self::clip_launch(
+            config,
+            path,
+            info,
+            "demux",
+            ("identity name=vout", "identity name=aout"),
+        )
+        .join(" ")
+    }
+
+    /// Source, demuxer and per-kind branches of one clip, ending in the given sinks
+    fn clip_launch(
+        config: &StreamConfig,
+        path: &Path,
+        info: Option<&MediaInfo>,
+        demux: &str,
+        (video_sink, audio_sink): (&str, &str),
+    ) -> Vec<String> {
+        // Unprobed clips are assumed to match the mount's configured format
+        let container = info.map_or(config.container(), MediaInfo::container);
+        let codec = info
+            .and_then(MediaInfo::video_codec)
+            .unwrap_or(config.codec());
+        let source = format!("filesrc location={}", Self::location(path));
+
+        let mut clip_chain = vec!["queue".to_string()];
+        if config.transcode().is_some() {
+            clip_chain.push("decodebin ! videoconvert".to_string());
+        } else if let Some(parser) = Self::parser_for_codec(codec) {
+            clip_chain.push(parser.to_string());
+        }
+        clip_chain.push(video_sink.to_string());
+        let clip_branch = clip_chain.join(" ! ");
+
+        let Some(demuxer) = Self::demuxer_for_container(container) else {
+            return vec![format!("{} ! {}", source, clip_branch)];
+        };
+
+        let mut launch = vec![format!(
+            "{} ! {} name={} {} ! {}",
+            source,
+            demuxer,
+            demux,
+            Self::demux_pad(demux, container, "video", Self::source_caps(codec, info)),
+            clip_branch
+        )];
+
+        if let Some(audio_codec) = config.audio_codec() {
+            launch.push(format!(
+                "{} ! queue ! {} ! {}",
+                Self::demux_pad(
+                    demux,
+                    container,
+                    "audio",
+                    Self::caps_for_audio_codec(audio_codec)
+                ),
+                Self::parser_for_audio_codec(audio_codec),
+                audio_sink
+            ));
+        }
+
+        launch
+    }
+
+    /// One-shot pipeline turning the first frame of `source` into a JPEG in the `snapshot` sink
+    ///
+    /// Frames wider than `max_width` are scaled down with their aspect ratio kept.
+    pub fn build_snapshot_launch_string(source: &str, max_width: u32) -> String {
+        format!(
+            "{} ! decodebin ! videoconvert ! videoscale ! video/x-raw,width=[1,{}],pixel-aspect-ratio=1/1 ! jpegenc ! appsink name=snapshot sync=false",
+            source, max_width
         )
     }
 
-    fn demuxer_for_container(container: &ContainerFormat) -> &'static str {
+    /// Snapshot source of a mount with no running media: one generated frame or its active file
+    pub fn build_snapshot_source(config: &StreamConfig, active_source: &Path) -> String {
+        match config.test_pattern() {
+            Some(pattern) => {
+                let (width, height) = pattern.resolution();
+                format!(
+                    "videotestsrc num-buffers=1 pattern={} ! video/x-raw,width={},height={}",
+                    pattern.pattern(),
+                    width,
+                    height
+                )
+            }
+            None => format!("filesrc location={}", Self::location(active_source)),
+        }
+    }
+
+    /// `tee` ahead of a payloader, splitting the parsed stream off to the recording
+    fn recording_tee(config: &StreamConfig, name: &str) -> String {
+        match config.recording() {
+            Some(_) => format!("tee name={} ! queue ! ", name),
+            None => String::new(),
+        }
+    }
+
+    /// `splitmuxsink` muxing the teed streams into MP4 segments
+    ///
+    /// The location is a fallback; every start of the media gets its own file prefix.
+    fn recording_branch(config: &StreamConfig) -> Option<String> {
+        let recording = config.recording()?;
+        let mut branch = format!(
+            "splitmuxsink name=rec muxer-factory=mp4mux max-size-time={} location={} vrec. ! queue ! rec.video",
+            recording.segment_duration().as_nanos(),
+            Self::location(&recording.directory().join("segment%05d.mp4"))
+        );
+        if config.audio_codec().is_some() {
+            branch.push_str(" arec. ! queue ! rec.audio_0");
+        }
+        Some(branch)
+    }
+
+    /// Path as a launch string value, quoted when it holds more than plain path characters
+    ///
+    /// Unquoted, a space ends the value and a `!` starts a new element.
+    fn location(path: &Path) -> String {
+        let path = path.display().to_string();
+        let plain = path
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/._-+%:@".contains(c));
+        if plain {
+            return path;
+        }
+        format!("\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    fn demuxer_for_container(container: &ContainerFormat) -> Option<&'static str> {
         match container {
-            ContainerFormat::MP4 => "qtdemux",
-            ContainerFormat::MKV => "matroskademux",
+            ContainerFormat::MP4 => Some("qtdemux"),
+            ContainerFormat::MKV | ContainerFormat::WebM => Some("matroskademux"),
+            ContainerFormat::MpegTs => Some("tsdemux"),
+            ContainerFormat::Flv => Some("flvdemux"),
+            ContainerFormat::RawElementaryStream => None,
+        }
+    }
+
+    /// Pad of the named demuxer feeding a branch of the given kind ("video" or "audio")
+    fn demux_pad(demuxer: &str, container: &ContainerFormat, kind: &str, caps: &str) -> String {
+        match container {
+            // tsdemux pad names embed the PID, so select the pad by caps instead
+            ContainerFormat::MpegTs => format!("{}. ! {}", demuxer, caps),
+            // flvdemux has a single pad per kind
+            ContainerFormat::Flv => format!("{}.{}", demuxer, kind),
+            _ => format!("{}.{}_0", demuxer, kind),
+        }
+    }
+
+    /// Caps of the source's video, which need not be the codec served when transcoding
+    fn source_caps<'a>(codec: &VideoCodec, info: Option<&'a MediaInfo>) -> &'a str {
+        info.and_then(MediaInfo::unsupported_video_type)
+            .unwrap_or_else(|| Self::caps_for_codec(codec))
+    }
+
+    fn caps_for_codec(codec: &VideoCodec) -> &'static str {
+        match codec {
+            VideoCodec::H264 => "video/x-h264",
+            VideoCodec::H265 => "video/x-h265",
+            VideoCodec::VP8 => "video/x-vp8",
+            VideoCodec::VP9 => "video/x-vp9",
+            VideoCodec::AV1 => "video/x-av1",
+            VideoCodec::MJPEG => "image/jpeg",
+        }
+    }
+
+    fn caps_for_audio_codec(codec: &AudioCodec) -> &'static str {
+        match codec {
+            AudioCodec::AAC => "audio/mpeg",
+            AudioCodec::Opus => "audio/x-opus",
+        }
+    }
+
+    /// Software encoder plus caps pinning the output profile
+    ///
+    /// B-frames are always disabled; baseline H.264 cannot carry them anyway.
+    /// Live sources are encoded without lookahead, which would otherwise hold
+    /// back frames for over a second.
+    fn encoder_for_profile(profile: &TranscodeProfile, live: bool) -> String {
+        let tune = if live { " tune=zerolatency" } else { "" };
+        match profile.codec() {
+            VideoCodec::H265 => format!(
+                "x265enc bitrate={} key-int-max={} speed-preset=veryfast{} option-string=bframes=0 ! video/x-h265,profile={}",
+                profile.bitrate_kbps(),
+                profile.gop_length(),
+                tune,
+                profile.profile().as_str()
+            ),
+            _ => format!(
+                "x264enc bitrate={} key-int-max={} bframes=0 speed-preset=veryfast{} ! video/x-h264,profile={}",
+                profile.bitrate_kbps(),
+                profile.gop_length(),
+                tune,
+                profile.profile().as_str()
+            ),
         }
     }
 
-    fn parser_for_codec(codec: &VideoCodec) -> &'static str {
+    /// VP8/VP9 frames come out of the demuxer ready to payload
+    fn parser_for_codec(codec: &VideoCodec) -> Option<&'static str> {
         match codec {
-            VideoCodec::H264 => "h264parse config-interval=-1",
-            VideoCodec::H265 => "h265parse config-interval=-1",
+            VideoCodec::H264 => Some("h264parse config-interval=-1"),
+            VideoCodec::H265 => Some("h265parse config-interval=-1"),
+            VideoCodec::AV1 => Some("av1parse"),
+            VideoCodec::MJPEG => Some("jpegparse"),
+            VideoCodec::VP8 | VideoCodec::VP9 => None,
         }
     }
 
@@ -37,6 +438,24 @@ impl PipelineBuilder {
         match codec {
             VideoCodec::H264 => "rtph264pay",
             VideoCodec::H265 => "rtph265pay",
+            VideoCodec::VP8 => "rtpvp8pay",
+            VideoCodec::VP9 => "rtpvp9pay",
+            VideoCodec::AV1 => "rtpav1pay",
+            VideoCodec::MJPEG => "rtpjpegpay",
+        }
+    }
+
+    fn parser_for_audio_codec(codec: &AudioCodec) -> &'static str {
+        match codec {
+            AudioCodec::AAC => "aacparse",
+            AudioCodec::Opus => "opusparse",
+        }
+    }
+
+    fn payloader_for_audio_codec(codec: &AudioCodec) -> &'static str {
+        match codec {
+            AudioCodec::AAC => "rtpmp4gpay",
+            AudioCodec::Opus => "rtpopuspay",
         }
     }
 }
@@ -44,7 +463,11 @@ impl PipelineBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::errors::Result;
+    use crate::domain::ports::MediaProbe;
+    use crate::domain::value_objects::{EncoderProfile, PlaylistItem, RecordingConfig, Schedule};
     use std::path::PathBuf;
+    use std::time::Duration;
 
     #[test]
     fn test_build_h264_mp4_pipeline() {
@@ -68,6 +491,97 @@ mod tests {
         assert!(pipeline.contains("rtph265pay"));
     }
 
+    #[test]
+    fn test_build_vp8_pipeline() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.webm"))
+            .with_container(ContainerFormat::WebM)
+            .with_codec(VideoCodec::VP8);
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.contains("demux.video_0 ! queue ! rtpvp8pay name=pay0 pt=96"));
+    }
+
+    #[test]
+    fn test_build_vp9_pipeline() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.webm"))
+            .with_container(ContainerFormat::WebM)
+            .with_codec(VideoCodec::VP9);
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.contains("queue ! rtpvp9pay name=pay0 pt=96"));
+    }
+
+    #[test]
+    fn test_build_av1_pipeline() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.mkv"))
+            .with_container(ContainerFormat::MKV)
+            .with_codec(VideoCodec::AV1);
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.contains("av1parse ! rtpav1pay name=pay0 pt=96"));
+    }
+
+    #[test]
+    fn test_build_mjpeg_pipeline() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.mkv"))
+            .with_container(ContainerFormat::MKV)
+            .with_codec(VideoCodec::MJPEG);
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.contains("jpegparse ! rtpjpegpay name=pay0 pt=26"));
+    }
+
+    #[test]
+    fn test_build_transcode_pipeline() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.mkv"))
+            .with_container(ContainerFormat::MKV)
+            .with_codec(VideoCodec::MJPEG)
+            .with_transcode(
+                TranscodeProfile::new()
+                    .with_gop_length(30)
+                    .with_bitrate_kbps(1500),
+            );
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.contains(
+            "demux.video_0 ! queue ! decodebin ! videoconvert ! x264enc bitrate=1500 key-int-max=30 bframes=0"
+        ));
+        assert!(pipeline.contains(
+            "video/x-h264,profile=baseline ! h264parse config-interval=-1 ! rtph264pay name=pay0 pt=96"
+        ));
+        assert!(!pipeline.contains("jpegparse"));
+    }
+
+    #[test]
+    fn test_build_vp9_mkv_transcode_pipeline() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.mkv"))
+            .with_container(ContainerFormat::MKV)
+            .with_codec(VideoCodec::VP9)
+            .with_transcode(TranscodeProfile::new());
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.contains(
+            "matroskademux name=demux demux.video_0 ! queue ! decodebin ! videoconvert ! x264enc"
+        ));
+        assert!(pipeline.contains("rtph264pay name=pay0 pt=96"));
+        assert!(!pipeline.contains("rtpvp9pay"));
+    }
+
+    #[test]
+    fn test_build_h265_transcode_pipeline() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.mp4")).with_transcode(
+            TranscodeProfile::new()
+                .with_codec(VideoCodec::H265)
+                .with_profile(EncoderProfile::Main),
+        );
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.contains("x265enc bitrate=2000 key-int-max=60"));
+        assert!(pipeline.contains("option-string=bframes=0"));
+        assert!(pipeline.contains("video/x-h265,profile=main ! h265parse"));
+        assert!(pipeline.contains("rtph265pay name=pay0"));
+    }
+
     #[test]
     fn test_build_mkv_pipeline() {
         let config =
@@ -77,6 +591,116 @@ mod tests {
         assert!(pipeline.contains("matroskademux"));
     }
 
+    #[test]
+    fn test_build_mpegts_pipeline() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.ts"))
+            .with_container(ContainerFormat::MpegTs)
+            .with_audio_codec(AudioCodec::AAC);
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.contains("tsdemux name=demux demux. ! video/x-h264 ! queue ! h264parse"));
+        assert!(pipeline.contains("demux. ! audio/mpeg ! queue ! aacparse"));
+    }
+
+    struct FixedProbe(MediaInfo);
+
+    impl MediaProbe for FixedProbe {
+        fn probe(&self, _path: &Path) -> Result<MediaInfo> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn test_build_transcoded_mpegts_pipeline_selects_source_codec() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let transcode = |info| {
+            let config = StreamConfig::new(file.path().to_path_buf())
+                .with_transcode(
+                    TranscodeProfile::new()
+                        .with_codec(VideoCodec::H265)
+                        .with_profile(EncoderProfile::Main),
+                )
+                .validate_with(&FixedProbe(info))
+                .unwrap();
+            PipelineBuilder::build_launch_string(&config)
+        };
+
+        // tsdemux's pad carries the file's codec, not the one served
+        let h264 = transcode(MediaInfo::new(ContainerFormat::MpegTs, VideoCodec::H264));
+        assert!(h264.contains("tsdemux name=demux demux. ! video/x-h264 ! queue ! decodebin"));
+        assert!(h264.contains("rtph265pay name=pay0"));
+
+        let mpeg2 = transcode(MediaInfo::with_unsupported_codec(
+            ContainerFormat::MpegTs,
+            "video/mpeg",
+        ));
+        assert!(mpeg2.contains("tsdemux name=demux demux. ! video/mpeg ! queue ! decodebin"));
+    }
+
+    #[test]
+    fn test_build_webm_pipeline() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.webm"))
+            .with_container(ContainerFormat::WebM);
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.contains("matroskademux name=demux demux.video_0"));
+    }
+
+    #[test]
+    fn test_build_flv_pipeline() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.flv"))
+            .with_container(ContainerFormat::Flv)
+            .with_audio_codec(AudioCodec::AAC);
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.contains("flvdemux name=demux demux.video ! queue ! h264parse"));
+        assert!(pipeline.contains("demux.audio ! queue ! aacparse"));
+    }
+
+    #[test]
+    fn test_build_raw_elementary_stream_pipeline() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.h264"))
+            .with_container(ContainerFormat::RawElementaryStream);
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert_eq!(
+            pipeline,
+            "( filesrc location=/test/video.h264 ! queue ! h264parse config-interval=-1 ! rtph264pay name=pay0 pt=96 )"
+        );
+        assert!(!pipeline.contains("demux"));
+    }
+
+    #[test]
+    fn test_video_only_pipeline_links_video_pad_by_name() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.mp4"));
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.contains("qtdemux name=demux demux.video_0"));
+        assert!(!pipeline.contains("audio_0"));
+        assert!(!pipeline.contains("pay1"));
+    }
+
+    #[test]
+    fn test_build_aac_audio_pipeline() {
+        let config =
+            StreamConfig::new(PathBuf::from("/test/video.mp4")).with_audio_codec(AudioCodec::AAC);
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.contains("demux.audio_0 ! queue ! aacparse ! rtpmp4gpay name=pay1 pt=97"));
+    }
+
+    #[test]
+    fn test_build_opus_audio_pipeline() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.mkv"))
+            .with_container(ContainerFormat::MKV)
+            .with_audio_codec(AudioCodec::Opus)
+            .with_audio_rtp_payload_type(111);
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.contains("matroskademux name=demux"));
+        assert!(pipeline.contains("opusparse ! rtpopuspay name=pay1 pt=111"));
+    }
+
     #[test]
     fn test_custom_payload_type() {
         let config =
@@ -85,4 +709,167 @@ mod tests {
 
         assert!(pipeline.contains("pt=97"));
     }
+
+    fn playlist_config(items: Vec<PlaylistItem>) -> StreamConfig {
+        StreamConfig::from_playlist(Playlist::new(items).unwrap())
+    }
+
+    #[test]
+    fn test_build_playlist_pipeline() {
+        let config = playlist_config(vec![
+            PlaylistItem::new(PathBuf::from("/test/day.mp4")),
+            PlaylistItem::new(PathBuf::from("/test/night.mp4")).with_repeat(2),
+        ]);
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.starts_with("( concat name=vcat ! queue ! rtph264pay name=pay0 pt=96"));
+        assert!(pipeline.contains(
+            "filesrc location=/test/day.mp4 ! qtdemux name=demux0 demux0.video_0 ! queue ! h264parse config-interval=-1 ! vcat."
+        ));
+        assert!(pipeline.contains("qtdemux name=demux2"));
+        assert_eq!(pipeline.matches("location=/test/night.mp4").count(), 2);
+    }
+
+    #[test]
+    fn test_build_playlist_uses_probed_formats() {
+        let items = vec![
+            PlaylistItem::new(PathBuf::from("/test/day.mp4")),
+            PlaylistItem::new(PathBuf::from("/test/night.ts")),
+        ];
+        let playlist = Playlist::new(items).unwrap().with_media_info(vec![
+            Some(MediaInfo::new(ContainerFormat::MP4, VideoCodec::H264)),
+            Some(MediaInfo::new(ContainerFormat::MpegTs, VideoCodec::H264)),
+        ]);
+        let pipeline = PipelineBuilder::build_launch_string(&StreamConfig::from_playlist(playlist));
+
+        assert!(pipeline.contains("tsdemux name=demux1 demux1. ! video/x-h264 ! queue"));
+    }
+
+    #[test]
+    fn test_build_transcoded_playlist_with_audio() {
+        let config = playlist_config(vec![
+            PlaylistItem::new(PathBuf::from("/test/day.mp4")),
+            PlaylistItem::new(PathBuf::from("/test/night.mp4")),
+        ])
+        .with_audio_codec(AudioCodec::AAC)
+        .with_transcode(TranscodeProfile::new());
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.contains("concat name=vcat ! queue ! x264enc"));
+        assert!(pipeline.contains("demux1.video_0 ! queue ! decodebin ! videoconvert ! vcat."));
+        assert!(pipeline.contains("concat name=acat ! queue ! rtpmp4gpay name=pay1 pt=97"));
+        assert!(pipeline.contains("demux1.audio_0 ! queue ! aacparse ! acat."));
+    }
+
+    #[test]
+    fn test_build_clip_description_for_scheduled_mount() {
+        let config = StreamConfig::new(PathBuf::from("/test/day.mp4"))
+            .with_audio_codec(AudioCodec::AAC)
+            .with_schedule(
+                Schedule::new(vec!["22:00-06:00=/test/night.mp4".parse().unwrap()]).unwrap(),
+            );
+
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+        assert!(pipeline.starts_with("( concat name=vcat ! queue ! rtph264pay name=pay0 pt=96"));
+        assert!(pipeline.contains("name=demux0 demux0.video_0"));
+
+        let clip = PipelineBuilder::build_clip_description(&config, Path::new("/test/night.mp4"));
+        assert_eq!(
+            clip,
+            "filesrc location=/test/night.mp4 ! qtdemux name=demux demux.video_0 ! queue ! h264parse config-interval=-1 ! identity name=vout demux.audio_0 ! queue ! aacparse ! identity name=aout"
+        );
+    }
+
+    #[test]
+    fn test_build_looping_playlist_clips() {
+        let items = vec![
+            PlaylistItem::new(PathBuf::from("/test/day.mp4")),
+            PlaylistItem::new(PathBuf::from("/test/night.mkv")),
+        ];
+        let playlist = Playlist::new(items).unwrap().with_media_info(vec![
+            Some(MediaInfo::new(ContainerFormat::MP4, VideoCodec::H264)),
+            Some(MediaInfo::new(ContainerFormat::MKV, VideoCodec::H264)),
+        ]);
+        let config = StreamConfig::from_playlist(playlist.clone());
+
+        let clips = PipelineBuilder::build_playlist_clip_descriptions(&config, &playlist);
+        assert_eq!(clips.len(), 2);
+        assert!(clips[0].starts_with("filesrc location=/test/day.mp4 ! qtdemux name=demux"));
+        assert!(clips[1].starts_with("filesrc location=/test/night.mkv ! matroskademux name=demux"));
+        assert!(clips
+            .iter()
+            .all(|clip| clip.ends_with("identity name=vout")));
+    }
+
+    #[test]
+    fn test_build_recording_pipeline() {
+        let recording = RecordingConfig::new("/recordings/{mount}", "/cam1")
+            .unwrap()
+            .with_segment_duration(Duration::from_secs(10));
+        let config = StreamConfig::new(PathBuf::from("/test/video.mp4"))
+            .with_audio_codec(AudioCodec::AAC)
+            .with_recording(recording);
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+
+        assert!(pipeline.contains(
+            "h264parse config-interval=-1 ! tee name=vrec ! queue ! rtph264pay name=pay0 pt=96"
+        ));
+        assert!(pipeline.contains("aacparse ! tee name=arec ! queue ! rtpmp4gpay name=pay1"));
+        assert!(pipeline.ends_with(
+            "splitmuxsink name=rec muxer-factory=mp4mux max-size-time=10000000000 location=/recordings/cam1/segment%05d.mp4 vrec. ! queue ! rec.video arec. ! queue ! rec.audio_0 )"
+        ));
+
+        let unrecorded = PipelineBuilder::build_launch_string(&StreamConfig::new(PathBuf::from(
+            "/test/video.mp4",
+        )));
+        assert!(!unrecorded.contains("tee"));
+    }
+
+    #[test]
+    fn test_quotes_locations_with_spaces() {
+        let config = StreamConfig::new(PathBuf::from("/clips/lobby cam!.mp4"));
+        let pipeline = PipelineBuilder::build_launch_string(&config);
+        assert!(pipeline.contains(r#"filesrc location="/clips/lobby cam!.mp4" ! qtdemux"#));
+
+        let source = PipelineBuilder::build_snapshot_source(
+            &config,
+            Path::new(r#"/clips/say "hi" \ bye.mp4"#),
+        );
+        assert_eq!(source, r#"filesrc location="/clips/say \"hi\" \\ bye.mp4""#);
+    }
+
+    #[test]
+    fn test_build_snapshot_pipeline() {
+        let config = StreamConfig::new(PathBuf::from("/test/video.mp4"));
+        let source = PipelineBuilder::build_snapshot_source(&config, Path::new("/test/night.mp4"));
+        assert_eq!(
+            PipelineBuilder::build_snapshot_launch_string(&source, 320),
+            "filesrc location=/test/night.mp4 ! decodebin ! videoconvert ! videoscale ! video/x-raw,width=[1,320],pixel-aspect-ratio=1/1 ! jpegenc ! appsink name=snapshot sync=false"
+        );
+
+        let pattern = StreamConfig::from_test_pattern(TestPattern::new().with_resolution(640, 480));
+        assert_eq!(
+            PipelineBuilder::build_snapshot_source(&pattern, Path::new("")),
+            "videotestsrc num-buffers=1 pattern=smpte ! video/x-raw,width=640,height=480"
+        );
+    }
+
+    #[test]
+    fn test_build_test_pattern_pipeline() {
+        let pattern = TestPattern::new()
+            .with_pattern("ball")
+            .unwrap()
+            .with_resolution(640, 480)
+            .with_fps(25)
+            .with_overlay(PatternOverlay::Clock);
+        let pipeline =
+            PipelineBuilder::build_launch_string(&StreamConfig::from_test_pattern(pattern));
+
+        assert!(pipeline.starts_with(
+            "( videotestsrc is-live=true pattern=ball ! video/x-raw,width=640,height=480,framerate=25/1 ! clockoverlay ! videoconvert ! x264enc"
+        ));
+        assert!(pipeline.contains("speed-preset=veryfast tune=zerolatency ! video/x-h264"));
+        assert!(pipeline.ends_with("h264parse config-interval=-1 ! rtph264pay name=pay0 pt=96 )"));
+        assert!(!pipeline.contains("filesrc"));
+    }
 }
@@ -0,0 +1,333 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use gstreamer::prelude::*;
+use gstreamer_rtsp_server as gst_rtsp;
+use gstreamer_rtsp_server::prelude::*;
+
+use super::client_tracker::SharedSessions;
+use crate::domain::entities::RecordingState;
+use crate::domain::errors::{DomainError, Result};
+use crate::domain::value_objects::{RecordingConfig, ServerConfig};
+
+/// How long a removed or draining mount waits for its recording to close its last file
+const FINISH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often a finishing recording is checked
+const FINISH_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Follow the `rec` splitmuxsink of every media the factory creates
+///
+/// Each media writes under its own start time, so a restarted mount never
+/// overwrites earlier files. Files past the retention count are deleted as
+/// soon as the sink opens a new one. Once the sink got EOS and closed its
+/// last file, the recording is idle again.
+pub(crate) fn setup_recording(
+    factory: &gst_rtsp::RTSPMediaFactory,
+    recording: &RecordingConfig,
+    mount_point: &str,
+    sessions: SharedSessions,
+) {
+    let recording = recording.clone();
+    let mount_point = mount_point.to_string();
+
+    factory.connect_media_configure(move |_factory, media| {
+        let element = media.element();
+        let Some(sink) = element
+            .downcast_ref::<gstreamer::Bin>()
+            .and_then(|bin| bin.by_name("rec"))
+        else {
+            return;
+        };
+        sink.set_property("location", segment_location(&recording));
+
+        let unprepared_sessions = sessions.clone();
+        let unprepared_mount_point = mount_point.clone();
+        media.connect_unprepared(move |_media| {
+            let mut sessions = unprepared_sessions.lock().unwrap();
+            if let Some(session) = sessions.get_mut(&unprepared_mount_point) {
+                if session.recording_state() == &RecordingState::Recording {
+                    session.set_recording_state(RecordingState::Idle);
+                }
+            }
+        });
+
+        let sink = sink.downgrade();
+        let sessions = sessions.clone();
+        let mount_point = mount_point.clone();
+        media.connect_handle_message(None, move |_media, msg| {
+            use gstreamer::MessageView;
+
+            match msg.view() {
+                MessageView::Element(element) => {
+                    let Some(structure) = element.structure() else {
+                        return true;
+                    };
+                    match structure.name().as_str() {
+                        "splitmuxsink-fragment-opened" => {
+                            let Ok(location) = structure.get::<String>("location") else {
+                                return true;
+                            };
+                            tracing::info!(mount_point = %mount_point, file = %location, "Recording segment opened");
+                            let expired = sessions
+                                .lock()
+                                .unwrap()
+                                .get_mut(&mount_point)
+                                .map(|session| session.add_recorded_segment(PathBuf::from(location)))
+                                .unwrap_or_default();
+                            remove_segments(&mount_point, expired);
+                        }
+                        // Splits close files too; only the close after EOS ends the recording
+                        "splitmuxsink-fragment-closed" if sink.upgrade().is_some_and(|sink| received_eos(&sink)) => {
+                            tracing::info!(mount_point = %mount_point, "Recording finished");
+                            if let Some(session) = sessions.lock().unwrap().get_mut(&mount_point) {
+                                session.set_recording_state(RecordingState::Idle);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                MessageView::Error(err) => {
+                    // Errors of the sink's own muxer and file sink count as its own
+                    let from_sink = sink
+                        .upgrade()
+                        .zip(msg.src())
+                        .is_some_and(|(sink, src)| src.has_as_ancestor(&sink));
+                    if !from_sink {
+                        return true;
+                    }
+                    tracing::error!(mount_point = %mount_point, error = %err.error(), "Recording failed");
+                    if let Some(session) = sessions.lock().unwrap().get_mut(&mount_point) {
+                        session.set_recording_state(RecordingState::Failed(err.error().to_string()));
+                    }
+                }
+                _ => {}
+            }
+            true
+        });
+    });
+}
+
+/// Prepare the mount's shared media and play it, so it records while no client is watching
+///
+/// The factory hands the same media to clients. A client pausing or leaving
+/// pauses the media, so it is set playing again as long as it is prepared.
+pub(crate) fn start_recorder(
+    factory: &gst_rtsp::RTSPMediaFactory,
+    server_config: &ServerConfig,
+) -> Result<gst_rtsp::RTSPMedia> {
+    // Shared media are cached by port and path, so this must match what clients request
+    let address = format!(
+        "rtsp://127.0.0.1:{}{}",
+        server_config.port(),
+        server_config.mount_point()
+    );
+    let (_, url) = gst_rtsp::gst_rtsp::RTSPUrl::parse(&address);
+    let media = url
+        .and_then(|url| factory.construct(&url).ok())
+        .ok_or(DomainError::ServerInitFailed)?;
+    media
+        .prepare(None)
+        .map_err(|err| DomainError::InvalidRecording(err.to_string()))?;
+
+    media.connect_new_state(|media, state| {
+        if state != gstreamer::State::Paused as i32 {
+            return;
+        }
+        // Unpreparing passes through PAUSED as well, so check once the change is done
+        let media = media.clone();
+        glib::idle_add_once(move || {
+            if media.status() == gst_rtsp::RTSPMediaStatus::Prepared {
+                media.set_state(gstreamer::State::Playing, &[]);
+            }
+        });
+    });
+    if !media.set_state(gstreamer::State::Playing, &[]) {
+        let _ = media.unprepare();
+        return Err(DomainError::InvalidRecording(
+            "recorder media cannot play".to_string(),
+        ));
+    }
+    Ok(media)
+}
+
+/// Send EOS into the `rec` splitmuxsink so the muxer writes its index and closes the file
+///
+/// Only the recording branch ends; clients and looping carry on until the
+/// media is unprepared.
+pub(crate) fn finish_recorder(media: &gst_rtsp::RTSPMedia) {
+    let element = media.element();
+    let Some(sink) = element
+        .downcast_ref::<gstreamer::Bin>()
+        .and_then(|bin| bin.by_name("rec"))
+    else {
+        return;
+    };
+    for pad in sink.sink_pads() {
+        pad.send_event(gstreamer::event::Eos::new());
+    }
+}
+
+/// Wait until the recordings of these mounts closed their last file, or give up
+///
+/// Bus messages are handled on the GLib main loop, which must keep running.
+pub(crate) async fn wait_finished(sessions: &SharedSessions, mount_points: &[String]) {
+    let deadline = Instant::now() + FINISH_TIMEOUT;
+    loop {
+        let recording: Vec<String> = {
+            let sessions = sessions.lock().unwrap();
+            mount_points
+                .iter()
+                .filter(|mount_point| {
+                    sessions.get(*mount_point).is_some_and(|session| {
+                        session.recording_state() == &RecordingState::Recording
+                    })
+                })
+                .cloned()
+                .collect()
+        };
+        if recording.is_empty() {
+            return;
+        }
+        if Instant::now() >= deadline {
+            tracing::warn!(mounts = ?recording, "Recordings did not finish their last file in time");
+            return;
+        }
+        tokio::time::sleep(FINISH_CHECK_INTERVAL).await;
+    }
+}
+
+/// Files an earlier run of the mount recorded, oldest first
+///
+/// Each run names its files after the mount and its start time in
+/// milliseconds, so they sort by start time and then by their index. Files
+/// of other mounts recording into the same directory are left out.
+pub(crate) fn existing_segments(recording: &RecordingConfig) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(recording.directory()) else {
+        return Vec::new();
+    };
+    let mut segments: Vec<((u128, u32), PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter_map(|path| Some((segment_key(&path, recording.file_prefix())?, path)))
+        .collect();
+    segments.sort();
+    segments.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Start time and index of a `<prefix>-<unix-ms>-<index>.mp4` file written by `splitmuxsink`
+fn segment_key(path: &Path, prefix: &str) -> Option<(u128, u32)> {
+    if path.extension()? != "mp4" {
+        return None;
+    }
+    let (started, index) = path
+        .file_stem()?
+        .to_str()?
+        .strip_prefix(prefix)?
+        .strip_prefix('-')?
+        .split_once('-')?;
+    Some((started.parse().ok()?, index.parse().ok()?))
+}
+
+/// Create the recording directory before the sink needs it
+pub(crate) fn create_directory(recording: &RecordingConfig) -> Result<()> {
+    std::fs::create_dir_all(recording.directory()).map_err(|err| {
+        DomainError::InvalidRecording(format!(
+            "cannot create {}: {}",
+            recording.directory().display(),
+            err
+        ))
+    })
+}
+
+/// `splitmuxsink` location for a new media, named after the mount and the Unix time in milliseconds
+fn segment_location(recording: &RecordingConfig) -> String {
+    let started = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or_default();
+    recording
+        .directory()
+        .join(format!("{}-{}-%05d.mp4", recording.file_prefix(), started))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Whether EOS reached every input of the sink
+fn received_eos(sink: &gstreamer::Element) -> bool {
+    sink.sink_pads()
+        .iter()
+        .all(|pad| pad.pad_flags().contains(gstreamer::PadFlags::EOS))
+}
+
+/// Delete recorded files that fell out of the retention
+pub(crate) fn remove_segments(mount_point: &str, segments: Vec<PathBuf>) {
+    for path in segments {
+        match std::fs::remove_file(&path) {
+            Ok(()) => {
+                tracing::debug!(mount_point = %mount_point, file = %path.display(), "Recording segment removed")
+            }
+            Err(err) => tracing::warn!(
+                mount_point = %mount_point,
+                file = %path.display(),
+                error = %err,
+                "Failed to remove recording segment"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment_names(recording: &RecordingConfig) -> Vec<String> {
+        existing_segments(recording)
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_existing_segments_sort_by_run_then_index() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "cam1-1700000000500-00000.mp4",
+            "cam1-1700000000000-00001.mp4",
+            "cam1-1700000000000-00000.mp4",
+            "notes.txt",
+            "cam1-segment.mp4",
+        ] {
+            std::fs::write(dir.path().join(name), b"").unwrap();
+        }
+
+        let recording = RecordingConfig::new(dir.path().to_str().unwrap(), "/cam1").unwrap();
+        assert_eq!(
+            segment_names(&recording),
+            [
+                "cam1-1700000000000-00000.mp4",
+                "cam1-1700000000000-00001.mp4",
+                "cam1-1700000000500-00000.mp4"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mounts_sharing_a_directory_keep_their_own_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let directory = dir.path().to_str().unwrap();
+        let cam = RecordingConfig::new(directory, "/cam").unwrap();
+        let cam_1 = RecordingConfig::new(directory, "/cam-1").unwrap();
+        let lobby = RecordingConfig::new(directory, "/cam/lobby").unwrap();
+        for recording in [&cam, &cam_1, &lobby] {
+            let location = segment_location(recording).replace("%05d", "00000");
+            std::fs::write(location, b"").unwrap();
+        }
+
+        for recording in [&cam, &cam_1, &lobby] {
+            let names = segment_names(recording);
+            assert_eq!(names.len(), 1, "{:?}", names);
+            assert!(names[0].starts_with(&format!("{}-", recording.file_prefix())));
+        }
+    }
+}
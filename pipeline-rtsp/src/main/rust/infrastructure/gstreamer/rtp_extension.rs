@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+/// Seconds between the NTP epoch (1900) and the Unix epoch (1970)
+const NTP_UNIX_OFFSET_SECS: u64 = 2_208_988_800;
+
+/// Extension id the RFC 6051 timestamp is announced with in the SDP
+pub(crate) const NTP64_EXTENSION_ID: u8 = 1;
+pub(crate) const NTP64_EXTENSION_URI: &str = "urn:ietf:params:rtp-hdrext:ntp-64";
+
+/// ONVIF Streaming Specification replay extension
+pub(crate) const ONVIF_REPLAY_PROFILE: u16 = 0xABAC;
+
+/// Time since the NTP epoch of an instant given as time since the Unix epoch
+pub(crate) fn ntp_time(since_unix_epoch: Duration) -> Duration {
+    since_unix_epoch + Duration::from_secs(NTP_UNIX_OFFSET_SECS)
+}
+
+/// 64-bit NTP timestamp: seconds since 1900 in the high word, the fraction in the low word
+pub(crate) fn ntp_timestamp(since_unix_epoch: Duration) -> u64 {
+    let ntp_time = ntp_time(since_unix_epoch);
+    let seconds = ntp_time.as_secs() & 0xFFFF_FFFF;
+    let fraction = (u64::from(ntp_time.subsec_nanos()) << 32) / 1_000_000_000;
+    (seconds << 32) | fraction
+}
+
+/// Body of the ONVIF replay extension: NTP timestamp, flags, CSeq and padding
+///
+/// The CSeq byte is left zero; clients match packets to PLAY requests by RTP-Info.
+pub(crate) fn onvif_replay_data(ntp: u64, clean_point: bool, discontinuity: bool) -> [u8; 12] {
+    let mut flags = 0u8;
+    if clean_point {
+        flags |= 0x80;
+    }
+    if discontinuity {
+        flags |= 0x20;
+    }
+
+    let mut data = [0u8; 12];
+    data[..8].copy_from_slice(&ntp.to_be_bytes());
+    data[8] = flags;
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ntp_timestamp() {
+        assert_eq!(ntp_timestamp(Duration::ZERO), NTP_UNIX_OFFSET_SECS << 32);
+        let half = ntp_timestamp(Duration::from_millis(1_500));
+        assert_eq!(half >> 32, NTP_UNIX_OFFSET_SECS + 1);
+        assert_eq!(half & 0xFFFF_FFFF, 0x8000_0000);
+    }
+
+    #[test]
+    fn test_onvif_replay_data() {
+        let data = onvif_replay_data(0x0102_0304_0506_0708, true, true);
+
+        assert_eq!(&data[..8], &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(data[8], 0xA0);
+        assert_eq!(&data[9..], &[0, 0, 0]);
+        assert_eq!(onvif_replay_data(0, false, false)[8], 0);
+    }
+}
@@ -0,0 +1,86 @@
+use glib::translate::{IntoGlib, ToGlibPtr};
+use gstreamer as gst;
+use gstreamer_rtsp_server as gst_rtsp;
+use gstreamer_rtsp_server::gst_rtsp::RTSPAuthMethod;
+use gstreamer_rtsp_server::prelude::*;
+
+use crate::domain::errors::{DomainError, Result};
+use crate::domain::value_objects::{AuthConfig, AuthMethod, TlsConfig};
+
+/// Role of clients that did not authenticate, used when only TLS is enabled
+pub const ANONYMOUS_ROLE: &str = "anonymous";
+
+/// Load the server certificate and key from PEM files
+pub fn load_certificate(tls: &TlsConfig) -> Result<gio::TlsCertificate> {
+    gio::TlsCertificate::from_files(tls.cert_path(), tls.key_path())
+        .map_err(|e| DomainError::InvalidTlsCertificate(e.to_string()))
+}
+
+/// Build the server-wide authenticator
+///
+/// Every user gets a token whose media factory role is its username, so mount
+/// permissions can grant access per user. Without users, clients get the
+/// anonymous role and only the TLS certificate is configured.
+pub fn build_auth(
+    config: Option<&AuthConfig>,
+    certificate: Option<&gio::TlsCertificate>,
+) -> gst_rtsp::RTSPAuth {
+    let auth = gst_rtsp::RTSPAuth::new();
+    if let Some(certificate) = certificate {
+        auth.set_tls_certificate(Some(certificate));
+    }
+
+    let Some(config) = config else {
+        let mut token = gst_rtsp::RTSPToken::builder()
+            .field(gst_rtsp::RTSP_TOKEN_MEDIA_FACTORY_ROLE, ANONYMOUS_ROLE)
+            .build();
+        auth.set_default_token(Some(&mut token));
+        return auth;
+    };
+
+    for user in config.users() {
+        let token = gst_rtsp::RTSPToken::builder()
+            .field(gst_rtsp::RTSP_TOKEN_MEDIA_FACTORY_ROLE, user.username())
+            .build();
+
+        if config.supports(AuthMethod::Basic) {
+            let basic = gst_rtsp::RTSPAuth::make_basic(user.username(), user.password());
+            auth.add_basic(&basic, &token);
+        }
+        if config.supports(AuthMethod::Digest) {
+            auth.add_digest(user.username(), user.password(), &token);
+        }
+    }
+
+    // The bindings take a single method, while the C API takes flags
+    let methods = config
+        .methods()
+        .iter()
+        .map(|method| match method {
+            AuthMethod::Basic => RTSPAuthMethod::Basic,
+            AuthMethod::Digest => RTSPAuthMethod::Digest,
+        })
+        .fold(0, |methods, method| methods | method.into_glib());
+    // SAFETY: the auth is valid and the value is a combination of GstRTSPAuthMethod flags
+    unsafe {
+        gst_rtsp::ffi::gst_rtsp_auth_set_supported_methods(auth.to_glib_none().0, methods);
+    }
+
+    auth
+}
+
+/// Factory roles letting the given users describe and play a mount
+///
+/// Each structure is named after a role and holds its permissions, the form
+/// `RTSPMediaFactory::add_role_from_structure` takes.
+pub fn mount_permissions(users: &[&str]) -> Vec<gst::Structure> {
+    users
+        .iter()
+        .map(|user| {
+            gst::Structure::builder(*user)
+                .field(gst_rtsp::RTSP_PERM_MEDIA_FACTORY_ACCESS, true)
+                .field(gst_rtsp::RTSP_PERM_MEDIA_FACTORY_CONSTRUCT, true)
+                .build()
+        })
+        .collect()
+}
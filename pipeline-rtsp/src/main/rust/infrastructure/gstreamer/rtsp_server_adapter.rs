@@ -1,67 +1,298 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
 use async_trait::async_trait;
 use gstreamer::prelude::*;
 use gstreamer_rtsp_server as gst_rtsp;
+use gstreamer_rtsp_server::gst_rtsp::RTSPLowerTrans;
 use gstreamer_rtsp_server::prelude::*;
 
-use super::PipelineBuilder;
-use crate::domain::entities::StreamSession;
+use super::client_tracker::{ClientTracker, SharedSessions};
+use super::concat_clips;
+use super::fault_injector::FaultInjector;
+use super::snapshot::{self, KeyframeTap};
+use super::{live_clock, recording, rtsp_auth, PipelineBuilder};
+use crate::domain::entities::{ClientSession, StreamSession};
 use crate::domain::errors::{DomainError, Result};
-use crate::domain::ports::StreamingServer;
-use crate::domain::value_objects::{ServerConfig, StreamConfig};
+use crate::domain::ports::{MetricsReporter, SnapshotJob, StreamingServer};
+use crate::domain::value_objects::{
+    AuthConfig, Fault, MulticastPool, PortRange, RtpProfile, ServerConfig, StreamConfig, TlsConfig,
+};
+
+/// Media of every mount, by mount point
+type MountMedia = Arc<Mutex<BTreeMap<String, Vec<glib::WeakRef<gst_rtsp::RTSPMedia>>>>>;
 
 pub struct GStreamerRtspServer {
     server: Option<gst_rtsp::RTSPServer>,
-    current_session: Option<StreamSession>,
-    #[allow(dead_code)]
+    port: Option<u16>,
+    sessions: SharedSessions,
+    factories: BTreeMap<String, gst_rtsp::RTSPMediaFactory>,
+    media: MountMedia,
+    fault_injectors: BTreeMap<String, FaultInjector>,
+    fault_injection: bool,
+    /// Media kept prepared for mounts that record
+    recorders: BTreeMap<String, gst_rtsp::RTSPMedia>,
+    keyframe_taps: BTreeMap<String, KeyframeTap>,
+    tracker: ClientTracker,
+    metrics: Arc<dyn MetricsReporter>,
+    auth: Option<AuthConfig>,
+    tls: Option<TlsConfig>,
+    /// Set once `drain` has sent EOS, so looping mounts let it through
+    draining: Arc<AtomicBool>,
     server_id: Option<glib::SourceId>,
 }
 
 impl GStreamerRtspServer {
-    pub fn new() -> Self {
+    pub fn new(metrics: Arc<dyn MetricsReporter>) -> Self {
+        let sessions: SharedSessions = Arc::new(Mutex::new(BTreeMap::new()));
         Self {
             server: None,
-            current_session: None,
+            port: None,
+            tracker: ClientTracker::new(sessions.clone(), metrics.clone()),
+            metrics,
+            sessions,
+            factories: BTreeMap::new(),
+            media: Arc::new(Mutex::new(BTreeMap::new())),
+            fault_injectors: BTreeMap::new(),
+            fault_injection: false,
+            recorders: BTreeMap::new(),
+            keyframe_taps: BTreeMap::new(),
+            auth: None,
+            tls: None,
+            draining: Arc::new(AtomicBool::new(false)),
             server_id: None,
         }
     }
 
-    fn setup_looping(factory: &gst_rtsp::RTSPMediaFactory, enabled: bool) {
-        if !enabled {
-            return;
+    /// Require clients to authenticate; mounts are then limited to their allowed users
+    pub fn with_auth(mut self, auth: AuthConfig) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Serve `rtsps://` with the given certificate instead of plain RTSP
+    pub fn with_tls(mut self, tls: TlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Let `inject_fault` disturb mounts; probes are only installed when enabled
+    pub fn with_fault_injection(mut self) -> Self {
+        self.fault_injection = true;
+        self
+    }
+
+    /// Create the GStreamer server and attach it on first use
+    fn ensure_server(&mut self, port: u16) -> Result<gst_rtsp::RTSPServer> {
+        if let (Some(server), Some(bound_port)) = (&self.server, self.port) {
+            if bound_port != port {
+                return Err(DomainError::PortMismatch {
+                    expected: bound_port,
+                    actual: port,
+                });
+            }
+            return Ok(server.clone());
         }
 
-        factory.connect_media_configure(|_factory, media| {
-            let element = media.element();
-            if let Some(bus) = element.bus() {
-                let element_weak = element.downgrade();
-                let _ = bus.add_watch(move |_bus, msg: &gstreamer::Message| {
-                    use gstreamer::MessageView;
-
-                    if let Some(element) = element_weak.upgrade() {
-                        match msg.view() {
-                            MessageView::Eos(..) => {
-                                let _ = element.seek_simple(
-                                    gstreamer::SeekFlags::FLUSH | gstreamer::SeekFlags::KEY_UNIT,
-                                    gstreamer::ClockTime::ZERO,
-                                );
-                            }
-                            MessageView::Error(err) => {
-                                tracing::error!("Pipeline error: {:?}", err);
-                            }
-                            _ => {}
-                        }
+        let certificate = self
+            .tls
+            .as_ref()
+            .map(rtsp_auth::load_certificate)
+            .transpose()?;
+
+        let server = gst_rtsp::RTSPServer::new();
+        server.set_service(&port.to_string());
+        if self.auth.is_some() || certificate.is_some() {
+            server.set_auth(Some(&rtsp_auth::build_auth(
+                self.auth.as_ref(),
+                certificate.as_ref(),
+            )));
+        }
+        self.tracker.attach(&server);
+
+        // Attach server to main context to start listening
+        let server_id = server
+            .attach(None)
+            .map_err(|_| DomainError::ServerInitFailed)?;
+
+        self.server = Some(server.clone());
+        self.port = Some(port);
+        self.server_id = Some(server_id);
+
+        Ok(server)
+    }
+
+    /// Factory permissions for a mount, or None when the server has no RTSPAuth
+    fn mount_permissions(
+        &self,
+        server_config: &ServerConfig,
+    ) -> Result<Option<Vec<gstreamer::Structure>>> {
+        match &self.auth {
+            Some(auth) => {
+                let users = auth.users_for_mount(server_config)?;
+                Ok(Some(rtsp_auth::mount_permissions(&users)))
+            }
+            // Refuse to silently serve a restricted mount to everyone
+            None if server_config.allowed_users().is_some() => {
+                Err(DomainError::InvalidAuthConfig(format!(
+                    "mount {} restricts users but authentication is disabled",
+                    server_config.mount_point()
+                )))
+            }
+            // TLS alone still installs an RTSPAuth, which denies mounts without permissions
+            None if self.tls.is_some() => Ok(Some(rtsp_auth::mount_permissions(&[
+                rtsp_auth::ANONYMOUS_ROLE,
+            ]))),
+            None => Ok(None),
+        }
+    }
+
+    /// Drop RTSP sessions still playing media from a removed mount
+    fn teardown_mount_sessions(server: &gst_rtsp::RTSPServer, mount_point: &str) {
+        let Some(pool) = server.session_pool() else {
+            return;
+        };
+
+        pool.filter(Some(&mut |_pool, session| {
+            if session_serves_mount(session, mount_point) {
+                gst_rtsp::RTSPFilterResult::Remove
+            } else {
+                gst_rtsp::RTSPFilterResult::Keep
+            }
+        }));
+    }
+
+    /// Close the connections of every client playing a mount, then drop their sessions
+    fn disconnect_mount_clients(server: &gst_rtsp::RTSPServer, mount_point: &str) {
+        server.client_filter(Some(&mut |_server, client| {
+            let plays_mount = !client
+                .session_filter(Some(&mut |_client, session| {
+                    if session_serves_mount(session, mount_point) {
+                        gst_rtsp::RTSPFilterResult::Ref
+                    } else {
+                        gst_rtsp::RTSPFilterResult::Keep
                     }
+                }))
+                .is_empty();
 
-                    glib::ControlFlow::Continue
-                });
+            if plays_mount {
+                gst_rtsp::RTSPFilterResult::Remove
+            } else {
+                gst_rtsp::RTSPFilterResult::Keep
             }
+        }));
+        Self::teardown_mount_sessions(server, mount_point);
+    }
+
+    /// Remember a mount's media so source switches and faults can reach it
+    fn track_mount_media(&self, factory: &gst_rtsp::RTSPMediaFactory, mount_point: &str) {
+        let mount_media = self.media.clone();
+        let mount_point = mount_point.to_string();
+        factory.connect_media_configure(move |_factory, media| {
+            let mut mount_media = mount_media.lock().unwrap();
+            let media_list = mount_media.entry(mount_point.clone()).or_default();
+            media_list.retain(|weak| weak.upgrade().is_some());
+            media_list.push(media.downgrade());
         });
     }
-}
 
-impl Default for GStreamerRtspServer {
-    fn default() -> Self {
-        Self::new()
+    /// Media of a mount that are prepared and streaming
+    fn prepared_media(&self, mount_point: &str) -> Vec<gst_rtsp::RTSPMedia> {
+        let mount_media = self.media.lock().unwrap();
+        mount_media
+            .get(mount_point)
+            .into_iter()
+            .flatten()
+            .filter_map(|weak| weak.upgrade())
+            .filter(|media| media.status() == gst_rtsp::RTSPMediaStatus::Prepared)
+            .collect()
+    }
+
+    /// Loop the file without gaps using segment seeks
+    ///
+    /// A flushing segment seek after preroll arms the loop. Each SEGMENT_DONE then
+    /// restarts the file with a non-flushing segment seek, so running time keeps
+    /// increasing across iterations and downstream segmenters never see a reset.
+    /// Playlists loop by queueing their clips again instead.
+    fn setup_looping(
+        &self,
+        factory: &gst_rtsp::RTSPMediaFactory,
+        stream_config: &StreamConfig,
+        server_config: &ServerConfig,
+    ) {
+        if !server_config.looping_enabled() {
+            return;
+        }
+        if let Some(playlist) = stream_config.playlist() {
+            concat_clips::setup_playlist_looping(
+                factory,
+                server_config,
+                PipelineBuilder::build_playlist_clip_descriptions(stream_config, playlist),
+                self.sessions.clone(),
+                self.metrics.clone(),
+                self.draining.clone(),
+            );
+            return;
+        }
+
+        let server_config = server_config.clone();
+        let sessions = self.sessions.clone();
+        let metrics = self.metrics.clone();
+        let draining = self.draining.clone();
+        factory.connect_media_configure(move |_factory, media| {
+            media.connect_prepared(|media| {
+                arm_segment_loop(&media.element(), gstreamer::SeekFlags::FLUSH);
+            });
+
+            let loops = Arc::new(AtomicU32::new(0));
+            let server_config = server_config.clone();
+            let sessions = sessions.clone();
+            let metrics = metrics.clone();
+            let draining = draining.clone();
+            media.connect_handle_message(None, move |media, msg| {
+                use gstreamer::MessageView;
+
+                if draining.load(Ordering::Relaxed) {
+                    return true;
+                }
+
+                // A flushing seek from a client drops the segment flag and ends in EOS
+                let flags = match msg.view() {
+                    MessageView::SegmentDone(..) => gstreamer::SeekFlags::empty(),
+                    MessageView::Eos(..) => gstreamer::SeekFlags::FLUSH,
+                    MessageView::Error(err) => {
+                        tracing::error!("Pipeline error: {:?}", err);
+                        return true;
+                    }
+                    _ => return true,
+                };
+
+                let element = media.element();
+                let mount_point = server_config.mount_point();
+                let completed = loops.load(Ordering::Relaxed);
+                if !server_config.continue_looping(completed) {
+                    tracing::info!(
+                        mount_point = %mount_point,
+                        loops = completed,
+                        "Maximum loop count reached, ending stream"
+                    );
+                    if matches!(msg.view(), MessageView::SegmentDone(..)) {
+                        element.send_event(gstreamer::event::Eos::new());
+                    }
+                    return true;
+                }
+
+                arm_segment_loop(&element, flags);
+                loops.fetch_add(1, Ordering::Relaxed);
+                if let Some(session) = sessions.lock().unwrap().get_mut(mount_point) {
+                    session.add_loop();
+                }
+                metrics.report_loop_completed(mount_point);
+                true
+            });
+        });
     }
 }
 
@@ -72,9 +303,26 @@ impl StreamingServer for GStreamerRtspServer {
         stream_config: StreamConfig,
         server_config: ServerConfig,
     ) -> Result<StreamSession> {
-        // Create GStreamer server
-        let server = gst_rtsp::RTSPServer::new();
-        server.set_service(&server_config.port().to_string());
+        let mount_point = server_config.mount_point().to_string();
+        if self.sessions.lock().unwrap().contains_key(&mount_point) {
+            return Err(DomainError::DuplicateMount(mount_point));
+        }
+        server_config.validate_transport()?;
+        server_config.validate_live()?;
+        if let Some(recording) = stream_config.recording() {
+            // Per-client media would write one recording per client
+            if !server_config.sharing_mode().is_shared() {
+                return Err(DomainError::InvalidRecording(
+                    "recording needs shared media".to_string(),
+                ));
+            }
+            recording::create_directory(recording)?;
+        }
+        let permissions = self.mount_permissions(&server_config)?;
+        let address_pool = address_pool(&server_config)?;
+
+        // Create GStreamer server (shared by all mounts)
+        let server = self.ensure_server(server_config.port())?;
 
         // Get mount points
         let mounts = server.mount_points().ok_or(DomainError::ServerInitFailed)?;
@@ -85,43 +333,276 @@ impl StreamingServer for GStreamerRtspServer {
         // Build pipeline from domain config
         let pipeline_str = PipelineBuilder::build_launch_string(&stream_config);
         factory.set_launch(&pipeline_str);
-        factory.set_shared(true);
+        // Per-client media is what lets a client seek with PLAY Range: npt=
+        factory.set_shared(server_config.sharing_mode().is_shared());
         factory.set_eos_shutdown(false);
+        for role in permissions.iter().flatten() {
+            factory.add_role_from_structure(role);
+        }
+        // Secure profiles make the server encrypt with SRTP and exchange keys via MIKEY
+        factory.set_profiles(rtsp_profiles(server_config.rtp_profiles()));
+        // Multicast clients all join the shared media on a group taken from the pool,
+        // unicast UDP binds to the configured server ports
+        if let Some(pool) = &address_pool {
+            factory.set_address_pool(Some(pool));
+        }
+        factory.set_protocols(lower_transports(&server_config));
 
-        // Setup looping if enabled
-        Self::setup_looping(&factory, server_config.looping_enabled());
+        // Setup looping if enabled; generated video never ends
+        if stream_config.test_pattern().is_none() {
+            self.setup_looping(&factory, &stream_config, &server_config);
+        }
+        if server_config.is_live() {
+            live_clock::setup_live(&factory, &server_config);
+        }
+        self.track_mount_media(&factory, &mount_point);
+        if self.fault_injection {
+            let injector = FaultInjector::default();
+            injector.attach(&factory);
+            self.fault_injectors.insert(mount_point.clone(), injector);
+        }
+        let keyframe_tap = KeyframeTap::default();
+        keyframe_tap.attach(&factory);
+        self.keyframe_taps.insert(mount_point.clone(), keyframe_tap);
+        self.tracker.track_media(&factory, &mount_point);
+        self.tracker
+            .count_bytes(&factory, &mount_point, server_config.sharing_mode());
+        if let Some(recording) = stream_config.recording() {
+            recording::setup_recording(&factory, recording, &mount_point, self.sessions.clone());
+        }
 
         // Mount factory
-        mounts.add_factory(server_config.mount_point(), factory);
-
-        // Attach server to main context to start listening
-        let server_id = server
-            .attach(None)
-            .map_err(|_| DomainError::ServerInitFailed)?;
+        mounts.add_factory(&mount_point, factory.clone());
+        self.factories.insert(mount_point.clone(), factory.clone());
 
         // Create session
-        let mut session = StreamSession::new(stream_config, server_config);
+        let active_source = stream_config.scheduled_source().to_path_buf();
+        let recording = stream_config.recording().cloned();
+        let mut session = StreamSession::new(stream_config, server_config.clone());
+        session.set_active_source(active_source);
         session.activate();
+        // Files of earlier runs count toward the retention as well
+        if let Some(recording) = &recording {
+            let expired =
+                session.restore_recorded_segments(recording::existing_segments(recording));
+            recording::remove_segments(&mount_point, expired);
+        }
 
-        self.server = Some(server);
-        self.server_id = Some(server_id);
-        self.current_session = Some(session.clone());
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(mount_point.clone(), session.clone());
+
+        // Recording starts with the mount, not with the first client
+        if recording.is_some() {
+            match recording::start_recorder(&factory, &server_config) {
+                Ok(media) => {
+                    self.recorders.insert(mount_point, media);
+                }
+                Err(err) => {
+                    self.remove(&mount_point).await?;
+                    return Err(err);
+                }
+            }
+        }
 
         Ok(session)
     }
 
+    async fn remove(&mut self, mount_point: &str) -> Result<StreamSession> {
+        // While the session is still there to follow the sink closing its last file
+        if let Some(recorder) = self.recorders.remove(mount_point) {
+            recording::finish_recorder(&recorder);
+            recording::wait_finished(&self.sessions, &[mount_point.to_string()]).await;
+            let _ = recorder.unprepare();
+        }
+
+        let mut session = self
+            .sessions
+            .lock()
+            .unwrap()
+            .remove(mount_point)
+            .ok_or_else(|| DomainError::MountNotFound(mount_point.to_string()))?;
+        session.stop();
+        self.factories.remove(mount_point);
+        self.media.lock().unwrap().remove(mount_point);
+        if let Some(injector) = self.fault_injectors.remove(mount_point) {
+            injector.clear();
+        }
+        self.keyframe_taps.remove(mount_point);
+
+        if let Some(server) = &self.server {
+            if let Some(mounts) = server.mount_points() {
+                mounts.remove_factory(mount_point);
+            }
+            Self::teardown_mount_sessions(server, mount_point);
+        }
+
+        session.mark_stopped();
+
+        Ok(session)
+    }
+
+    async fn switch_source(&mut self, mount_point: &str, source: &Path) -> Result<()> {
+        let stream_config = self
+            .session(mount_point)
+            .ok_or_else(|| DomainError::MountNotFound(mount_point.to_string()))?
+            .stream_config()
+            .clone();
+        if stream_config.schedule().is_none() {
+            return Err(DomainError::InvalidSchedule(format!(
+                "mount {} has no schedule",
+                mount_point
+            )));
+        }
+
+        // Media created from now on starts with the new file
+        if let Some(factory) = self.factories.get(mount_point) {
+            factory.set_launch(&PipelineBuilder::build_scheduled_launch_string(
+                &stream_config,
+                source,
+            ));
+        }
+
+        // Running media get the file appended live, so their clients stay connected
+        let description = PipelineBuilder::build_clip_description(&stream_config, source);
+        for media in self.prepared_media(mount_point) {
+            append_clip(&media, &description)?;
+        }
+
+        if let Some(session) = self.sessions.lock().unwrap().get_mut(mount_point) {
+            session.set_active_source(source.to_path_buf());
+        }
+
+        Ok(())
+    }
+
+    async fn inject_fault(&mut self, mount_point: &str, fault: Fault) -> Result<()> {
+        if !self.fault_injection {
+            return Err(DomainError::FaultInjectionDisabled);
+        }
+        let injector = self
+            .fault_injectors
+            .get(mount_point)
+            .ok_or_else(|| DomainError::MountNotFound(mount_point.to_string()))?;
+
+        match fault {
+            // Looping mounts restart the file after the EOS, like a rebooting camera
+            Fault::Eos => {
+                for media in self.prepared_media(mount_point) {
+                    media.element().send_event(gstreamer::event::Eos::new());
+                }
+            }
+            Fault::TeardownClients => {
+                if let Some(server) = &self.server {
+                    Self::disconnect_mount_clients(server, mount_point);
+                }
+            }
+            Fault::Freeze { .. } | Fault::DropPackets { .. } | Fault::Latency { .. } => {
+                injector.start(fault)
+            }
+        }
+
+        if let Some(session) = self.sessions.lock().unwrap().get_mut(mount_point) {
+            session.add_fault();
+        }
+
+        Ok(())
+    }
+
+    fn snapshot(&self, mount_point: &str, max_width: u32) -> Result<SnapshotJob> {
+        let tap = self
+            .keyframe_taps
+            .get(mount_point)
+            .ok_or_else(|| DomainError::MountNotFound(mount_point.to_string()))?;
+
+        // Without running media, decode the start of what the mount would play
+        match tap.latest() {
+            Some(keyframe) => {
+                let launch = PipelineBuilder::build_snapshot_launch_string(
+                    "appsrc name=frame format=time",
+                    max_width,
+                );
+                Ok(Box::new(move || {
+                    snapshot::encode_keyframe(&launch, keyframe)
+                }))
+            }
+            None => {
+                let session = self
+                    .session(mount_point)
+                    .ok_or_else(|| DomainError::MountNotFound(mount_point.to_string()))?;
+                let source = PipelineBuilder::build_snapshot_source(
+                    session.stream_config(),
+                    session.active_source(),
+                );
+                let launch = PipelineBuilder::build_snapshot_launch_string(&source, max_width);
+                Ok(Box::new(move || snapshot::encode_first_frame(&launch)))
+            }
+        }
+    }
+
+    async fn drain(&mut self) -> Result<()> {
+        // Connected clients are watched by sources of their own and stay up
+        if let Some(server_id) = self.server_id.take() {
+            server_id.remove();
+        }
+
+        self.draining.store(true, Ordering::Relaxed);
+        // Held packets would keep the EOS from the clients
+        for injector in self.fault_injectors.values() {
+            injector.clear();
+        }
+        let media: Vec<gst_rtsp::RTSPMedia> = self
+            .media
+            .lock()
+            .unwrap()
+            .values()
+            .flatten()
+            .filter_map(|weak| weak.upgrade())
+            .filter(|media| media.status() == gst_rtsp::RTSPMediaStatus::Prepared)
+            .collect();
+        // Clients get an RTCP BYE and recordings finish their last file
+        for media in media {
+            media.element().send_event(gstreamer::event::Eos::new());
+        }
+        let recording: Vec<String> = self.recorders.keys().cloned().collect();
+        recording::wait_finished(&self.sessions, &recording).await;
+
+        Ok(())
+    }
+
     async fn stop(&mut self) -> Result<()> {
-        if let Some(session) = &mut self.current_session {
+        // Before taking the lock; unprepared handlers update the sessions
+        for recorder in std::mem::take(&mut self.recorders).into_values() {
+            let _ = recorder.unprepare();
+        }
+
+        let mut sessions = self.sessions.lock().unwrap();
+        for session in sessions.values_mut() {
             session.stop();
         }
 
+        if let Some(mounts) = self.server.as_ref().and_then(|s| s.mount_points()) {
+            for mount_point in sessions.keys() {
+                mounts.remove_factory(mount_point);
+            }
+        }
+
+        self.factories.clear();
+        self.media.lock().unwrap().clear();
+        for injector in std::mem::take(&mut self.fault_injectors).into_values() {
+            injector.clear();
+        }
+        self.keyframe_taps.clear();
+
         // Server will be dropped and cleaned up
         self.server = None;
+        self.port = None;
 
-        if let Some(session) = &mut self.current_session {
+        for session in sessions.values_mut() {
             session.mark_stopped();
         }
-        self.current_session = None;
+        sessions.clear();
 
         Ok(())
     }
@@ -130,7 +611,142 @@ impl StreamingServer for GStreamerRtspServer {
         self.server.is_some()
     }
 
-    fn current_session(&self) -> Option<&StreamSession> {
-        self.current_session.as_ref()
+    fn session(&self, mount_point: &str) -> Option<StreamSession> {
+        self.sessions.lock().unwrap().get(mount_point).cloned()
+    }
+
+    fn sessions(&self) -> Vec<StreamSession> {
+        self.sessions.lock().unwrap().values().cloned().collect()
+    }
+
+    fn clients(&self) -> Vec<ClientSession> {
+        self.tracker.clients()
+    }
+}
+
+fn session_serves_mount(session: &gst_rtsp::RTSPSession, mount_point: &str) -> bool {
+    !session
+        .filter(Some(&mut |_session, media| {
+            if media.matches(mount_point).is_some() {
+                gst_rtsp::RTSPFilterResult::Ref
+            } else {
+                gst_rtsp::RTSPFilterResult::Keep
+            }
+        }))
+        .is_empty()
+}
+
+/// Play the file from the start as a segment that posts SEGMENT_DONE instead of EOS
+fn arm_segment_loop(element: &gstreamer::Element, flags: gstreamer::SeekFlags) {
+    let seeked = element.seek(
+        1.0,
+        flags | gstreamer::SeekFlags::SEGMENT,
+        gstreamer::SeekType::Set,
+        gstreamer::ClockTime::ZERO,
+        gstreamer::SeekType::None,
+        gstreamer::ClockTime::NONE,
+    );
+    if let Err(e) = seeked {
+        tracing::warn!("Loop seek failed: {}", e);
+    }
+}
+
+/// Append a clip to a scheduled media's `concat` elements and end the one playing
+///
+/// `concat` moves on to its next pad once the active pad receives EOS and keeps
+/// running time increasing, so clients see one continuous stream. The replaced
+/// clip, whether appended earlier or part of the launch string, is released from
+/// `concat` after a short delay, which also makes the new clip the first pad that
+/// loop seeks restart.
+fn append_clip(media: &gst_rtsp::RTSPMedia, description: &str) -> Result<()> {
+    let element = media.element();
+    let pipeline = element
+        .downcast_ref::<gstreamer::Bin>()
+        .ok_or_else(|| DomainError::SourceSwitchFailed("media element is not a bin".to_string()))?
+        .clone();
+    let linked = concat_clips::attach_clip(&pipeline, description)?;
+
+    let finished: Vec<_> = linked
+        .iter()
+        .flat_map(|(concat, next)| {
+            concat
+                .sink_pads()
+                .into_iter()
+                .filter(move |pad| pad != next)
+                .map(move |pad| (concat.clone(), pad))
+        })
+        .collect();
+    for (_, pad) in &finished {
+        pad.send_event(gstreamer::event::Eos::new());
+    }
+    concat_clips::retire_clips(pipeline, finished);
+
+    Ok(())
+}
+
+fn rtsp_profiles(profiles: &[RtpProfile]) -> gst_rtsp::gst_rtsp::RTSPProfile {
+    use gst_rtsp::gst_rtsp::RTSPProfile;
+
+    profiles
+        .iter()
+        .fold(RTSPProfile::empty(), |flags, profile| {
+            flags
+                | match profile {
+                    RtpProfile::Avp => RTSPProfile::AVP,
+                    RtpProfile::Savp => RTSPProfile::SAVP,
+                    RtpProfile::Avpf => RTSPProfile::AVPF,
+                    RtpProfile::Savpf => RTSPProfile::SAVPF,
+                }
+        })
+}
+
+/// Lower transports clients may request; multicast needs an address pool
+fn lower_transports(server_config: &ServerConfig) -> RTSPLowerTrans {
+    let policy = server_config.transport_policy();
+    let mut protocols = RTSPLowerTrans::empty();
+    if policy.allows_tcp() {
+        protocols |= RTSPLowerTrans::TCP;
+    }
+    if policy.allows_udp() {
+        protocols |= RTSPLowerTrans::UDP;
+        if server_config.multicast().is_some() {
+            protocols |= RTSPLowerTrans::UDP_MCAST;
+        }
+    }
+    protocols
+}
+
+fn address_pool(server_config: &ServerConfig) -> Result<Option<gst_rtsp::RTSPAddressPool>> {
+    if server_config.multicast().is_none() && server_config.udp_ports().is_none() {
+        return Ok(None);
+    }
+
+    let pool = gst_rtsp::RTSPAddressPool::new();
+    if let Some(multicast) = server_config.multicast() {
+        add_multicast_range(&pool, multicast)?;
+    }
+    if let Some(ports) = server_config.udp_ports() {
+        add_unicast_range(&pool, ports)?;
+    }
+    Ok(Some(pool))
+}
+
+/// Unicast ranges use the any-address so the pool applies to every interface
+fn add_unicast_range(pool: &gst_rtsp::RTSPAddressPool, ports: &PortRange) -> Result<()> {
+    for any_address in ["0.0.0.0", "::"] {
+        pool.add_range(any_address, any_address, ports.first(), ports.last(), 0)
+            .map_err(|e| DomainError::InvalidTransportConfig(e.to_string()))?;
     }
+    Ok(())
+}
+
+fn add_multicast_range(pool: &gst_rtsp::RTSPAddressPool, multicast: &MulticastPool) -> Result<()> {
+    pool.add_range(
+        &multicast.first_address().to_string(),
+        &multicast.last_address().to_string(),
+        multicast.ports().first(),
+        multicast.ports().last(),
+        multicast.ttl(),
+    )
+    .map_err(|e| DomainError::InvalidTransportConfig(e.to_string()))
 }
@@ -0,0 +1,150 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use gstreamer_rtsp_server as gst_rtsp;
+use gstreamer_rtsp_server::prelude::*;
+
+use crate::domain::errors::{DomainError, Result};
+
+/// Time a snapshot pipeline gets to produce its JPEG
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Encoded keyframe together with the caps needed to decode it
+#[derive(Clone)]
+pub(crate) struct Keyframe {
+    caps: gstreamer::Caps,
+    buffer: gstreamer::Buffer,
+}
+
+/// Latest keyframe entering a mount's video payloader
+///
+/// Keyframes decode on their own, so a snapshot never has to wait for the
+/// next one. The frame is dropped with the media it came from.
+#[derive(Clone, Default)]
+pub(crate) struct KeyframeTap {
+    latest: Arc<Mutex<Option<Keyframe>>>,
+}
+
+impl KeyframeTap {
+    /// Watch `pay0` of every media the factory creates
+    pub(crate) fn attach(&self, factory: &gst_rtsp::RTSPMediaFactory) {
+        let tap = self.clone();
+        factory.connect_media_configure(move |_factory, media| {
+            let Some(pad) = media
+                .element()
+                .downcast_ref::<gstreamer::Bin>()
+                .and_then(|bin| bin.by_name("pay0"))
+                .and_then(|payloader| payloader.static_pad("sink"))
+            else {
+                return;
+            };
+
+            let latest = tap.latest.clone();
+            pad.add_probe(gstreamer::PadProbeType::BUFFER, move |pad, info| {
+                if let Some(gstreamer::PadProbeData::Buffer(buffer)) = &info.data {
+                    if !buffer.flags().contains(gstreamer::BufferFlags::DELTA_UNIT) {
+                        if let Some(caps) = pad.current_caps() {
+                            *latest.lock().unwrap() = Some(Keyframe {
+                                caps,
+                                buffer: buffer.clone(),
+                            });
+                        }
+                    }
+                }
+                gstreamer::PadProbeReturn::Ok
+            });
+
+            let latest = tap.latest.clone();
+            media.connect_unprepared(move |_media| {
+                latest.lock().unwrap().take();
+            });
+        });
+    }
+
+    pub(crate) fn latest(&self) -> Option<Keyframe> {
+        self.latest.lock().unwrap().clone()
+    }
+}
+
+/// Decode a keyframe through a snapshot pipeline whose source is `appsrc name=frame`
+pub(crate) fn encode_keyframe(launch: &str, keyframe: Keyframe) -> Result<Vec<u8>> {
+    let pipeline = parse_snapshot_pipeline(launch)?;
+    let source = pipeline
+        .by_name("frame")
+        .and_then(|element| element.downcast::<gst_app::AppSrc>().ok())
+        .ok_or_else(|| {
+            DomainError::SnapshotFailed("snapshot pipeline has no appsrc".to_string())
+        })?;
+    source.set_caps(Some(&keyframe.caps));
+
+    let mut buffer = keyframe.buffer;
+    // Timestamps belong to the running media; the decoder only needs one frame
+    {
+        let buffer = buffer.make_mut();
+        buffer.set_pts(gstreamer::ClockTime::ZERO);
+        buffer.set_dts(gstreamer::ClockTime::NONE);
+    }
+    source
+        .push_buffer(buffer)
+        .map_err(|err| DomainError::SnapshotFailed(err.to_string()))?;
+    let _ = source.end_of_stream();
+
+    run_snapshot(&pipeline)
+}
+
+/// Run a snapshot pipeline with its own source until the first JPEG comes out
+pub(crate) fn encode_first_frame(launch: &str) -> Result<Vec<u8>> {
+    run_snapshot(&parse_snapshot_pipeline(launch)?)
+}
+
+fn parse_snapshot_pipeline(launch: &str) -> Result<gstreamer::Bin> {
+    gstreamer::parse::launch(launch)
+        .map_err(|err| DomainError::SnapshotFailed(err.to_string()))?
+        .downcast::<gstreamer::Bin>()
+        .map_err(|_| DomainError::SnapshotFailed("snapshot pipeline is not a bin".to_string()))
+}
+
+fn run_snapshot(pipeline: &gstreamer::Bin) -> Result<Vec<u8>> {
+    let sink = pipeline
+        .by_name("snapshot")
+        .and_then(|element| element.downcast::<gst_app::AppSink>().ok())
+        .ok_or_else(|| {
+            DomainError::SnapshotFailed("snapshot pipeline has no appsink".to_string())
+        })?;
+
+    let result = pipeline
+        .set_state(gstreamer::State::Playing)
+        .map_err(|err| DomainError::SnapshotFailed(err.to_string()))
+        .and_then(|_| {
+            let timeout = gstreamer::ClockTime::from_nseconds(SNAPSHOT_TIMEOUT.as_nanos() as u64);
+            sink.try_pull_sample(timeout)
+                .ok_or_else(|| first_error(pipeline))
+        })
+        .and_then(|sample| {
+            let buffer = sample.buffer().ok_or_else(|| {
+                DomainError::SnapshotFailed("snapshot sample has no buffer".to_string())
+            })?;
+            let map = buffer
+                .map_readable()
+                .map_err(|err| DomainError::SnapshotFailed(err.to_string()))?;
+            Ok(map.as_slice().to_vec())
+        });
+
+    let _ = pipeline.set_state(gstreamer::State::Null);
+    result
+}
+
+/// The error that kept a snapshot pipeline from producing a frame
+fn first_error(pipeline: &gstreamer::Bin) -> DomainError {
+    let message = pipeline
+        .bus()
+        .and_then(|bus| bus.pop_filtered(&[gstreamer::MessageType::Error]))
+        .and_then(|msg| match msg.view() {
+            gstreamer::MessageView::Error(err) => Some(err.error().to_string()),
+            _ => None,
+        })
+        .unwrap_or_else(|| "no frame decoded in time".to_string());
+    DomainError::SnapshotFailed(message)
+}
@@ -0,0 +1,562 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use warp::http::StatusCode;
+use warp::reply::Response;
+use warp::{Filter, Reply};
+
+use super::metrics_server::HealthResponse;
+use crate::application::services::StreamingService;
+use crate::config::{ChaosSpec, FaultSpec, MountSpec};
+use crate::domain::entities::{ClientSession, RecordingState, StreamSession};
+use crate::domain::errors::DomainError;
+use crate::domain::value_objects::{ChaosSchedule, MulticastPool};
+
+/// Mount description returned by the control API
+#[derive(serde::Serialize)]
+struct MountResponse {
+    mount_point: String,
+    session_id: String,
+    source: &'static str,
+    source_path: String,
+    test_pattern: Option<String>,
+    playlist: Option<Vec<String>>,
+    schedule: Option<Vec<String>>,
+    active_source: String,
+    codec: &'static str,
+    output_codec: &'static str,
+    transcode_profile: Option<&'static str>,
+    container: &'static str,
+    audio_codec: Option<&'static str>,
+    resolution: Option<(u32, u32)>,
+    framerate: Option<f64>,
+    duration_secs: Option<f64>,
+    looping: bool,
+    max_loops: Option<u32>,
+    loops: u64,
+    faults_injected: u64,
+    live: bool,
+    header_extension: Option<&'static str>,
+    recording: &'static str,
+    allowed_users: Option<Vec<String>>,
+    rtp_profiles: Vec<&'static str>,
+    multicast: Option<MulticastResponse>,
+    transport: &'static str,
+    udp_ports: Option<String>,
+    sharing_mode: &'static str,
+    media_instances: u32,
+    clients: u32,
+    bytes_sent: u64,
+    uptime_secs: u64,
+}
+
+impl From<&StreamSession> for MountResponse {
+    fn from(session: &StreamSession) -> Self {
+        let media_info = session.stream_config().media_info();
+        let test_pattern = session.stream_config().test_pattern();
+        Self {
+            mount_point: session.server_config().mount_point().to_string(),
+            session_id: session.id().to_string(),
+            source: session.stream_config().source_kind().as_str(),
+            source_path: session.stream_config().source_path().display().to_string(),
+            test_pattern: test_pattern.map(|pattern| pattern.pattern().to_string()),
+            playlist: session.stream_config().playlist().map(|playlist| {
+                playlist
+                    .play_order()
+                    .iter()
+                    .map(|item| item.path().display().to_string())
+                    .collect()
+            }),
+            schedule: session
+                .stream_config()
+                .schedule()
+                .map(|schedule| schedule.windows().iter().map(ToString::to_string).collect()),
+            active_source: session.active_source().display().to_string(),
+            codec: session.stream_config().codec().as_str(),
+            output_codec: session.stream_config().output_codec().as_str(),
+            transcode_profile: session
+                .stream_config()
+                .transcode()
+                .map(|profile| profile.profile().as_str()),
+            container: session.stream_config().container().as_str(),
+            audio_codec: session.stream_config().audio_codec().map(|c| c.as_str()),
+            resolution: media_info
+                .and_then(|info| info.resolution())
+                .or_else(|| test_pattern.map(|pattern| pattern.resolution())),
+            framerate: media_info
+                .and_then(|info| info.framerate())
+                .or_else(|| test_pattern.map(|pattern| f64::from(pattern.fps()))),
+            duration_secs: media_info
+                .and_then(|info| info.duration())
+                .map(|d| d.as_secs_f64()),
+            looping: session.server_config().looping_enabled(),
+            max_loops: session.server_config().max_loops(),
+            loops: session.loop_count(),
+            faults_injected: session.fault_count(),
+            live: session.server_config().is_live(),
+            header_extension: session
+                .server_config()
+                .header_extension()
+                .map(|extension| extension.as_str()),
+            recording: session.recording_state().as_str(),
+            allowed_users: session
+                .server_config()
+                .allowed_users()
+                .map(<[String]>::to_vec),
+            rtp_profiles: session
+                .server_config()
+                .rtp_profiles()
+                .iter()
+                .map(|profile| profile.as_str())
+                .collect(),
+            multicast: session.server_config().multicast().map(Into::into),
+            transport: session.server_config().transport_policy().as_str(),
+            udp_ports: session
+                .server_config()
+                .udp_ports()
+                .map(|ports| ports.to_string()),
+            sharing_mode: session.server_config().sharing_mode().as_str(),
+            media_instances: session.media_count(),
+            clients: session.client_count(),
+            bytes_sent: session.bytes_sent(),
+            uptime_secs: session.uptime().as_secs(),
+        }
+    }
+}
+
+/// Multicast pool of a mount returned by the control API
+#[derive(serde::Serialize)]
+struct MulticastResponse {
+    first_address: String,
+    last_address: String,
+    first_port: u16,
+    last_port: u16,
+    ttl: u8,
+}
+
+impl From<&MulticastPool> for MulticastResponse {
+    fn from(pool: &MulticastPool) -> Self {
+        Self {
+            first_address: pool.first_address().to_string(),
+            last_address: pool.last_address().to_string(),
+            first_port: pool.ports().first(),
+            last_port: pool.ports().last(),
+            ttl: pool.ttl(),
+        }
+    }
+}
+
+/// Random fault injection of a mount returned by the control API
+#[derive(serde::Serialize)]
+struct ChaosResponse {
+    mount_point: String,
+    faults: Vec<String>,
+    min_interval_secs: u64,
+    max_interval_secs: u64,
+}
+
+impl ChaosResponse {
+    fn new(mount_point: String, schedule: &ChaosSchedule) -> Self {
+        Self {
+            mount_point,
+            faults: schedule.faults().iter().map(ToString::to_string).collect(),
+            min_interval_secs: schedule.min_interval().as_secs(),
+            max_interval_secs: schedule.max_interval().as_secs(),
+        }
+    }
+}
+
+/// Recording of a mount returned by the control API
+#[derive(serde::Serialize)]
+struct RecordingResponse {
+    mount_point: String,
+    state: &'static str,
+    error: Option<String>,
+    directory: Option<String>,
+    segment_duration_secs: Option<u64>,
+    max_segments: Option<u32>,
+    files: Vec<String>,
+}
+
+impl From<&StreamSession> for RecordingResponse {
+    fn from(session: &StreamSession) -> Self {
+        let recording = session.stream_config().recording();
+        Self {
+            mount_point: session.server_config().mount_point().to_string(),
+            state: session.recording_state().as_str(),
+            error: match session.recording_state() {
+                RecordingState::Failed(error) => Some(error.clone()),
+                _ => None,
+            },
+            directory: recording.map(|config| config.directory().display().to_string()),
+            segment_duration_secs: recording.map(|config| config.segment_duration().as_secs()),
+            max_segments: recording.and_then(|config| config.max_segments()),
+            files: session
+                .recorded_segments()
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Injected fault returned by the control API
+#[derive(serde::Serialize)]
+struct FaultResponse {
+    mount_point: String,
+    fault: String,
+    faults_injected: u64,
+}
+
+/// Client description returned by the control API
+#[derive(serde::Serialize)]
+struct ClientResponse {
+    id: String,
+    remote_address: Option<String>,
+    mount_point: Option<String>,
+    transport: &'static str,
+    rtp_profile: Option<&'static str>,
+    srtp: bool,
+    connected_at_unix_secs: u64,
+    connected_secs: u64,
+    bytes_sent: u64,
+}
+
+impl From<&ClientSession> for ClientResponse {
+    fn from(client: &ClientSession) -> Self {
+        Self {
+            id: client.id().to_string(),
+            remote_address: client.remote_address().map(str::to_string),
+            mount_point: client.mount_point().map(str::to_string),
+            transport: client.transport().as_str(),
+            rtp_profile: client.rtp_profile().map(|profile| profile.as_str()),
+            srtp: client.uses_srtp(),
+            connected_at_unix_secs: client
+                .connected_at()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            connected_secs: client.connected_for().as_secs(),
+            bytes_sent: client.bytes_sent(),
+        }
+    }
+}
+
+/// Error body returned by the control API
+#[derive(serde::Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// Readiness probe (`GET /readyz`), failing with 503 once the service drains for shutdown
+pub fn readiness_routes(
+    service: Arc<StreamingService>,
+) -> impl Filter<Extract = (Response,), Error = warp::Rejection> + Clone {
+    warp::path("readyz")
+        .and(warp::path::end())
+        .and(warp::get())
+        .map(move || {
+            let (status, code) = if service.is_draining() {
+                ("draining", StatusCode::SERVICE_UNAVAILABLE)
+            } else {
+                ("ready", StatusCode::OK)
+            };
+            let response = HealthResponse {
+                status,
+                service: "pipeline-rtsp",
+                version: env!("CARGO_PKG_VERSION"),
+            };
+            with_status(&response, code)
+        })
+}
+
+/// Routes for runtime mount management (`GET/POST /mounts`, `DELETE /mounts/{name}`)
+pub fn mount_routes(
+    service: Arc<StreamingService>,
+    rtsp_port: u16,
+) -> impl Filter<Extract = (Response,), Error = warp::Rejection> + Clone {
+    let with_service = warp::any().map(move || service.clone());
+
+    let list_route = warp::path("mounts")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(with_service.clone())
+        .and_then(list_mounts);
+
+    let add_route = warp::path("mounts")
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_service.clone())
+        .and(warp::any().map(move || rtsp_port))
+        .and_then(add_mount);
+
+    // Tail keeps nested mounts addressable, e.g. DELETE /mounts/clips/lobby
+    let remove_route = warp::path("mounts")
+        .and(warp::path::tail())
+        .and(warp::delete())
+        .and(with_service)
+        .and_then(|tail: warp::path::Tail, service| {
+            remove_mount(format!("/{}", tail.as_str()), service)
+        });
+
+    list_route.or(add_route).unify().or(remove_route).unify()
+}
+
+/// Routes for fault injection (`POST /mounts/{name}/chaos`, `GET/PUT/DELETE /mounts/{name}/chaos/schedule`)
+///
+/// Serve these before `mount_routes`, whose DELETE would otherwise take the schedule path.
+pub fn chaos_routes(
+    service: Arc<StreamingService>,
+) -> impl Filter<Extract = (Response,), Error = warp::Rejection> + Clone {
+    let with_service = warp::any().map(move || service.clone());
+
+    let inject_route = mount_path("/chaos")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(with_service.clone())
+        .and_then(inject_fault);
+
+    let show_route = mount_path("/chaos/schedule")
+        .and(warp::get())
+        .and(with_service.clone())
+        .and_then(show_chaos);
+
+    let start_route = mount_path("/chaos/schedule")
+        .and(warp::put())
+        .and(warp::body::json())
+        .and(with_service.clone())
+        .and_then(start_chaos);
+
+    let stop_route = mount_path("/chaos/schedule")
+        .and(warp::delete())
+        .and(with_service)
+        .and_then(stop_chaos);
+
+    inject_route
+        .or(show_route)
+        .unify()
+        .or(start_route)
+        .unify()
+        .or(stop_route)
+        .unify()
+}
+
+/// Route listing a mount's recording files (`GET /mounts/{name}/recordings`)
+pub fn recording_routes(
+    service: Arc<StreamingService>,
+) -> impl Filter<Extract = (Response,), Error = warp::Rejection> + Clone {
+    mount_path("/recordings")
+        .and(warp::get())
+        .and(warp::any().map(move || service.clone()))
+        .and_then(show_recordings)
+}
+
+/// Query of `GET /mounts/{name}/snapshot.jpg`
+#[derive(serde::Deserialize)]
+struct SnapshotQuery {
+    /// Narrower than the configured maximum, e.g. for thumbnails
+    max_width: Option<u32>,
+}
+
+/// Route returning a mount's current picture (`GET /mounts/{name}/snapshot.jpg`)
+pub fn snapshot_routes(
+    service: Arc<StreamingService>,
+    max_width: u32,
+) -> impl Filter<Extract = (Response,), Error = warp::Rejection> + Clone {
+    mount_path("/snapshot.jpg")
+        .and(warp::get())
+        .and(warp::query::<SnapshotQuery>())
+        .and(warp::any().map(move || service.clone()))
+        .and_then(move |mount_point, query: SnapshotQuery, service| {
+            let max_width = query.max_width.unwrap_or(max_width).clamp(1, max_width);
+            show_snapshot(mount_point, max_width, service)
+        })
+}
+
+/// Mount point of a `/mounts/{name}<suffix>` path; names may be nested like `clips/lobby`
+fn mount_path(
+    suffix: &'static str,
+) -> impl Filter<Extract = (String,), Error = warp::Rejection> + Clone {
+    warp::path("mounts").and(warp::path::tail()).and_then(
+        move |tail: warp::path::Tail| async move {
+            match tail.as_str().strip_suffix(suffix) {
+                Some(name) if !name.is_empty() => Ok(format!("/{}", name)),
+                _ => Err(warp::reject::not_found()),
+            }
+        },
+    )
+}
+
+/// Route listing connected RTSP clients (`GET /clients`)
+pub fn client_routes(
+    service: Arc<StreamingService>,
+) -> impl Filter<Extract = (Response,), Error = warp::Rejection> + Clone {
+    warp::path("clients")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::any().map(move || service.clone()))
+        .and_then(list_clients)
+}
+
+async fn list_clients(service: Arc<StreamingService>) -> Result<Response, Infallible> {
+    let clients: Vec<ClientResponse> = service.clients().await.iter().map(Into::into).collect();
+    Ok(warp::reply::json(&clients).into_response())
+}
+
+async fn list_mounts(service: Arc<StreamingService>) -> Result<Response, Infallible> {
+    let mounts: Vec<MountResponse> = service.sessions().await.iter().map(Into::into).collect();
+    Ok(warp::reply::json(&mounts).into_response())
+}
+
+async fn add_mount(
+    spec: MountSpec,
+    service: Arc<StreamingService>,
+    rtsp_port: u16,
+) -> Result<Response, Infallible> {
+    let configs = spec
+        .to_stream_config()
+        .and_then(|stream| Ok((stream, spec.to_server_config(rtsp_port)?)));
+
+    let result = match configs {
+        Ok((stream_config, server_config)) => {
+            service.start_streaming(stream_config, server_config).await
+        }
+        Err(e) => Err(e),
+    };
+
+    Ok(match result {
+        Ok(session) => with_status(&MountResponse::from(&session), StatusCode::CREATED),
+        Err(e) => error_response(e),
+    })
+}
+
+async fn remove_mount(
+    mount_point: String,
+    service: Arc<StreamingService>,
+) -> Result<Response, Infallible> {
+    Ok(match service.stop_mount(&mount_point).await {
+        Ok(session) => with_status(&MountResponse::from(&session), StatusCode::OK),
+        Err(e) => error_response(e),
+    })
+}
+
+async fn inject_fault(
+    mount_point: String,
+    spec: FaultSpec,
+    service: Arc<StreamingService>,
+) -> Result<Response, Infallible> {
+    let fault = match spec.to_fault() {
+        Ok(fault) => fault,
+        Err(e) => return Ok(error_response(e)),
+    };
+
+    Ok(match service.inject_fault(&mount_point, fault).await {
+        Ok(()) => {
+            let faults_injected = service
+                .session(&mount_point)
+                .await
+                .map_or(0, |session| session.fault_count());
+            let body = FaultResponse {
+                mount_point,
+                fault: fault.to_string(),
+                faults_injected,
+            };
+            with_status(&body, StatusCode::ACCEPTED)
+        }
+        Err(e) => error_response(e),
+    })
+}
+
+async fn show_chaos(
+    mount_point: String,
+    service: Arc<StreamingService>,
+) -> Result<Response, Infallible> {
+    Ok(match service.chaos_schedule(&mount_point) {
+        Some(schedule) => with_status(&ChaosResponse::new(mount_point, &schedule), StatusCode::OK),
+        None => no_chaos_schedule(&mount_point),
+    })
+}
+
+async fn start_chaos(
+    mount_point: String,
+    spec: ChaosSpec,
+    service: Arc<StreamingService>,
+) -> Result<Response, Infallible> {
+    let schedule = match spec.to_chaos_schedule() {
+        Ok(schedule) => schedule,
+        Err(e) => return Ok(error_response(e)),
+    };
+
+    Ok(
+        match service.start_chaos(&mount_point, schedule.clone()).await {
+            Ok(()) => with_status(&ChaosResponse::new(mount_point, &schedule), StatusCode::OK),
+            Err(e) => error_response(e),
+        },
+    )
+}
+
+async fn stop_chaos(
+    mount_point: String,
+    service: Arc<StreamingService>,
+) -> Result<Response, Infallible> {
+    Ok(match service.stop_chaos(&mount_point) {
+        Some(schedule) => with_status(&ChaosResponse::new(mount_point, &schedule), StatusCode::OK),
+        None => no_chaos_schedule(&mount_point),
+    })
+}
+
+async fn show_recordings(
+    mount_point: String,
+    service: Arc<StreamingService>,
+) -> Result<Response, Infallible> {
+    Ok(match service.session(&mount_point).await {
+        Some(session) => with_status(&RecordingResponse::from(&session), StatusCode::OK),
+        None => error_response(DomainError::MountNotFound(mount_point)),
+    })
+}
+
+async fn show_snapshot(
+    mount_point: String,
+    max_width: u32,
+    service: Arc<StreamingService>,
+) -> Result<Response, Infallible> {
+    Ok(match service.snapshot(&mount_point, max_width).await {
+        Ok(jpeg) => warp::reply::with_header(jpeg, "content-type", "image/jpeg").into_response(),
+        Err(e) => error_response(e),
+    })
+}
+
+fn no_chaos_schedule(mount_point: &str) -> Response {
+    with_status(
+        &ErrorResponse {
+            error: format!("No chaos schedule running on {}", mount_point),
+        },
+        StatusCode::NOT_FOUND,
+    )
+}
+
+fn with_status<T: serde::Serialize>(body: &T, status: StatusCode) -> Response {
+    warp::reply::with_status(warp::reply::json(body), status).into_response()
+}
+
+fn error_response(error: DomainError) -> Response {
+    let status = match &error {
+        DomainError::MountNotFound(_) => StatusCode::NOT_FOUND,
+        DomainError::DuplicateMount(_) => StatusCode::CONFLICT,
+        DomainError::FaultInjectionDisabled => StatusCode::FORBIDDEN,
+        DomainError::ShuttingDown => StatusCode::SERVICE_UNAVAILABLE,
+        DomainError::ServerInitFailed
+        | DomainError::SourceSwitchFailed(_)
+        | DomainError::SnapshotFailed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        _ => StatusCode::BAD_REQUEST,
+    };
+
+    with_status(
+        &ErrorResponse {
+            error: error.to_string(),
+        },
+        status,
+    )
+}
@@ -1,21 +1,47 @@
+use std::sync::Arc;
+
 use warp::Filter;
 
+use super::control_api::{
+    chaos_routes, client_routes, mount_routes, readiness_routes, recording_routes, snapshot_routes,
+};
 use super::PrometheusReporter;
+use crate::application::services::StreamingService;
 
-/// Health check response structure
+/// Health check response structure, shared with the readiness probe
 #[derive(serde::Serialize)]
-struct HealthResponse {
-    status: &'static str,
-    service: &'static str,
-    version: &'static str,
+pub(super) struct HealthResponse {
+    pub(super) status: &'static str,
+    pub(super) service: &'static str,
+    pub(super) version: &'static str,
+}
+
+/// CORS policy of the metrics and control API
+///
+/// Any origin may read. The control API has no authentication of its own, so
+/// browsers may only change mounts or inject faults from the listed origins.
+pub fn cors_policy(origins: &[String]) -> warp::cors::Builder {
+    if origins.is_empty() {
+        return warp::cors()
+            .allow_any_origin()
+            .allow_methods(vec!["GET", "OPTIONS"])
+            .allow_headers(vec!["Content-Type"]);
+    }
+    warp::cors()
+        .allow_origins(origins.iter().map(String::as_str))
+        .allow_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS"])
+        .allow_headers(vec!["Content-Type"])
 }
 
-pub async fn serve_metrics(port: u16) {
+pub async fn serve_metrics(
+    port: u16,
+    service: Arc<StreamingService>,
+    rtsp_port: u16,
+    snapshot_max_width: u32,
+    cors_origins: Vec<String>,
+) {
     // CORS configuration for browser access
-    let cors = warp::cors()
-        .allow_any_origin()
-        .allow_methods(vec!["GET", "OPTIONS"])
-        .allow_headers(vec!["Content-Type"]);
+    let cors = cors_policy(&cors_origins);
 
     let metrics_route = warp::path("metrics").map(|| {
         let body = PrometheusReporter::gather_metrics();
@@ -35,20 +61,15 @@ pub async fn serve_metrics(port: u16) {
     let liveness_route =
         warp::path("livez").map(|| warp::reply::with_status("OK", warp::http::StatusCode::OK));
 
-    // Readiness probe endpoint (can the service accept traffic?)
-    let readiness_route = warp::path("readyz").map(|| {
-        let response = HealthResponse {
-            status: "ready",
-            service: "pipeline-rtsp",
-            version: env!("CARGO_PKG_VERSION"),
-        };
-        warp::reply::json(&response)
-    });
-
     let routes = metrics_route
         .or(health_route)
         .or(liveness_route)
-        .or(readiness_route)
+        .or(readiness_routes(service.clone()))
+        .or(chaos_routes(service.clone()))
+        .or(recording_routes(service.clone()))
+        .or(snapshot_routes(service.clone(), snapshot_max_width))
+        .or(mount_routes(service.clone(), rtsp_port))
+        .or(client_routes(service))
         .with(cors);
 
     tracing::info!("Metrics server starting on port {}", port);
@@ -1,5 +1,9 @@
+mod control_api;
 mod metrics_server;
 mod prometheus_reporter;
 
-pub use metrics_server::serve_metrics;
+pub use control_api::{
+    chaos_routes, client_routes, mount_routes, readiness_routes, recording_routes, snapshot_routes,
+};
+pub use metrics_server::{cors_policy, serve_metrics};
 pub use prometheus_reporter::PrometheusReporter;
@@ -1,9 +1,12 @@
 use std::sync::LazyLock;
 
-use prometheus::{Encoder, IntCounter, IntGauge, Registry, TextEncoder};
+use prometheus::{
+    Encoder, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
 
 use crate::domain::entities::StreamSession;
 use crate::domain::ports::MetricsReporter;
+use crate::domain::value_objects::{ClientTransport, Fault, WatchEvent};
 
 pub static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::new);
 pub static ACTIVE_SESSIONS: LazyLock<IntGauge> = LazyLock::new(|| {
@@ -34,6 +37,96 @@ pub static BYTES_SENT: LazyLock<IntCounter> = LazyLock::new(|| {
     )
     .expect("metric can be created")
 });
+pub static MOUNT_ACTIVE_CLIENTS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    IntGaugeVec::new(
+        Opts::new(
+            "rtsp_mount_active_clients",
+            "Number of currently connected RTSP clients per mount point",
+        ),
+        &["mount"],
+    )
+    .expect("metric can be created")
+});
+pub static MOUNT_CONNECTIONS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "rtsp_mount_client_connections_total",
+            "Total number of RTSP client connections per mount point",
+        ),
+        &["mount"],
+    )
+    .expect("metric can be created")
+});
+pub static MOUNT_BYTES_SENT: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "rtsp_mount_bytes_sent_total",
+            "Total bytes sent to RTSP clients per mount point",
+        ),
+        &["mount"],
+    )
+    .expect("metric can be created")
+});
+pub static UNAUTHENTICATED_ATTEMPTS: LazyLock<IntCounter> = LazyLock::new(|| {
+    IntCounter::new(
+        "rtsp_unauthenticated_attempts_total",
+        "Total RTSP requests rejected with 401 Unauthorized",
+    )
+    .expect("metric can be created")
+});
+pub static MOUNT_SRTP_CLIENTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "rtsp_mount_srtp_clients_total",
+            "Total RTSP clients that negotiated SRTP (RTP/SAVP or RTP/SAVPF) per mount point",
+        ),
+        &["mount"],
+    )
+    .expect("metric can be created")
+});
+
+pub static MOUNT_CLIENT_TRANSPORTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "rtsp_mount_client_transports_total",
+            "Total RTSP clients per mount point and negotiated lower transport",
+        ),
+        &["mount", "transport"],
+    )
+    .expect("metric can be created")
+});
+
+pub static MOUNT_LOOPS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "rtsp_mount_loops_total",
+            "Total times the source file was restarted per mount point",
+        ),
+        &["mount"],
+    )
+    .expect("metric can be created")
+});
+
+pub static MOUNT_FAULTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "rtsp_mount_faults_injected_total",
+            "Total faults injected per mount point and fault kind",
+        ),
+        &["mount", "fault"],
+    )
+    .expect("metric can be created")
+});
+pub static WATCH_EVENTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "rtsp_watch_events_total",
+            "Total mount changes from the watched directory by event and result",
+        ),
+        &["event", "result"],
+    )
+    .expect("metric can be created")
+});
 
 pub struct PrometheusReporter;
 
@@ -47,6 +140,15 @@ impl PrometheusReporter {
         REGISTRY.register(Box::new(ACTIVE_CLIENTS.clone()))?;
         REGISTRY.register(Box::new(TOTAL_CONNECTIONS.clone()))?;
         REGISTRY.register(Box::new(BYTES_SENT.clone()))?;
+        REGISTRY.register(Box::new(MOUNT_ACTIVE_CLIENTS.clone()))?;
+        REGISTRY.register(Box::new(MOUNT_CONNECTIONS.clone()))?;
+        REGISTRY.register(Box::new(MOUNT_BYTES_SENT.clone()))?;
+        REGISTRY.register(Box::new(UNAUTHENTICATED_ATTEMPTS.clone()))?;
+        REGISTRY.register(Box::new(MOUNT_SRTP_CLIENTS.clone()))?;
+        REGISTRY.register(Box::new(MOUNT_CLIENT_TRANSPORTS.clone()))?;
+        REGISTRY.register(Box::new(MOUNT_LOOPS.clone()))?;
+        REGISTRY.register(Box::new(MOUNT_FAULTS.clone()))?;
+        REGISTRY.register(Box::new(WATCH_EVENTS.clone()))?;
         Ok(())
     }
 
@@ -69,20 +171,82 @@ impl Default for PrometheusReporter {
 }
 
 impl MetricsReporter for PrometheusReporter {
-    fn report_session_started(&self, _session: &StreamSession) {
+    fn report_session_started(&self, session: &StreamSession) {
         ACTIVE_SESSIONS.inc();
+        MOUNT_ACTIVE_CLIENTS
+            .with_label_values(&[session.server_config().mount_point()])
+            .set(0);
     }
 
-    fn report_session_stopped(&self, _session: &StreamSession) {
+    fn report_session_stopped(&self, session: &StreamSession) {
         ACTIVE_SESSIONS.dec();
+        // Clients of a removed mount are dropped along with it
+        ACTIVE_CLIENTS.sub(i64::from(session.client_count()));
+        let mount_point = session.server_config().mount_point();
+        let _ = MOUNT_ACTIVE_CLIENTS.remove_label_values(&[mount_point]);
+        let _ = MOUNT_CONNECTIONS.remove_label_values(&[mount_point]);
+        let _ = MOUNT_BYTES_SENT.remove_label_values(&[mount_point]);
+        let _ = MOUNT_SRTP_CLIENTS.remove_label_values(&[mount_point]);
+        let _ = MOUNT_LOOPS.remove_label_values(&[mount_point]);
+        for transport in [
+            ClientTransport::Udp,
+            ClientTransport::UdpMulticast,
+            ClientTransport::Tcp,
+        ] {
+            let _ = MOUNT_CLIENT_TRANSPORTS.remove_label_values(&[mount_point, transport.as_str()]);
+        }
+        for kind in Fault::KINDS {
+            let _ = MOUNT_FAULTS.remove_label_values(&[mount_point, kind]);
+        }
     }
 
-    fn report_client_connected(&self) {
+    fn report_client_connected(&self, mount_point: &str) {
         ACTIVE_CLIENTS.inc();
         TOTAL_CONNECTIONS.inc();
+        MOUNT_ACTIVE_CLIENTS.with_label_values(&[mount_point]).inc();
+        MOUNT_CONNECTIONS.with_label_values(&[mount_point]).inc();
     }
 
-    fn report_client_disconnected(&self) {
+    fn report_client_disconnected(&self, mount_point: &str) {
         ACTIVE_CLIENTS.dec();
+        MOUNT_ACTIVE_CLIENTS.with_label_values(&[mount_point]).dec();
+    }
+
+    fn report_bytes_sent(&self, mount_point: &str, bytes: u64) {
+        BYTES_SENT.inc_by(bytes);
+        MOUNT_BYTES_SENT
+            .with_label_values(&[mount_point])
+            .inc_by(bytes);
+    }
+
+    fn report_unauthenticated_attempt(&self) {
+        UNAUTHENTICATED_ATTEMPTS.inc();
+    }
+
+    fn report_srtp_negotiated(&self, mount_point: &str) {
+        MOUNT_SRTP_CLIENTS.with_label_values(&[mount_point]).inc();
+    }
+
+    fn report_client_transport(&self, mount_point: &str, transport: ClientTransport) {
+        MOUNT_CLIENT_TRANSPORTS
+            .with_label_values(&[mount_point, transport.as_str()])
+            .inc();
+    }
+
+    fn report_loop_completed(&self, mount_point: &str) {
+        MOUNT_LOOPS.with_label_values(&[mount_point]).inc();
+    }
+
+    fn report_fault_injected(&self, mount_point: &str, fault: &Fault) {
+        MOUNT_FAULTS
+            .with_label_values(&[mount_point, fault.kind()])
+            .inc();
+    }
+
+    fn report_watch_event(&self, event: &WatchEvent, accepted: bool) {
+        let result = if accepted { "ok" } else { "rejected" };
+        WATCH_EVENTS
+            .with_label_values(&[event.kind(), result])
+            .inc();
     }
 }
@@ -1,2 +1,3 @@
 pub mod gstreamer;
 pub mod metrics;
+pub mod watch;
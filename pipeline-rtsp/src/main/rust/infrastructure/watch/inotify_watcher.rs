@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use inotify::{EventMask, Inotify, WatchMask};
+use tokio::sync::mpsc;
+
+use crate::domain::value_objects::WatchEvent;
+
+/// Events buffered between the watcher thread and the publisher
+const EVENT_QUEUE: usize = 64;
+
+/// Size and modification time of a file, to tell a rewrite from a file merely reopened
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        metadata.is_file().then(|| Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+/// Files reported as added and not removed since, keyed by path
+type KnownFiles = BTreeMap<PathBuf, FileStamp>;
+
+/// Reports files appearing in and leaving a directory, using inotify
+///
+/// Files count once they are closed after writing or moved in, so clips still
+/// being copied are never probed. A file closed without changes is not
+/// reported again. Hidden files are skipped, which covers the temporary names
+/// rsync and most copy tools write to. When the kernel drops events, the
+/// directory is scanned again and compared with what was reported.
+pub struct InotifyWatcher;
+
+impl InotifyWatcher {
+    /// Watch `directory` on a thread of its own; files already there are reported first
+    pub fn spawn(directory: &Path) -> std::io::Result<mpsc::Receiver<WatchEvent>> {
+        let mut inotify = Inotify::init()?;
+        inotify.watches().add(
+            directory,
+            WatchMask::CLOSE_WRITE
+                | WatchMask::MOVED_TO
+                | WatchMask::DELETE
+                | WatchMask::MOVED_FROM,
+        )?;
+
+        let existing = scan(directory)?;
+
+        let (sender, receiver) = mpsc::channel(EVENT_QUEUE);
+        let directory = directory.to_path_buf();
+        std::thread::spawn(move || {
+            let mut known = KnownFiles::new();
+            for event in reconcile(&mut known, existing) {
+                if sender.blocking_send(event).is_err() {
+                    return;
+                }
+            }
+
+            let mut buffer = [0; 4096];
+            loop {
+                let events = match inotify.read_events_blocking(&mut buffer) {
+                    Ok(events) => events,
+                    Err(err) => {
+                        tracing::error!(
+                            directory = %directory.display(),
+                            "Watching stopped: {}",
+                            err
+                        );
+                        return;
+                    }
+                };
+                for event in events {
+                    let changes = if event.mask.contains(EventMask::Q_OVERFLOW) {
+                        tracing::warn!(
+                            directory = %directory.display(),
+                            "Watch events were lost, scanning the directory again"
+                        );
+                        match scan(&directory) {
+                            Ok(current) => reconcile(&mut known, current),
+                            Err(err) => {
+                                tracing::error!(
+                                    directory = %directory.display(),
+                                    "Scanning failed: {}",
+                                    err
+                                );
+                                Vec::new()
+                            }
+                        }
+                    } else {
+                        watch_event(&directory, event.mask, event.name)
+                            .and_then(|event| track(&mut known, event))
+                            .into_iter()
+                            .collect()
+                    };
+                    for change in changes {
+                        if sender.blocking_send(change).is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(receiver)
+    }
+}
+
+fn watch_event(directory: &Path, mask: EventMask, name: Option<&OsStr>) -> Option<WatchEvent> {
+    let name = name.filter(|name| !is_hidden(name))?;
+    if mask.contains(EventMask::ISDIR) {
+        return None;
+    }
+
+    let path = directory.join(name);
+    if mask.intersects(EventMask::CLOSE_WRITE | EventMask::MOVED_TO) {
+        Some(WatchEvent::Added(path))
+    } else if mask.intersects(EventMask::DELETE | EventMask::MOVED_FROM) {
+        Some(WatchEvent::Removed(path))
+    } else {
+        None
+    }
+}
+
+/// Visible files of the directory with their stamps
+fn scan(directory: &Path) -> std::io::Result<KnownFiles> {
+    Ok(std::fs::read_dir(directory)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| !is_hidden(&entry.file_name()))
+        .filter_map(|entry| {
+            let path = entry.path();
+            Some((path.clone(), FileStamp::of(&path)?))
+        })
+        .collect())
+}
+
+/// Events that bring the known files in line with a scan, which then becomes the known files
+///
+/// Removals come first, additions and changed files follow in path order.
+fn reconcile(known: &mut KnownFiles, current: KnownFiles) -> Vec<WatchEvent> {
+    let mut events: Vec<WatchEvent> = known
+        .keys()
+        .filter(|path| !current.contains_key(*path))
+        .cloned()
+        .map(WatchEvent::Removed)
+        .collect();
+    events.extend(
+        current
+            .iter()
+            .filter(|(path, stamp)| known.get(*path) != Some(*stamp))
+            .map(|(path, _)| WatchEvent::Added(path.clone())),
+    );
+    *known = current;
+    events
+}
+
+/// Record an event in the known files, dropping it when nothing changed
+fn track(known: &mut KnownFiles, event: WatchEvent) -> Option<WatchEvent> {
+    match &event {
+        // Gone again before it could be looked at, a later event reports that
+        WatchEvent::Added(path) => {
+            let stamp = FileStamp::of(path)?;
+            if known.insert(path.clone(), stamp) == Some(stamp) {
+                return None;
+            }
+        }
+        WatchEvent::Removed(path) => {
+            known.remove(path)?;
+        }
+    }
+    Some(event)
+}
+
+fn is_hidden(name: &OsStr) -> bool {
+    name.to_str().is_some_and(|name| name.starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watch_event_mapping() {
+        let directory = Path::new("/watch");
+        assert_eq!(
+            watch_event(directory, EventMask::CLOSE_WRITE, Some(OsStr::new("a.mp4"))),
+            Some(WatchEvent::Added(PathBuf::from("/watch/a.mp4")))
+        );
+        assert_eq!(
+            watch_event(directory, EventMask::MOVED_FROM, Some(OsStr::new("a.mp4"))),
+            Some(WatchEvent::Removed(PathBuf::from("/watch/a.mp4")))
+        );
+        assert_eq!(
+            watch_event(
+                directory,
+                EventMask::MOVED_TO,
+                Some(OsStr::new(".a.mp4.tmp"))
+            ),
+            None
+        );
+        assert_eq!(
+            watch_event(
+                directory,
+                EventMask::DELETE | EventMask::ISDIR,
+                Some(OsStr::new("old"))
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_track_skips_unchanged_files() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("a.mp4");
+        std::fs::write(&path, b"clip").unwrap();
+        let mut known = KnownFiles::new();
+
+        let added = WatchEvent::Added(path.clone());
+        assert_eq!(track(&mut known, added.clone()), Some(added.clone()));
+        // Reopened and closed without writing
+        assert_eq!(track(&mut known, added.clone()), None);
+
+        std::fs::write(&path, b"longer clip").unwrap();
+        assert_eq!(track(&mut known, added.clone()), Some(added));
+
+        let removed = WatchEvent::Removed(path);
+        assert_eq!(track(&mut known, removed.clone()), Some(removed.clone()));
+        assert_eq!(track(&mut known, removed), None);
+    }
+
+    #[test]
+    fn test_reconcile_reports_differences_to_scan() {
+        let stamp = |len| FileStamp {
+            len,
+            modified: None,
+        };
+        let mut known = KnownFiles::from([
+            (PathBuf::from("/watch/gone.mp4"), stamp(1)),
+            (PathBuf::from("/watch/kept.mp4"), stamp(2)),
+            (PathBuf::from("/watch/rewritten.mp4"), stamp(3)),
+        ]);
+        let current = KnownFiles::from([
+            (PathBuf::from("/watch/kept.mp4"), stamp(2)),
+            (PathBuf::from("/watch/new.mp4"), stamp(4)),
+            (PathBuf::from("/watch/rewritten.mp4"), stamp(5)),
+        ]);
+
+        assert_eq!(
+            reconcile(&mut known, current.clone()),
+            vec![
+                WatchEvent::Removed(PathBuf::from("/watch/gone.mp4")),
+                WatchEvent::Added(PathBuf::from("/watch/new.mp4")),
+                WatchEvent::Added(PathBuf::from("/watch/rewritten.mp4")),
+            ]
+        );
+        assert_eq!(known, current);
+    }
+}
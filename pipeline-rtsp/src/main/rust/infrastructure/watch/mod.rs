@@ -0,0 +1,3 @@
+mod inotify_watcher;
+
+pub use inotify_watcher::InotifyWatcher;
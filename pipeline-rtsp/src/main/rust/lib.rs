@@ -5,10 +5,23 @@ pub mod infrastructure;
 
 // Re-exports for convenience
 pub use application::services::StreamingService;
-pub use config::Config;
-pub use domain::entities::{SessionState, StreamSession};
+pub use config::{
+    ChaosSpec, Config, FaultSpec, MountSpec, MulticastSpec, PlaylistItemSpec, PlaylistSpec,
+    RecordingSpec, ScheduleSpec, TestPatternSpec, TranscodeSpec,
+};
+pub use domain::entities::{ClientSession, RecordingState, SessionState, StreamSession};
 pub use domain::errors::{DomainError, Result};
-pub use domain::ports::{MetricsReporter, StreamingServer};
-pub use domain::value_objects::{ContainerFormat, ServerConfig, StreamConfig, VideoCodec};
-pub use infrastructure::gstreamer::{GStreamerRtspServer, PipelineBuilder};
-pub use infrastructure::metrics::{serve_metrics, PrometheusReporter};
+pub use domain::ports::{MediaProbe, MetricsReporter, SnapshotJob, StreamingServer};
+pub use domain::value_objects::{
+    AudioCodec, AuthConfig, AuthMethod, ChaosSchedule, ClientTransport, ContainerFormat,
+    EncoderProfile, Fault, HeaderExtension, MediaInfo, MulticastPool, PatternOverlay, Playlist,
+    PlaylistItem, PortRange, RecordingConfig, RtpProfile, RtspUser, Schedule, ScheduleWindow,
+    ServerConfig, SharingMode, SourceKind, StreamConfig, TestPattern, TimeOfDay, TlsConfig,
+    TranscodeProfile, TransportPolicy, VideoCodec, WatchEvent,
+};
+pub use infrastructure::gstreamer::{DiscovererProbe, GStreamerRtspServer, PipelineBuilder};
+pub use infrastructure::metrics::{
+    chaos_routes, client_routes, cors_policy, mount_routes, readiness_routes, recording_routes,
+    serve_metrics, snapshot_routes, PrometheusReporter,
+};
+pub use infrastructure::watch::InotifyWatcher;
@@ -1,12 +1,14 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
 use clap::Parser;
+use tokio::signal::unix::{signal, SignalKind};
 use tracing::{error, info};
 
 use pipeline_rtsp::{
-    Config, GStreamerRtspServer, PrometheusReporter, ServerConfig, StreamConfig, StreamingService,
-    serve_metrics,
+    serve_metrics, Config, DiscovererProbe, GStreamerRtspServer, InotifyWatcher,
+    PrometheusReporter, StreamingService,
 };
 
 #[tokio::main]
@@ -44,56 +46,135 @@ async fn main() -> Result<()> {
     PrometheusReporter::init_metrics()?;
     info!("Metrics initialized");
 
-    // Start metrics server
-    let metrics_port = config.metrics_port;
-    tokio::spawn(async move {
-        serve_metrics(metrics_port).await;
-    });
-    info!("Metrics server started on port {}", config.metrics_port);
-
     // Create infrastructure implementations (dependency injection)
-    let server = Box::new(GStreamerRtspServer::new());
     let metrics_reporter = Arc::new(PrometheusReporter::new());
+    let mut server = GStreamerRtspServer::new(metrics_reporter.clone());
+    if let Some(auth) = config.auth_config()? {
+        info!(
+            "RTSP authentication enabled for {} user(s)",
+            auth.users().len()
+        );
+        server = server.with_auth(auth);
+    }
+    let tls = config.tls_config()?;
+    if let Some(tls) = &tls {
+        info!("RTSPS enabled with certificate {:?}", tls.cert_path());
+        server = server.with_tls(tls.clone());
+    }
+    if config.chaos {
+        info!("Fault injection enabled on the control API");
+        server = server.with_fault_injection();
+    }
+    let scheme = if tls.is_some() { "rtsps" } else { "rtsp" };
+    let server = Box::new(server);
 
     // Create application service
-    let streaming_service = StreamingService::new(server, metrics_reporter);
+    let streaming_service = Arc::new(
+        StreamingService::new(server, metrics_reporter)
+            .with_media_probe(Arc::new(DiscovererProbe::new())),
+    );
 
-    // Convert CLI config to domain configs
-    let stream_config = StreamConfig::new(config.video_path.clone());
-    let server_config = ServerConfig::new(config.rtsp_port, config.mount_point.clone())
-        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    // Start metrics and control API server
+    let metrics_port = config.metrics_port;
+    let rtsp_port = config.rtsp_port;
+    let snapshot_max_width = config.snapshot_max_width;
+    let cors_origins = config.cors_origins.clone();
+    let api_service = streaming_service.clone();
+    tokio::spawn(async move {
+        serve_metrics(
+            metrics_port,
+            api_service,
+            rtsp_port,
+            snapshot_max_width,
+            cors_origins,
+        )
+        .await;
+    });
+    info!("Metrics server started on port {}", config.metrics_port);
 
-    // Start streaming (use case)
-    let session = streaming_service
-        .start_streaming(stream_config, server_config)
-        .await
-        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    // Convert CLI config to domain configs and start streaming (use case)
+    let mut sessions = Vec::new();
+    for spec in config.mount_specs()? {
+        let stream_config = spec
+            .to_stream_config()
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        let server_config = spec
+            .to_server_config(config.rtsp_port)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        let session = streaming_service
+            .start_streaming(stream_config, server_config)
+            .await
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        sessions.push(session);
+    }
+
+    // Swap files of scheduled mounts at their window boundaries
+    let scheduler = streaming_service.clone().spawn_scheduler();
+
+    // Publish clips dropped into the watch directory on /clips/<stem>
+    let watch_publisher = match &config.watch_dir {
+        Some(directory) => {
+            let events = InotifyWatcher::spawn(directory)
+                .map_err(|e| anyhow::anyhow!("Cannot watch {:?}: {}", directory, e))?;
+            info!("Watching {:?} for clips", directory);
+            Some(
+                streaming_service
+                    .clone()
+                    .spawn_watch_publisher(events, config.rtsp_port),
+            )
+        }
+        None => None,
+    };
 
     info!("-------------------------------------------------------");
     info!("RTSP Server Ready");
-    info!(
-        "   URL:     rtsp://0.0.0.0:{}{}",
-        config.rtsp_port, config.mount_point
-    );
-    info!("   Video:   {:?}", config.video_path);
-    info!("   Session: {}", session.id());
+    for session in &sessions {
+        info!(
+            "   URL:     {}://0.0.0.0:{}{}",
+            scheme,
+            config.rtsp_port,
+            session.server_config().mount_point()
+        );
+        match session.stream_config().test_pattern() {
+            Some(pattern) => info!("   Video:   test pattern {}", pattern.pattern()),
+            None => info!("   Video:   {:?}", session.stream_config().source_path()),
+        }
+        info!(
+            "   Format:  {} in {}",
+            session.stream_config().codec().as_str(),
+            session.stream_config().container().as_str()
+        );
+        info!("   Session: {}", session.id());
+    }
     info!("   Metrics: http://0.0.0.0:{}/metrics", config.metrics_port);
     info!("   Health:  http://0.0.0.0:{}/health", config.metrics_port);
+    info!("   Ready:   http://0.0.0.0:{}/readyz", config.metrics_port);
+    info!("   Mounts:  http://0.0.0.0:{}/mounts", config.metrics_port);
     info!("-------------------------------------------------------");
 
     // Create main loop for GStreamer
     let main_loop = glib::MainLoop::new(None, false);
     let main_loop_clone = main_loop.clone();
 
-    // Handle graceful shutdown
+    // Handle graceful shutdown; the main loop keeps serving clients while they drain
+    let drain_service = streaming_service.clone();
+    let drain_timeout = Duration::from_secs(config.drain_timeout_secs);
     tokio::spawn(async move {
-        match tokio::signal::ctrl_c().await {
-            Ok(()) => {
-                info!("Shutdown signal received (Ctrl+C)");
+        match shutdown_signal().await {
+            Ok(name) => {
+                info!("Shutdown signal received ({})", name);
+                scheduler.abort();
+                if let Some(watch_publisher) = watch_publisher {
+                    watch_publisher.abort();
+                }
+                if let Err(err) = drain_service.drain(drain_timeout).await {
+                    error!("Failed to drain clients: {}", err);
+                }
                 main_loop_clone.quit();
             }
             Err(err) => {
-                error!("Failed to listen for shutdown signal: {}", err);
+                error!("Failed to listen for shutdown signals: {}", err);
             }
         }
     });
@@ -107,3 +188,13 @@ async fn main() -> Result<()> {
     info!("Server stopped gracefully");
     Ok(())
 }
+
+/// Wait for SIGTERM (`docker stop`) or SIGINT (Ctrl+C), returning the signal's name
+async fn shutdown_signal() -> std::io::Result<&'static str> {
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    Ok(tokio::select! {
+        _ = terminate.recv() => "SIGTERM",
+        _ = interrupt.recv() => "SIGINT",
+    })
+}
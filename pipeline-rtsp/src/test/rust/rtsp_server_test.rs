@@ -1,8 +1,179 @@
+use async_trait::async_trait;
+use clap::Parser;
 use pipeline_rtsp::{
-    Config, GStreamerRtspServer, PipelineBuilder, PrometheusReporter, ServerConfig, StreamConfig,
-    StreamingServer,
+    chaos_routes, client_routes, cors_policy, mount_routes, readiness_routes, recording_routes,
+    snapshot_routes, AudioCodec, AuthConfig, AuthMethod, ClientSession, ClientTransport, Config,
+    ContainerFormat, DiscovererProbe, DomainError, EncoderProfile, Fault, FaultSpec,
+    GStreamerRtspServer, HeaderExtension, MediaProbe, MetricsReporter, MountSpec, PipelineBuilder,
+    Playlist, PlaylistItem, PrometheusReporter, RtpProfile, RtspUser, ServerConfig, SharingMode,
+    SnapshotJob, SourceKind, StreamConfig, StreamSession, StreamingServer, StreamingService,
+    TlsConfig, TransportPolicy, VideoCodec, WatchEvent,
 };
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use warp::Filter;
+
+/// In-memory server so the control API can be tested without GStreamer
+#[derive(Default)]
+struct FakeServer {
+    sessions: BTreeMap<String, StreamSession>,
+    clients: Vec<ClientSession>,
+}
+
+#[async_trait]
+impl StreamingServer for FakeServer {
+    async fn start(
+        &mut self,
+        stream_config: StreamConfig,
+        server_config: ServerConfig,
+    ) -> pipeline_rtsp::Result<StreamSession> {
+        let mount_point = server_config.mount_point().to_string();
+        if self.sessions.contains_key(&mount_point) {
+            return Err(DomainError::DuplicateMount(mount_point));
+        }
+        let mut session = StreamSession::new(stream_config, server_config);
+        session.activate();
+        self.sessions.insert(mount_point, session.clone());
+        Ok(session)
+    }
+
+    async fn remove(&mut self, mount_point: &str) -> pipeline_rtsp::Result<StreamSession> {
+        self.sessions
+            .remove(mount_point)
+            .ok_or_else(|| DomainError::MountNotFound(mount_point.to_string()))
+    }
+
+    async fn switch_source(
+        &mut self,
+        mount_point: &str,
+        source: &Path,
+    ) -> pipeline_rtsp::Result<()> {
+        let session = self
+            .sessions
+            .get_mut(mount_point)
+            .ok_or_else(|| DomainError::MountNotFound(mount_point.to_string()))?;
+        session.set_active_source(source.to_path_buf());
+        Ok(())
+    }
+
+    async fn inject_fault(
+        &mut self,
+        mount_point: &str,
+        _fault: Fault,
+    ) -> pipeline_rtsp::Result<()> {
+        let session = self
+            .sessions
+            .get_mut(mount_point)
+            .ok_or_else(|| DomainError::MountNotFound(mount_point.to_string()))?;
+        session.add_fault();
+        Ok(())
+    }
+
+    fn snapshot(&self, mount_point: &str, max_width: u32) -> pipeline_rtsp::Result<SnapshotJob> {
+        if !self.sessions.contains_key(mount_point) {
+            return Err(DomainError::MountNotFound(mount_point.to_string()));
+        }
+        Ok(Box::new(move || {
+            Ok(format!("jpeg {}", max_width).into_bytes())
+        }))
+    }
+
+    async fn drain(&mut self) -> pipeline_rtsp::Result<()> {
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> pipeline_rtsp::Result<()> {
+        self.sessions.clear();
+        Ok(())
+    }
+
+    fn is_running(&self) -> bool {
+        !self.sessions.is_empty()
+    }
+
+    fn session(&self, mount_point: &str) -> Option<StreamSession> {
+        self.sessions.get(mount_point).cloned()
+    }
+
+    fn sessions(&self) -> Vec<StreamSession> {
+        self.sessions.values().cloned().collect()
+    }
+
+    fn clients(&self) -> Vec<ClientSession> {
+        self.clients.clone()
+    }
+}
+
+struct NoopReporter;
+
+impl MetricsReporter for NoopReporter {
+    fn report_session_started(&self, _session: &StreamSession) {}
+    fn report_session_stopped(&self, _session: &StreamSession) {}
+    fn report_client_connected(&self, _mount_point: &str) {}
+    fn report_client_disconnected(&self, _mount_point: &str) {}
+    fn report_bytes_sent(&self, _mount_point: &str, _bytes: u64) {}
+    fn report_unauthenticated_attempt(&self) {}
+    fn report_srtp_negotiated(&self, _mount_point: &str) {}
+    fn report_client_transport(&self, _mount_point: &str, _transport: ClientTransport) {}
+    fn report_loop_completed(&self, _mount_point: &str) {}
+    fn report_fault_injected(&self, _mount_point: &str, _fault: &Fault) {}
+    fn report_watch_event(&self, _event: &WatchEvent, _accepted: bool) {}
+}
+
+fn create_fake_service() -> Arc<StreamingService> {
+    Arc::new(StreamingService::new(
+        Box::new(FakeServer::default()),
+        Arc::new(NoopReporter),
+    ))
+}
+
+/// Add a mount through the control API and return the created mount
+async fn mount_with<F>(routes: &F, body: serde_json::Value) -> serde_json::Value
+where
+    F: Filter + 'static,
+    F::Extract: warp::Reply + Send,
+{
+    let created = warp::test::request()
+        .method("POST")
+        .path("/mounts")
+        .json(&body)
+        .reply(routes)
+        .await;
+    assert_eq!(created.status(), 201);
+    serde_json::from_slice(created.body()).unwrap()
+}
+
+/// Mount `video_path` at `/clips/lobby` through the control API
+async fn mount_clip<F>(routes: &F, video_path: &Path) -> serde_json::Value
+where
+    F: Filter + 'static,
+    F::Extract: warp::Reply + Send,
+{
+    mount_with(
+        routes,
+        serde_json::json!({"mount_point": "/clips/lobby", "video_path": video_path}),
+    )
+    .await
+}
+
+/// Send a request for `/mounts/cam9{suffix}`, a mount that does not exist, and expect a 404
+///
+/// Routes that take a body get a valid fault, so only the missing mount can fail them.
+async fn assert_unknown_mount<F>(routes: &F, method: &str, suffix: &str)
+where
+    F: Filter + 'static,
+    F::Extract: warp::Reply + Send,
+{
+    let missing = warp::test::request()
+        .method(method)
+        .path(&format!("/mounts/cam9{}", suffix))
+        .json(&serde_json::json!({"kind": "eos"}))
+        .reply(routes)
+        .await;
+    assert_eq!(missing.status(), 404);
+}
 
 fn create_test_config() -> Config {
     Config {
@@ -10,9 +181,46 @@ fn create_test_config() -> Config {
             env!("CARGO_MANIFEST_DIR"),
             "/src/main/resources/camera1.mp4"
         )),
+        source: SourceKind::File,
+        pattern: "smpte".to_string(),
+        pattern_width: 1280,
+        pattern_height: 720,
+        pattern_fps: 30,
+        pattern_overlay: None,
+        playlist: vec![],
+        shuffle: false,
+        schedule: vec![],
+        schedule_utc_offset: 0,
+        audio_codec: None,
+        transcode: None,
+        transcode_bitrate: 2000,
+        transcode_gop: 60,
         rtsp_port: 8554,
         mount_point: "/cam1".to_string(),
+        mounts_file: None,
+        watch_dir: None,
+        rtsp_users: vec![],
+        auth_methods: vec![AuthMethod::Basic, AuthMethod::Digest],
+        tls_cert: None,
+        tls_key: None,
+        rtp_profiles: vec![RtpProfile::Avp],
+        multicast_addresses: None,
+        multicast_ports: "5000-5999".to_string(),
+        multicast_ttl: 16,
+        transport: TransportPolicy::Both,
+        udp_ports: None,
+        sharing_mode: SharingMode::Shared,
+        max_loops: None,
+        live: false,
+        header_extension: None,
+        record_dir: None,
+        record_segment_secs: 60,
+        record_max_segments: None,
+        chaos: false,
+        snapshot_max_width: 640,
+        drain_timeout_secs: 10,
         metrics_port: 9001,
+        cors_origins: Vec::new(),
         verbose: false,
     }
 }
@@ -49,6 +257,63 @@ fn test_pipeline_parsing() {
     );
 }
 
+#[test]
+fn test_audio_pipeline_parsing() {
+    gstreamer::init().unwrap();
+    let stream_config =
+        StreamConfig::new(create_test_config().video_path).with_audio_codec(AudioCodec::AAC);
+    let launch = PipelineBuilder::build_launch_string(&stream_config);
+    let result = gstreamer::parse::launch(&launch);
+
+    assert!(
+        result.is_ok(),
+        "Audio pipeline should parse successfully: {:?}",
+        result.err()
+    );
+}
+
+#[test]
+fn test_pipeline_parsing_keeps_spaces_in_file_names() {
+    use gstreamer::prelude::*;
+
+    gstreamer::init().unwrap();
+    let video_path = PathBuf::from("/videos/lobby camera!.mp4");
+    let launch = PipelineBuilder::build_launch_string(&StreamConfig::new(video_path.clone()));
+    let pipeline = gstreamer::parse::launch(&launch)
+        .unwrap()
+        .downcast::<gstreamer::Bin>()
+        .unwrap();
+
+    let filesrc = pipeline
+        .iterate_recurse()
+        .into_iter()
+        .flatten()
+        .find(|element| element.factory().is_some_and(|f| f.name() == "filesrc"))
+        .expect("filesrc in pipeline");
+    assert_eq!(
+        filesrc.property::<Option<String>>("location"),
+        Some(video_path.display().to_string())
+    );
+}
+
+#[test]
+fn test_discoverer_detects_sample_format() {
+    gstreamer::init().unwrap();
+    let video_path = create_test_config().video_path;
+
+    // Skip test if video file doesn't exist
+    if !video_path.exists() {
+        eprintln!("Skipping test: video file not found at {:?}", video_path);
+        return;
+    }
+
+    let info = DiscovererProbe::new().probe(&video_path).unwrap();
+
+    assert_eq!(*info.container(), ContainerFormat::MP4);
+    assert_eq!(info.video_codec(), Some(&VideoCodec::H264));
+    assert!(info.resolution().is_some());
+}
+
 #[tokio::test]
 async fn test_create_rtsp_server() {
     gstreamer::init().unwrap();
@@ -66,7 +331,7 @@ async fn test_create_rtsp_server() {
     let stream_config = StreamConfig::new(cli_config.video_path);
     let server_config = ServerConfig::new(cli_config.rtsp_port, cli_config.mount_point).unwrap();
 
-    let mut server = GStreamerRtspServer::new();
+    let mut server = GStreamerRtspServer::new(Arc::new(PrometheusReporter::new()));
     let result = server.start(stream_config, server_config).await;
 
     // Note: This test may fail in environments without a GLib main loop context
@@ -87,6 +352,1575 @@ async fn test_create_rtsp_server() {
     }
 }
 
+/// Send DESCRIBE over TLS, trusting whatever certificate the server presents
+fn rtsps_describe(port: u16, mount_point: &str) -> String {
+    use gio::prelude::*;
+
+    let client = gio::SocketClient::new();
+    client.set_tls(true);
+    client.connect_event(|_client, event, _connectable, connection| {
+        if event != gio::SocketClientEvent::TlsHandshaking {
+            return;
+        }
+        if let Some(tls) = connection.and_then(|c| c.downcast_ref::<gio::TlsClientConnection>()) {
+            tls.connect_accept_certificate(|_connection, _certificate, _errors| true);
+        }
+    });
+
+    let connection = client
+        .connect_to_host(&format!("127.0.0.1:{}", port), 0, gio::Cancellable::NONE)
+        .expect("TLS connection");
+    let request = format!(
+        "DESCRIBE rtsps://127.0.0.1:{}{} RTSP/1.0\r\nCSeq: 1\r\nAccept: application/sdp\r\n\r\n",
+        port, mount_point
+    );
+    connection
+        .output_stream()
+        .write_all(request.as_bytes(), gio::Cancellable::NONE)
+        .expect("send DESCRIBE");
+
+    // Headers and SDP may arrive in several TLS records
+    let mut response = Vec::new();
+    let mut buffer = [0u8; 4096];
+    while let Ok(read) = connection
+        .input_stream()
+        .read(&mut buffer, gio::Cancellable::NONE)
+    {
+        if read == 0 {
+            break;
+        }
+        response.extend_from_slice(&buffer[..read]);
+        if String::from_utf8_lossy(&response).contains("a=control") {
+            break;
+        }
+    }
+    String::from_utf8_lossy(&response).into_owned()
+}
+
+/// Send DESCRIBE over plain RTSP and return the response with its SDP body
+fn rtsp_describe(port: u16, mount_point: &str) -> String {
+    use std::io::{Read, Write};
+
+    let mut stream = std::net::TcpStream::connect(("127.0.0.1", port)).expect("connect");
+    stream
+        .set_read_timeout(Some(std::time::Duration::from_secs(10)))
+        .unwrap();
+    let request = format!(
+        "DESCRIBE rtsp://127.0.0.1:{}{} RTSP/1.0\r\nCSeq: 1\r\nAccept: application/sdp\r\n\r\n",
+        port, mount_point
+    );
+    stream.write_all(request.as_bytes()).expect("send DESCRIBE");
+
+    // Headers and SDP may arrive in several reads
+    let mut response = Vec::new();
+    let mut buffer = [0u8; 4096];
+    while let Ok(read) = stream.read(&mut buffer) {
+        if read == 0 {
+            break;
+        }
+        response.extend_from_slice(&buffer[..read]);
+        if String::from_utf8_lossy(&response).contains("a=control") {
+            break;
+        }
+    }
+    String::from_utf8_lossy(&response).into_owned()
+}
+
+/// Read one RTSP response, headers and body
+fn read_rtsp_response(stream: &mut std::net::TcpStream) -> String {
+    use std::io::Read;
+
+    let mut response = Vec::new();
+    let mut byte = [0u8; 1];
+    while !response.ends_with(b"\r\n\r\n") {
+        stream.read_exact(&mut byte).expect("read response");
+        response.push(byte[0]);
+    }
+    let headers = String::from_utf8_lossy(&response).into_owned();
+    let length = headers
+        .lines()
+        .find_map(|line| line.strip_prefix("Content-Length:"))
+        .map_or(0, |length| length.trim().parse().unwrap());
+    let mut body = vec![0u8; length];
+    stream.read_exact(&mut body).expect("read body");
+    headers + &String::from_utf8_lossy(&body)
+}
+
+/// Start playing a video-only mount with RTP and RTCP interleaved on channels 0 and 1
+fn rtsp_play_interleaved(port: u16, mount_point: &str) -> std::net::TcpStream {
+    use std::io::Write;
+
+    let url = format!("rtsp://127.0.0.1:{}{}", port, mount_point);
+    let mut stream = std::net::TcpStream::connect(("127.0.0.1", port)).expect("connect");
+    stream
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+
+    let describe = format!(
+        "DESCRIBE {} RTSP/1.0\r\nCSeq: 1\r\nAccept: application/sdp\r\n\r\n",
+        url
+    );
+    stream.write_all(describe.as_bytes()).unwrap();
+    let sdp = read_rtsp_response(&mut stream);
+    let control = sdp
+        .lines()
+        .find_map(|line| line.strip_prefix("a=control:stream="))
+        .expect("stream control");
+
+    let setup = format!(
+        "SETUP {}/stream={} RTSP/1.0\r\nCSeq: 2\r\nTransport: RTP/AVP/TCP;unicast;interleaved=0-1\r\n\r\n",
+        url,
+        control.trim()
+    );
+    stream.write_all(setup.as_bytes()).unwrap();
+    let response = read_rtsp_response(&mut stream);
+    let session = response
+        .lines()
+        .find_map(|line| line.strip_prefix("Session:"))
+        .and_then(|session| session.split(';').next())
+        .expect("session")
+        .trim()
+        .to_string();
+
+    let play = format!(
+        "PLAY {} RTSP/1.0\r\nCSeq: 3\r\nSession: {}\r\n\r\n",
+        url, session
+    );
+    stream.write_all(play.as_bytes()).unwrap();
+    read_rtsp_response(&mut stream);
+    stream
+}
+
+/// Play a video-only mount with RTP interleaved over RTSP, discarding data for `duration`
+fn rtsp_play(port: u16, mount_point: &str, duration: Duration) {
+    use std::io::Read;
+
+    let mut stream = rtsp_play_interleaved(port, mount_point);
+    let started = std::time::Instant::now();
+    let mut buffer = [0u8; 4096];
+    while started.elapsed() < duration {
+        if stream.read(&mut buffer).unwrap_or(0) == 0 {
+            break;
+        }
+    }
+}
+
+/// Next packet interleaved on an RTSP connection, with its channel
+fn read_interleaved(stream: &mut std::net::TcpStream) -> (u8, Vec<u8>) {
+    use std::io::Read;
+
+    let mut header = [0u8; 4];
+    stream
+        .read_exact(&mut header)
+        .expect("read interleaved header");
+    assert_eq!(header[0], b'$', "expected interleaved data");
+    let mut packet = vec![0u8; usize::from(u16::from_be_bytes([header[2], header[3]]))];
+    stream
+        .read_exact(&mut packet)
+        .expect("read interleaved packet");
+    (header[1], packet)
+}
+
+/// Encode `frames` frames of generated video into an H.264 MP4 file
+fn write_test_clip(path: &Path, frames: u32) {
+    use gstreamer::prelude::*;
+
+    let pipeline = gstreamer::parse::launch(&format!(
+        "videotestsrc num-buffers={} ! video/x-raw,width=320,height=240,framerate=30/1 ! x264enc ! h264parse ! mp4mux ! filesink location={}",
+        frames,
+        path.display()
+    ))
+    .unwrap();
+    pipeline.set_state(gstreamer::State::Playing).unwrap();
+    let bus = pipeline.bus().unwrap();
+    bus.timed_pop_filtered(
+        gstreamer::ClockTime::NONE,
+        &[gstreamer::MessageType::Eos, gstreamer::MessageType::Error],
+    );
+    pipeline.set_state(gstreamer::State::Null).unwrap();
+}
+
+#[tokio::test]
+async fn test_looping_playlist_plays_every_clip() {
+    gstreamer::init().unwrap();
+
+    // Two one-second clips; the playlist loops only once both played
+    let dir = tempfile::tempdir().unwrap();
+    let clips = [dir.path().join("first.mp4"), dir.path().join("second.mp4")];
+    for clip in &clips {
+        write_test_clip(clip, 30);
+    }
+    let playlist = Playlist::new(clips.iter().cloned().map(PlaylistItem::new).collect()).unwrap();
+
+    let mut server = GStreamerRtspServer::new(Arc::new(NoopReporter));
+    server
+        .start(
+            StreamConfig::from_playlist(playlist),
+            ServerConfig::new(8576, "/playlist".to_string()).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let main_loop = glib::MainLoop::new(None, false);
+    let loop_thread = {
+        let main_loop = main_loop.clone();
+        std::thread::spawn(move || main_loop.run())
+    };
+
+    rtsp_play(8576, "/playlist", Duration::from_secs(5));
+
+    main_loop.quit();
+    loop_thread.join().unwrap();
+    let session = server.session("/playlist").unwrap();
+    let _ = server.stop().await;
+
+    assert!(
+        session.loop_count() >= 1,
+        "playlist never looped past its clips"
+    );
+}
+
+#[tokio::test]
+async fn test_per_client_mount_reports_duration() {
+    gstreamer::init().unwrap();
+    let cli_config = create_test_config();
+
+    // Skip test if video file doesn't exist
+    if !cli_config.video_path.exists() {
+        eprintln!(
+            "Skipping test: video file not found at {:?}",
+            cli_config.video_path
+        );
+        return;
+    }
+
+    let server_config = ServerConfig::new(8573, "/vod".to_string())
+        .unwrap()
+        .with_sharing_mode(SharingMode::PerClient);
+    let mut server = GStreamerRtspServer::new(Arc::new(NoopReporter));
+    server
+        .start(StreamConfig::new(cli_config.video_path), server_config)
+        .await
+        .unwrap();
+
+    let main_loop = glib::MainLoop::new(None, false);
+    let loop_thread = {
+        let main_loop = main_loop.clone();
+        std::thread::spawn(move || main_loop.run())
+    };
+
+    let response = rtsp_describe(8573, "/vod");
+
+    main_loop.quit();
+    loop_thread.join().unwrap();
+    let session = server.session("/vod").unwrap();
+    let _ = server.stop().await;
+
+    assert!(
+        response.starts_with("RTSP/1.0 200 OK"),
+        "unexpected response: {}",
+        response
+    );
+    // A seekable per-client media advertises a bounded range rather than npt=now-
+    assert!(
+        response.contains("a=range:npt=0"),
+        "no range in: {}",
+        response
+    );
+    assert!(!session.server_config().looping_enabled());
+}
+
+#[tokio::test]
+async fn test_rtsps_with_self_signed_certificate() {
+    gstreamer::init().unwrap();
+    let cli_config = create_test_config();
+
+    // Skip test if video file doesn't exist
+    if !cli_config.video_path.exists() {
+        eprintln!(
+            "Skipping test: video file not found at {:?}",
+            cli_config.video_path
+        );
+        return;
+    }
+
+    // Self-signed certificate generated for this run only
+    let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let cert_path = dir.path().join("cert.pem");
+    let key_path = dir.path().join("key.pem");
+    std::fs::write(&cert_path, certified.cert.pem()).unwrap();
+    std::fs::write(&key_path, certified.key_pair.serialize_pem()).unwrap();
+
+    let tls = TlsConfig::new(cert_path, key_path).unwrap();
+    let mut server = GStreamerRtspServer::new(Arc::new(NoopReporter)).with_tls(tls);
+    server
+        .start(
+            StreamConfig::new(cli_config.video_path),
+            ServerConfig::new(8571, "/secure".to_string()).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // The server is attached to the default main context; serve it from another thread
+    let main_loop = glib::MainLoop::new(None, false);
+    let loop_thread = {
+        let main_loop = main_loop.clone();
+        std::thread::spawn(move || main_loop.run())
+    };
+
+    let response = rtsps_describe(8571, "/secure");
+
+    main_loop.quit();
+    loop_thread.join().unwrap();
+    let _ = server.stop().await;
+
+    assert!(
+        response.starts_with("RTSP/1.0 200 OK"),
+        "unexpected response: {}",
+        response
+    );
+    // The media was prepared and described through the encrypted connection
+    assert!(response.contains("m=video"), "no SDP in: {}", response);
+}
+
+/// Reporter that only counts failed authentication attempts
+#[derive(Default)]
+struct AuthFailureCounter {
+    failures: std::sync::atomic::AtomicU32,
+}
+
+impl MetricsReporter for AuthFailureCounter {
+    fn report_session_started(&self, _session: &StreamSession) {}
+    fn report_session_stopped(&self, _session: &StreamSession) {}
+    fn report_client_connected(&self, _mount_point: &str) {}
+    fn report_client_disconnected(&self, _mount_point: &str) {}
+    fn report_bytes_sent(&self, _mount_point: &str, _bytes: u64) {}
+    fn report_unauthenticated_attempt(&self) {
+        self.failures
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+    fn report_srtp_negotiated(&self, _mount_point: &str) {}
+    fn report_client_transport(&self, _mount_point: &str, _transport: ClientTransport) {}
+    fn report_loop_completed(&self, _mount_point: &str) {}
+    fn report_fault_injected(&self, _mount_point: &str, _fault: &Fault) {}
+    fn report_watch_event(&self, _event: &WatchEvent, _accepted: bool) {}
+}
+
+#[tokio::test]
+async fn test_only_rejected_credentials_count_as_failed_auth() {
+    use std::io::Write;
+
+    gstreamer::init().unwrap();
+    let cli_config = create_test_config();
+
+    // Skip test if video file doesn't exist
+    if !cli_config.video_path.exists() {
+        eprintln!(
+            "Skipping test: video file not found at {:?}",
+            cli_config.video_path
+        );
+        return;
+    }
+
+    let user = RtspUser::new("alice".to_string(), "secret".to_string()).unwrap();
+    let auth = AuthConfig::new(vec![user], vec![AuthMethod::Basic]).unwrap();
+    let counter = Arc::new(AuthFailureCounter::default());
+    let mut server = GStreamerRtspServer::new(counter.clone()).with_auth(auth);
+    server
+        .start(
+            StreamConfig::new(cli_config.video_path),
+            ServerConfig::new(8577, "/cam1".to_string()).unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let main_loop = glib::MainLoop::new(None, false);
+    let loop_thread = {
+        let main_loop = main_loop.clone();
+        std::thread::spawn(move || main_loop.run())
+    };
+
+    let mut stream = std::net::TcpStream::connect(("127.0.0.1", 8577)).expect("connect");
+    stream
+        .set_read_timeout(Some(Duration::from_secs(10)))
+        .unwrap();
+    let describe = |cseq: u32, authorization: &str| {
+        format!(
+            "DESCRIBE rtsp://127.0.0.1:8577/cam1 RTSP/1.0\r\nCSeq: {}\r\n{}Accept: application/sdp\r\n\r\n",
+            cseq, authorization
+        )
+    };
+    // Clients first ask without credentials to learn the scheme
+    stream.write_all(describe(1, "").as_bytes()).unwrap();
+    let challenge = read_rtsp_response(&mut stream);
+    // "alice:wrong"
+    stream
+        .write_all(describe(2, "Authorization: Basic YWxpY2U6d3Jvbmc=\r\n").as_bytes())
+        .unwrap();
+    let rejected = read_rtsp_response(&mut stream);
+
+    main_loop.quit();
+    loop_thread.join().unwrap();
+    let _ = server.stop().await;
+
+    assert!(challenge.starts_with("RTSP/1.0 401"), "{}", challenge);
+    assert!(rejected.starts_with("RTSP/1.0 401"), "{}", rejected);
+    assert_eq!(
+        counter.failures.load(std::sync::atomic::Ordering::Relaxed),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_serve_multiple_mounts() {
+    gstreamer::init().unwrap();
+    let cli_config = create_test_config();
+
+    // Skip test if video file doesn't exist
+    if !cli_config.video_path.exists() {
+        eprintln!(
+            "Skipping test: video file not found at {:?}",
+            cli_config.video_path
+        );
+        return;
+    }
+
+    let mut server = GStreamerRtspServer::new(Arc::new(PrometheusReporter::new()));
+    for mount in ["/cam1", "/cam2"] {
+        let stream_config = StreamConfig::new(cli_config.video_path.clone());
+        let server_config = ServerConfig::new(8564, mount.to_string()).unwrap();
+        if let Err(e) = server.start(stream_config, server_config).await {
+            eprintln!(
+                "RTSP server creation failed (expected without GLib main loop): {:?}",
+                e
+            );
+            return;
+        }
+    }
+
+    assert_eq!(server.sessions().len(), 2);
+    assert!(server.session("/cam2").is_some());
+
+    // Same mount twice is rejected
+    let duplicate = server
+        .start(
+            StreamConfig::new(cli_config.video_path.clone()),
+            ServerConfig::new(8564, "/cam1".to_string()).unwrap(),
+        )
+        .await;
+    assert!(matches!(duplicate, Err(DomainError::DuplicateMount(_))));
+
+    // A different port cannot share the server
+    let other_port = server
+        .start(
+            StreamConfig::new(cli_config.video_path.clone()),
+            ServerConfig::new(8565, "/cam3".to_string()).unwrap(),
+        )
+        .await;
+    assert!(matches!(other_port, Err(DomainError::PortMismatch { .. })));
+
+    let _ = server.stop().await;
+    assert!(server.sessions().is_empty());
+}
+
+#[test]
+fn test_mount_spec_parsing() {
+    let specs: Vec<MountSpec> = serde_json::from_str(
+        r#"[
+            {"mount_point": "/cam2", "video_path": "/videos/b.mp4", "codec": "h265"},
+            {"mount_point": "/cam3", "video_path": "/videos/c.mkv", "container": "mkv", "audio_codec": "opus", "looping": false}
+        ]"#,
+    )
+    .unwrap();
+
+    assert_eq!(specs.len(), 2);
+
+    let stream_config = specs[0].to_stream_config().unwrap();
+    assert_eq!(*stream_config.codec(), VideoCodec::H265);
+    assert_eq!(stream_config.audio_codec(), None);
+    assert!(specs[0].to_server_config(8554).unwrap().looping_enabled());
+    assert!(!specs[1].to_server_config(8554).unwrap().looping_enabled());
+    assert_eq!(
+        specs[1].to_stream_config().unwrap().audio_codec(),
+        Some(&AudioCodec::Opus)
+    );
+
+    let invalid = MountSpec {
+        codec: Some("mpeg2".to_string()),
+        ..specs[0].clone()
+    };
+    assert!(matches!(
+        invalid.to_stream_config(),
+        Err(DomainError::UnsupportedCodec(_))
+    ));
+}
+
+#[test]
+fn test_transcode_mount_spec() {
+    let spec: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/cam4", "video_path": "/videos/d.mkv", "codec": "mjpeg",
+            "transcode": {"profile": "main", "bitrate_kbps": 1500}}"#,
+    )
+    .unwrap();
+
+    let stream_config = spec.to_stream_config().unwrap();
+    let profile = stream_config.transcode().unwrap();
+    assert_eq!(*profile.profile(), EncoderProfile::Main);
+    assert_eq!(profile.bitrate_kbps(), 1500);
+    assert_eq!(profile.gop_length(), 60);
+    assert_eq!(*stream_config.output_codec(), VideoCodec::H264);
+
+    let mut config = create_test_config();
+    config.transcode = Some("baseline".to_string());
+    let default_mount = config.mount_specs().unwrap().remove(0);
+    assert!(default_mount
+        .to_stream_config()
+        .unwrap()
+        .transcode()
+        .is_some());
+}
+
+#[test]
+fn test_auth_config_from_cli() {
+    let config = Config::parse_from([
+        "pipeline-rtsp",
+        "--rtsp-user",
+        "alice:secret,bob:hunter2",
+        "--auth-methods",
+        "digest",
+    ]);
+
+    let auth = config.auth_config().unwrap().unwrap();
+    assert_eq!(auth.users().len(), 2);
+    assert!(auth.supports(AuthMethod::Digest));
+    assert!(!auth.supports(AuthMethod::Basic));
+    assert!(!format!("{:?}", config).contains("hunter2"));
+
+    let spec: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/cam2", "video_path": "/videos/b.mp4", "users": ["bob"]}"#,
+    )
+    .unwrap();
+    let server_config = spec.to_server_config(8554).unwrap();
+    assert_eq!(auth.users_for_mount(&server_config).unwrap(), vec!["bob"]);
+
+    assert!(create_test_config().auth_config().unwrap().is_none());
+}
+
+#[test]
+fn test_tls_config_requires_cert_and_key() {
+    let cert = tempfile::NamedTempFile::new().unwrap();
+    let key = tempfile::NamedTempFile::new().unwrap();
+
+    let mut config = create_test_config();
+    assert!(config.tls_config().unwrap().is_none());
+
+    config.tls_cert = Some(cert.path().to_path_buf());
+    assert!(config.tls_config().is_err());
+
+    config.tls_key = Some(key.path().to_path_buf());
+    let tls = config.tls_config().unwrap().unwrap();
+    assert_eq!(tls.key_path(), key.path());
+}
+
+#[test]
+fn test_config_rejects_duplicate_mounts() {
+    let mut mounts_file = tempfile::NamedTempFile::new().unwrap();
+    std::io::Write::write_all(
+        &mut mounts_file,
+        br#"[{"mount_point": "/cam1", "video_path": "/videos/b.mp4"}]"#,
+    )
+    .unwrap();
+
+    let mut config = create_test_config();
+    config.mounts_file = Some(mounts_file.path().to_path_buf());
+
+    let specs = config.mount_specs().unwrap();
+    assert_eq!(specs.len(), 2);
+
+    if config.video_path.exists() {
+        assert!(config.validate().is_err());
+    }
+}
+
+#[tokio::test]
+async fn test_mount_api_lifecycle() {
+    let video = tempfile::NamedTempFile::new().unwrap();
+    let service = create_fake_service();
+    let routes = mount_routes(service.clone(), 8554);
+
+    let body = serde_json::json!({
+        "mount_point": "/clips/lobby",
+        "video_path": video.path(),
+    });
+
+    let created = warp::test::request()
+        .method("POST")
+        .path("/mounts")
+        .json(&body)
+        .reply(&routes)
+        .await;
+    assert_eq!(created.status(), 201);
+
+    let duplicate = warp::test::request()
+        .method("POST")
+        .path("/mounts")
+        .json(&body)
+        .reply(&routes)
+        .await;
+    assert_eq!(duplicate.status(), 409);
+
+    let listed = warp::test::request()
+        .method("GET")
+        .path("/mounts")
+        .reply(&routes)
+        .await;
+    let mounts: serde_json::Value = serde_json::from_slice(listed.body()).unwrap();
+    assert_eq!(mounts[0]["mount_point"], "/clips/lobby");
+
+    let removed = warp::test::request()
+        .method("DELETE")
+        .path("/mounts/clips/lobby")
+        .reply(&routes)
+        .await;
+    assert_eq!(removed.status(), 200);
+    assert!(service.sessions().await.is_empty());
+
+    let missing = warp::test::request()
+        .method("DELETE")
+        .path("/mounts/clips/lobby")
+        .reply(&routes)
+        .await;
+    assert_eq!(missing.status(), 404);
+}
+
+#[tokio::test]
+async fn test_cors_limits_mutating_routes_to_listed_origins() {
+    let preflight = |origin: &'static str, method: &'static str| {
+        warp::test::request()
+            .method("OPTIONS")
+            .path("/mounts/cam1")
+            .header("origin", origin)
+            .header("access-control-request-method", method)
+    };
+
+    let read_only = mount_routes(create_fake_service(), 8554).with(cors_policy(&[]));
+    let get = preflight("https://ops.example.com", "GET")
+        .reply(&read_only)
+        .await;
+    assert_eq!(get.status(), 200);
+    let delete = preflight("https://ops.example.com", "DELETE")
+        .reply(&read_only)
+        .await;
+    assert_eq!(delete.status(), 403);
+
+    let origins = vec!["https://ops.example.com".to_string()];
+    let listed = mount_routes(create_fake_service(), 8554).with(cors_policy(&origins));
+    let allowed = preflight("https://ops.example.com", "DELETE")
+        .reply(&listed)
+        .await;
+    assert_eq!(allowed.status(), 200);
+    let other = preflight("https://evil.example.com", "DELETE")
+        .reply(&listed)
+        .await;
+    assert_eq!(other.status(), 403);
+}
+
+#[tokio::test]
+async fn test_mount_api_rejects_invalid_mounts() {
+    let service = create_fake_service();
+    let routes = mount_routes(service, 8554);
+
+    let missing_file = warp::test::request()
+        .method("POST")
+        .path("/mounts")
+        .json(&serde_json::json!({
+            "mount_point": "/cam9",
+            "video_path": "/nonexistent/video.mp4",
+        }))
+        .reply(&routes)
+        .await;
+    assert_eq!(missing_file.status(), 400);
+
+    let bad_mount = warp::test::request()
+        .method("POST")
+        .path("/mounts")
+        .json(&serde_json::json!({
+            "mount_point": "cam9",
+            "video_path": "/nonexistent/video.mp4",
+        }))
+        .reply(&routes)
+        .await;
+    assert_eq!(bad_mount.status(), 400);
+}
+
+#[tokio::test]
+async fn test_client_listing() {
+    let mut client = ClientSession::new(Some("10.0.0.7".to_string()));
+    client.assign_mount("/cam1".to_string());
+    client.set_transport(ClientTransport::Tcp);
+    client.set_rtp_profile(RtpProfile::Savp);
+    client.add_bytes_sent(4096);
+
+    let server = FakeServer {
+        clients: vec![client],
+        ..FakeServer::default()
+    };
+    let service = Arc::new(StreamingService::new(
+        Box::new(server),
+        Arc::new(NoopReporter),
+    ));
+    let routes = client_routes(service);
+
+    let response = warp::test::request()
+        .method("GET")
+        .path("/clients")
+        .reply(&routes)
+        .await;
+    assert_eq!(response.status(), 200);
+
+    let clients: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(clients[0]["remote_address"], "10.0.0.7");
+    assert_eq!(clients[0]["mount_point"], "/cam1");
+    assert_eq!(clients[0]["transport"], "tcp");
+    assert_eq!(clients[0]["bytes_sent"], 4096);
+    assert_eq!(clients[0]["rtp_profile"], "RTP/SAVP");
+    assert_eq!(clients[0]["srtp"], true);
+}
+
+#[test]
+fn test_srtp_mount_spec() {
+    let spec: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/secure", "video_path": "/videos/b.mp4", "rtp_profiles": ["savp", "savpf"]}"#,
+    )
+    .unwrap();
+    let server_config = spec.to_server_config(8554).unwrap();
+    assert_eq!(
+        server_config.rtp_profiles(),
+        &[RtpProfile::Savp, RtpProfile::Savpf]
+    );
+    assert!(server_config.srtp_enabled());
+
+    let default_mount = create_test_config().mount_specs().unwrap().remove(0);
+    assert!(!default_mount.to_server_config(8554).unwrap().srtp_enabled());
+
+    let invalid = MountSpec {
+        rtp_profiles: Some(vec!["srtp".to_string()]),
+        ..spec
+    };
+    assert!(matches!(
+        invalid.to_server_config(8554),
+        Err(DomainError::InvalidRtpProfile(_))
+    ));
+}
+
+#[test]
+fn test_multicast_mount_spec() {
+    let spec: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/mcast", "video_path": "/videos/b.mp4",
+            "multicast": {"addresses": "239.255.0.1-239.255.0.16", "ports": "6000-6099", "ttl": 4}}"#,
+    )
+    .unwrap();
+    let server_config = spec.to_server_config(8554).unwrap();
+    let pool = server_config.multicast().unwrap();
+    assert_eq!(pool.first_address().to_string(), "239.255.0.1");
+    assert_eq!(pool.last_address().to_string(), "239.255.0.16");
+    assert_eq!(pool.ports().first(), 6000);
+    assert_eq!(pool.ttl(), 4);
+
+    let config = Config::parse_from(["pipeline-rtsp", "--multicast-addresses", "239.1.1.1"]);
+    let default_mount = config.mount_specs().unwrap().remove(0);
+    let pool = default_mount
+        .to_server_config(8554)
+        .unwrap()
+        .multicast()
+        .cloned()
+        .unwrap();
+    assert_eq!(pool.first_address(), pool.last_address());
+    assert_eq!(pool.ports().last(), 5999);
+    assert_eq!(pool.ttl(), 16);
+    assert!(create_test_config().mount_specs().unwrap()[0]
+        .multicast
+        .is_none());
+
+    let unicast: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/mcast", "video_path": "/videos/b.mp4",
+            "multicast": {"addresses": "10.0.0.1-10.0.0.9"}}"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        unicast.to_server_config(8554),
+        Err(DomainError::InvalidTransportConfig(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_serve_multicast_mount() {
+    gstreamer::init().unwrap();
+    let cli_config = create_test_config();
+
+    // Skip test if video file doesn't exist
+    if !cli_config.video_path.exists() {
+        eprintln!(
+            "Skipping test: video file not found at {:?}",
+            cli_config.video_path
+        );
+        return;
+    }
+
+    let ports = "5000-5099".parse().unwrap();
+    let pool = pipeline_rtsp::MulticastPool::parse("239.255.0.1-239.255.0.4", ports).unwrap();
+    let server_config = ServerConfig::new(8572, "/mcast".to_string())
+        .unwrap()
+        .with_multicast(pool);
+
+    let mut server = GStreamerRtspServer::new(Arc::new(PrometheusReporter::new()));
+    match server
+        .start(
+            StreamConfig::new(cli_config.video_path.clone()),
+            server_config,
+        )
+        .await
+    {
+        Ok(session) => assert!(session.server_config().multicast().is_some()),
+        Err(e) => eprintln!(
+            "RTSP server creation failed (expected without GLib main loop): {:?}",
+            e
+        ),
+    }
+
+    let _ = server.stop().await;
+}
+
+#[test]
+fn test_transport_policy_mount_spec() {
+    let spec: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/fw", "video_path": "/videos/b.mp4", "transport": "udp", "udp_ports": "20000-20099"}"#,
+    )
+    .unwrap();
+    let server_config = spec.to_server_config(8554).unwrap();
+    assert_eq!(server_config.transport_policy(), TransportPolicy::UdpOnly);
+    assert_eq!(server_config.udp_ports().unwrap().first(), 20000);
+    assert_eq!(server_config.udp_ports().unwrap().last(), 20099);
+
+    let config = Config::parse_from(["pipeline-rtsp", "--transport", "tcp"]);
+    let default_mount = config.mount_specs().unwrap().remove(0);
+    let server_config = default_mount.to_server_config(8554).unwrap();
+    assert_eq!(server_config.transport_policy(), TransportPolicy::TcpOnly);
+    assert!(server_config.udp_ports().is_none());
+
+    // TCP-only mounts cannot carry UDP settings
+    let tcp_with_ports = MountSpec {
+        transport: Some("tcp".to_string()),
+        ..spec.clone()
+    };
+    assert!(matches!(
+        tcp_with_ports.to_server_config(8554),
+        Err(DomainError::InvalidTransportConfig(_))
+    ));
+
+    let reversed = MountSpec {
+        udp_ports: Some("20099-20000".to_string()),
+        ..spec
+    };
+    assert!(reversed.to_server_config(8554).is_err());
+}
+
+#[test]
+fn test_config_validates_transport() {
+    let video = tempfile::NamedTempFile::new().unwrap();
+    let mut config = create_test_config();
+    config.video_path = video.path().to_path_buf();
+    config.udp_ports = Some("20000-20999".parse().unwrap());
+    assert!(config.validate().is_ok());
+
+    config.udp_ports = Some("500-600".parse().unwrap());
+    assert!(config.validate().is_err());
+
+    config.udp_ports = None;
+    config.transport = TransportPolicy::TcpOnly;
+    config.multicast_addresses = Some("239.255.0.1".to_string());
+    assert!(config.validate().is_err());
+
+    config.transport = TransportPolicy::UdpOnly;
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_config_validates_cors_origins() {
+    let video = tempfile::NamedTempFile::new().unwrap();
+    let mut config = create_test_config();
+    config.video_path = video.path().to_path_buf();
+    config.cors_origins = vec!["https://ops.example.com:8443".to_string()];
+    assert!(config.validate().is_ok());
+
+    config.cors_origins = vec!["ops.example.com".to_string()];
+    assert!(config.validate().is_err());
+
+    config.cors_origins = vec!["https://ops.example.com/dashboard".to_string()];
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_sharing_mode_mount_spec() {
+    let spec: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/vod", "video_path": "/videos/b.mp4", "sharing_mode": "per-client"}"#,
+    )
+    .unwrap();
+    let server_config = spec.to_server_config(8554).unwrap();
+    assert_eq!(server_config.sharing_mode(), SharingMode::PerClient);
+    assert!(!server_config.looping_enabled());
+
+    let default_mount = create_test_config().mount_specs().unwrap().remove(0);
+    let server_config = default_mount.to_server_config(8554).unwrap();
+    assert_eq!(server_config.sharing_mode(), SharingMode::Shared);
+    assert!(server_config.looping_enabled());
+
+    let invalid = MountSpec {
+        sharing_mode: Some("exclusive".to_string()),
+        ..spec.clone()
+    };
+    assert!(matches!(
+        invalid.to_server_config(8554),
+        Err(DomainError::InvalidSharingMode(_))
+    ));
+
+    // Multicast relies on every client sharing one media
+    let multicast: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/vod", "video_path": "/videos/b.mp4", "sharing_mode": "vod",
+            "multicast": {"addresses": "239.255.0.1"}}"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        multicast.to_server_config(8554),
+        Err(DomainError::InvalidTransportConfig(_))
+    ));
+}
+
+#[test]
+fn test_max_loops_mount_spec() {
+    let spec: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/promo", "video_path": "/videos/b.mp4", "max_loops": 3}"#,
+    )
+    .unwrap();
+    let server_config = spec.to_server_config(8554).unwrap();
+    assert_eq!(server_config.max_loops(), Some(3));
+    assert!(server_config.continue_looping(2));
+    assert!(!server_config.continue_looping(3));
+
+    let config = Config::parse_from(["pipeline-rtsp", "--max-loops", "1"]);
+    let default_mount = config.mount_specs().unwrap().remove(0);
+    assert_eq!(
+        default_mount.to_server_config(8554).unwrap().max_loops(),
+        Some(1)
+    );
+
+    let unlimited = create_test_config().mount_specs().unwrap().remove(0);
+    assert_eq!(unlimited.to_server_config(8554).unwrap().max_loops(), None);
+}
+
+#[test]
+fn test_live_mount_spec() {
+    let spec: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/cam", "video_path": "/videos/a.mp4", "live": true, "header_extension": "onvif-replay"}"#,
+    )
+    .unwrap();
+    let server_config = spec.to_server_config(8554).unwrap();
+    assert!(server_config.is_live());
+    assert_eq!(
+        server_config.header_extension(),
+        Some(HeaderExtension::OnvifReplay)
+    );
+
+    let config = Config::parse_from(["pipeline-rtsp", "--live", "--header-extension", "ntp-64"]);
+    let default_mount = config.mount_specs().unwrap().remove(0);
+    assert_eq!(
+        default_mount
+            .to_server_config(8554)
+            .unwrap()
+            .header_extension(),
+        Some(HeaderExtension::Ntp64)
+    );
+
+    // Capture timestamps only make sense against the wall clock
+    let not_live: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/cam", "video_path": "/videos/a.mp4", "header_extension": "ntp-64"}"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        not_live.to_server_config(8554),
+        Err(DomainError::InvalidLiveConfig(_))
+    ));
+
+    let per_client: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/cam", "video_path": "/videos/a.mp4", "live": true, "sharing_mode": "per-client"}"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        per_client.to_server_config(8554),
+        Err(DomainError::InvalidLiveConfig(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_live_sender_reports_agree_with_ntp64_extension() {
+    gstreamer::init().unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let clip = dir.path().join("clip.mp4");
+    write_test_clip(&clip, 90);
+
+    let server_config = ServerConfig::new(8578, "/live".to_string())
+        .unwrap()
+        .with_live(true)
+        .with_header_extension(HeaderExtension::Ntp64);
+    let mut server = GStreamerRtspServer::new(Arc::new(NoopReporter));
+    server
+        .start(StreamConfig::new(clip), server_config)
+        .await
+        .unwrap();
+
+    let main_loop = glib::MainLoop::new(None, false);
+    let loop_thread = {
+        let main_loop = main_loop.clone();
+        std::thread::spawn(move || main_loop.run())
+    };
+
+    // RTP and NTP time of every stamped packet up to the first sender report
+    let mut stream = rtsp_play_interleaved(8578, "/live");
+    let mut stamped = Vec::new();
+    let (report_ntp, report_rtp) = loop {
+        let (channel, packet) = read_interleaved(&mut stream);
+        if channel == 1 && packet.get(1) == Some(&200) {
+            break (
+                u64::from_be_bytes(packet[8..16].try_into().unwrap()),
+                u32::from_be_bytes(packet[16..20].try_into().unwrap()),
+            );
+        }
+        if channel != 0 {
+            continue;
+        }
+        let buffer = gstreamer::Buffer::from_slice(packet);
+        let rtp = gstreamer_rtp::RTPBuffer::from_buffer_readable(&buffer).unwrap();
+        if let Some(ntp) = rtp.extension_onebyte_header(1, 0) {
+            stamped.push((rtp.timestamp(), u64::from_be_bytes(ntp.try_into().unwrap())));
+        }
+    };
+
+    main_loop.quit();
+    loop_thread.join().unwrap();
+    let _ = server.stop().await;
+
+    assert!(!stamped.is_empty(), "no packet carried the NTP extension");
+    for (rtp_time, ntp) in stamped {
+        // Time between the packet and the report, by RTP clock and by NTP clock
+        let by_rtp = f64::from(report_rtp.wrapping_sub(rtp_time) as i32) / 90_000.0;
+        let by_ntp = (report_ntp as i64).wrapping_sub(ntp as i64) as f64 / 2f64.powi(32);
+        assert!(
+            (by_rtp - by_ntp).abs() < 0.01,
+            "sender report is {:.3}s after the packet by RTP time but {:.3}s by NTP time",
+            by_rtp,
+            by_ntp
+        );
+    }
+}
+
+#[test]
+fn test_playlist_mount_spec() {
+    let spec: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/daynight", "playlist": {"items": [
+            {"path": "/videos/day.mp4", "repeat": 2},
+            {"path": "/videos/night.mkv"}
+        ]}}"#,
+    )
+    .unwrap();
+    let stream_config = spec.to_stream_config().unwrap();
+    let playlist = stream_config.playlist().unwrap();
+    assert_eq!(playlist.items().len(), 2);
+    assert_eq!(playlist.items()[0].repeat(), 2);
+    assert_eq!(playlist.play_order().len(), 3);
+    assert!(!playlist.is_shuffled());
+    assert_eq!(
+        stream_config.source_path(),
+        &PathBuf::from("/videos/day.mp4")
+    );
+
+    let both = MountSpec {
+        video_path: PathBuf::from("/videos/b.mp4"),
+        ..spec.clone()
+    };
+    assert!(matches!(
+        both.to_stream_config(),
+        Err(DomainError::InvalidPlaylist(_))
+    ));
+
+    let config = Config::parse_from([
+        "pipeline-rtsp",
+        "--playlist",
+        "/videos/day.mp4,/videos/night.mp4",
+        "--shuffle",
+    ]);
+    let default_mount = config.mount_specs().unwrap().remove(0);
+    let playlist = default_mount
+        .to_stream_config()
+        .unwrap()
+        .playlist()
+        .cloned()
+        .unwrap();
+    assert_eq!(playlist.items().len(), 2);
+    assert!(playlist.is_shuffled());
+
+    // Playlist clips are checked instead of the default video path
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_schedule_mount_spec() {
+    let spec: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/daynight", "video_path": "/videos/dusk.mp4", "schedule": {
+            "windows": ["06:00-18:00=/videos/day.mp4", "22:00-04:00=/videos/night.mp4"],
+            "utc_offset_minutes": -300
+        }}"#,
+    )
+    .unwrap();
+    let stream_config = spec.to_stream_config().unwrap();
+    let schedule = stream_config.schedule().unwrap();
+    assert_eq!(schedule.windows().len(), 2);
+    assert_eq!(schedule.utc_offset_minutes(), -300);
+    assert_eq!(
+        schedule.source_at("23:00".parse().unwrap()),
+        Some(Path::new("/videos/night.mp4"))
+    );
+
+    let overlapping: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/daynight", "video_path": "/videos/dusk.mp4",
+            "schedule": {"windows": ["06:00-18:00=/a.mp4", "12:00-20:00=/b.mp4"]}}"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        overlapping.to_stream_config(),
+        Err(DomainError::InvalidSchedule(_))
+    ));
+
+    let config = Config::parse_from([
+        "pipeline-rtsp",
+        "--schedule",
+        "06:00-18:00=/videos/day.mp4,18:00-06:00=/videos/night.mp4",
+        "--schedule-utc-offset",
+        "-60",
+    ]);
+    let default_mount = config.mount_specs().unwrap().remove(0);
+    let schedule = default_mount.schedule.clone().unwrap();
+    assert_eq!(schedule.windows.len(), 2);
+    assert_eq!(schedule.utc_offset_minutes, -60);
+
+    // Scheduled files must exist
+    assert!(config.validate().is_err());
+}
+
+#[tokio::test]
+async fn test_scheduler_switches_source() {
+    let (dusk, morning, evening) = (
+        tempfile::NamedTempFile::new().unwrap(),
+        tempfile::NamedTempFile::new().unwrap(),
+        tempfile::NamedTempFile::new().unwrap(),
+    );
+    let service = create_fake_service();
+    let routes = mount_routes(service.clone(), 8554);
+
+    // The two windows cover the whole day, so one of them is always active
+    let body = serde_json::json!({
+        "mount_point": "/daynight",
+        "video_path": dusk.path(),
+        "schedule": {"windows": [
+            format!("00:00-12:00={}", morning.path().display()),
+            format!("12:00-00:00={}", evening.path().display()),
+        ]},
+    });
+    let created = warp::test::request()
+        .method("POST")
+        .path("/mounts")
+        .json(&body)
+        .reply(&routes)
+        .await;
+    assert_eq!(created.status(), 201);
+
+    let session = service.session("/daynight").await.unwrap();
+    assert_eq!(session.active_source(), dusk.path());
+
+    service.apply_schedules().await;
+
+    let session = service.session("/daynight").await.unwrap();
+    let scheduled = session.stream_config().scheduled_source();
+    assert_ne!(scheduled, dusk.path());
+    assert_eq!(session.active_source(), scheduled);
+
+    let listed = warp::test::request()
+        .method("GET")
+        .path("/mounts")
+        .reply(&routes)
+        .await;
+    let mounts: serde_json::Value = serde_json::from_slice(listed.body()).unwrap();
+    let mount = &mounts[0];
+    assert_eq!(mount["active_source"], scheduled.display().to_string());
+    assert_eq!(mount["schedule"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_fault_spec_parsing() {
+    let drop: FaultSpec =
+        serde_json::from_str(r#"{"kind": "drop", "percent": 20, "duration_ms": 5000}"#).unwrap();
+    assert_eq!(
+        drop.to_fault().unwrap(),
+        Fault::DropPackets {
+            percent: 20,
+            duration: Duration::from_secs(5)
+        }
+    );
+
+    let teardown: FaultSpec = serde_json::from_str(r#"{"kind": "teardown"}"#).unwrap();
+    assert_eq!(teardown.to_fault().unwrap(), Fault::TeardownClients);
+
+    for invalid in [
+        r#"{"kind": "freeze"}"#,
+        r#"{"kind": "latency", "duration_ms": 1000}"#,
+        r#"{"kind": "drop", "percent": 0, "duration_ms": 1000}"#,
+        r#"{"kind": "reboot"}"#,
+    ] {
+        let spec: FaultSpec = serde_json::from_str(invalid).unwrap();
+        assert!(matches!(spec.to_fault(), Err(DomainError::InvalidFault(_))));
+    }
+}
+
+#[tokio::test]
+async fn test_chaos_api() {
+    let video = tempfile::NamedTempFile::new().unwrap();
+    let service = create_fake_service();
+    let routes = chaos_routes(service.clone()).or(mount_routes(service.clone(), 8554));
+    mount_clip(&routes, video.path()).await;
+
+    let injected = warp::test::request()
+        .method("POST")
+        .path("/mounts/clips/lobby/chaos")
+        .json(&serde_json::json!({"kind": "freeze", "duration_ms": 2000}))
+        .reply(&routes)
+        .await;
+    assert_eq!(injected.status(), 202);
+    let body: serde_json::Value = serde_json::from_slice(injected.body()).unwrap();
+    assert_eq!(body["faults_injected"], 1);
+
+    let invalid = warp::test::request()
+        .method("POST")
+        .path("/mounts/clips/lobby/chaos")
+        .json(&serde_json::json!({"kind": "drop", "percent": 120, "duration_ms": 2000}))
+        .reply(&routes)
+        .await;
+    assert_eq!(invalid.status(), 400);
+
+    assert_unknown_mount(&routes, "POST", "/chaos").await;
+
+    let started = warp::test::request()
+        .method("PUT")
+        .path("/mounts/clips/lobby/chaos/schedule")
+        .json(&serde_json::json!({
+            "faults": [{"kind": "eos"}, {"kind": "latency", "delay_ms": 500, "duration_ms": 3000}],
+            "min_interval_secs": 30,
+            "max_interval_secs": 60,
+        }))
+        .reply(&routes)
+        .await;
+    assert_eq!(started.status(), 200);
+
+    let shown = warp::test::request()
+        .method("GET")
+        .path("/mounts/clips/lobby/chaos/schedule")
+        .reply(&routes)
+        .await;
+    let body: serde_json::Value = serde_json::from_slice(shown.body()).unwrap();
+    assert_eq!(body["faults"].as_array().unwrap().len(), 2);
+    assert_eq!(body["max_interval_secs"], 60);
+
+    // The schedule route must win over removing a mount named /clips/lobby/chaos/schedule
+    let stopped = warp::test::request()
+        .method("DELETE")
+        .path("/mounts/clips/lobby/chaos/schedule")
+        .reply(&routes)
+        .await;
+    assert_eq!(stopped.status(), 200);
+    assert!(service.chaos_schedule("/clips/lobby").is_none());
+    assert!(service.session("/clips/lobby").await.is_some());
+
+    let listed = warp::test::request()
+        .method("GET")
+        .path("/mounts")
+        .reply(&routes)
+        .await;
+    let mounts: serde_json::Value = serde_json::from_slice(listed.body()).unwrap();
+    assert_eq!(mounts[0]["faults_injected"], 1);
+}
+
+#[test]
+fn test_recording_mount_spec() {
+    let spec: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/clips/lobby", "video_path": "/videos/a.mp4", "recording": {"directory": "/recordings/{mount}", "max_segments": 24}}"#,
+    )
+    .unwrap();
+    let stream_config = spec.to_stream_config().unwrap();
+    let recording = stream_config.recording().unwrap();
+    assert_eq!(recording.directory(), Path::new("/recordings/clips/lobby"));
+    assert_eq!(recording.segment_duration(), Duration::from_secs(60));
+    assert_eq!(recording.max_segments(), Some(24));
+
+    let config = Config::parse_from([
+        "pipeline-rtsp",
+        "--record-dir",
+        "/recordings/{mount}",
+        "--record-segment-secs",
+        "300",
+    ]);
+    let default_mount = config.mount_specs().unwrap().remove(0);
+    let recording = default_mount
+        .to_stream_config()
+        .unwrap()
+        .recording()
+        .cloned()
+        .unwrap();
+    assert_eq!(recording.directory(), Path::new("/recordings/cam1"));
+    assert_eq!(recording.segment_duration(), Duration::from_secs(300));
+    assert_eq!(recording.max_segments(), None);
+
+    // MP4 segments only hold the codecs mp4mux takes without transcoding
+    let vp8: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/cam", "video_path": "/videos/a.webm", "codec": "vp8", "container": "webm", "recording": {"directory": "/recordings"}}"#,
+    )
+    .unwrap();
+    assert!(matches!(
+        vp8.to_stream_config().unwrap().validate(),
+        Err(DomainError::InvalidRecording(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_recording_api() {
+    let video = tempfile::NamedTempFile::new().unwrap();
+    let service = create_fake_service();
+    let routes = recording_routes(service.clone()).or(mount_routes(service.clone(), 8554));
+
+    let created = mount_with(
+        &routes,
+        serde_json::json!({
+            "mount_point": "/clips/lobby",
+            "video_path": video.path(),
+            "recording": {"directory": "/recordings/{mount}", "segment_secs": 10, "max_segments": 6},
+        }),
+    )
+    .await;
+    assert_eq!(created["recording"], "idle");
+
+    let shown = warp::test::request()
+        .method("GET")
+        .path("/mounts/clips/lobby/recordings")
+        .reply(&routes)
+        .await;
+    assert_eq!(shown.status(), 200);
+    let body: serde_json::Value = serde_json::from_slice(shown.body()).unwrap();
+    assert_eq!(body["state"], "idle");
+    assert_eq!(body["directory"], "/recordings/clips/lobby");
+    assert_eq!(body["segment_duration_secs"], 10);
+    assert_eq!(body["max_segments"], 6);
+    assert_eq!(body["files"], serde_json::json!([]));
+
+    assert_unknown_mount(&routes, "GET", "/recordings").await;
+}
+
+#[tokio::test]
+async fn test_snapshot_api() {
+    let video = tempfile::NamedTempFile::new().unwrap();
+    let service = create_fake_service();
+    let routes = snapshot_routes(service.clone(), 640).or(mount_routes(service.clone(), 8554));
+    mount_clip(&routes, video.path()).await;
+
+    let snapshot = warp::test::request()
+        .method("GET")
+        .path("/mounts/clips/lobby/snapshot.jpg")
+        .reply(&routes)
+        .await;
+    assert_eq!(snapshot.status(), 200);
+    assert_eq!(snapshot.headers()["content-type"], "image/jpeg");
+    assert_eq!(snapshot.body().as_ref(), b"jpeg 640");
+
+    // Thumbnails may ask for less, never for more than the configured width
+    let thumbnail = warp::test::request()
+        .method("GET")
+        .path("/mounts/clips/lobby/snapshot.jpg?max_width=160")
+        .reply(&routes)
+        .await;
+    assert_eq!(thumbnail.body().as_ref(), b"jpeg 160");
+    let oversized = warp::test::request()
+        .method("GET")
+        .path("/mounts/clips/lobby/snapshot.jpg?max_width=4096")
+        .reply(&routes)
+        .await;
+    assert_eq!(oversized.body().as_ref(), b"jpeg 640");
+
+    assert_unknown_mount(&routes, "GET", "/snapshot.jpg").await;
+}
+
+#[tokio::test]
+async fn test_drain_fails_readiness_and_refuses_mounts() {
+    let video = tempfile::NamedTempFile::new().unwrap();
+    let service = create_fake_service();
+    let routes = readiness_routes(service.clone()).or(mount_routes(service.clone(), 8554));
+
+    let ready = warp::test::request()
+        .method("GET")
+        .path("/readyz")
+        .reply(&routes)
+        .await;
+    assert_eq!(ready.status(), 200);
+    mount_clip(&routes, video.path()).await;
+
+    // Without clients the drain returns at once instead of waiting out the timeout
+    tokio::time::timeout(
+        Duration::from_secs(5),
+        service.drain(Duration::from_secs(60)),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert!(service.is_draining());
+
+    let draining = warp::test::request()
+        .method("GET")
+        .path("/readyz")
+        .reply(&routes)
+        .await;
+    assert_eq!(draining.status(), 503);
+    let body: serde_json::Value = serde_json::from_slice(draining.body()).unwrap();
+    assert_eq!(body["status"], "draining");
+
+    let refused = warp::test::request()
+        .method("POST")
+        .path("/mounts")
+        .json(&serde_json::json!({"mount_point": "/cam2", "video_path": video.path()}))
+        .reply(&routes)
+        .await;
+    assert_eq!(refused.status(), 503);
+
+    // Existing mounts keep serving until the service stops
+    assert_eq!(service.sessions().await.len(), 1);
+}
+
+#[tokio::test]
+async fn test_watch_events_publish_and_withdraw_clips() {
+    let directory = tempfile::tempdir().unwrap();
+    let clip = directory.path().join("lobby.mp4");
+    std::fs::write(&clip, b"").unwrap();
+    let service = create_fake_service();
+
+    service
+        .apply_watch_event(&WatchEvent::Added(clip.clone()), 8554)
+        .await
+        .unwrap();
+    let session = service.session("/clips/lobby").await.unwrap();
+    assert_eq!(session.stream_config().source_path(), &clip);
+
+    // Names must still make a valid mount point, and files must pass validation
+    let spaced = directory.path().join("front door.mp4");
+    std::fs::write(&spaced, b"").unwrap();
+    assert!(matches!(
+        service
+            .apply_watch_event(&WatchEvent::Added(spaced), 8554)
+            .await,
+        Err(DomainError::InvalidMountPoint(_))
+    ));
+    let gone = directory.path().join("gone.mp4");
+    assert!(service
+        .apply_watch_event(&WatchEvent::Added(gone), 8554)
+        .await
+        .is_err());
+    assert_eq!(service.sessions().await.len(), 1);
+
+    // Only deleting the file the mount plays removes it
+    service
+        .apply_watch_event(
+            &WatchEvent::Removed(directory.path().join("lobby.mkv")),
+            8554,
+        )
+        .await
+        .unwrap();
+    assert!(service.session("/clips/lobby").await.is_some());
+
+    service
+        .apply_watch_event(&WatchEvent::Removed(clip), 8554)
+        .await
+        .unwrap();
+    assert!(service.session("/clips/lobby").await.is_none());
+}
+
+#[test]
+fn test_test_pattern_mount_spec() {
+    let spec: MountSpec = serde_json::from_str(
+        r#"{"mount_point": "/bars", "test_pattern": {"pattern": "ball", "width": 640, "height": 480, "overlay": "time"}}"#,
+    )
+    .unwrap();
+    let stream_config = spec.to_stream_config().unwrap();
+    assert_eq!(stream_config.source_kind(), SourceKind::TestPattern);
+    let pattern = stream_config.test_pattern().unwrap();
+    assert_eq!(pattern.resolution(), (640, 480));
+    assert_eq!(pattern.fps(), 30);
+    assert!(stream_config.validate().is_ok());
+
+    let with_file = MountSpec {
+        video_path: PathBuf::from("/videos/b.mp4"),
+        ..spec
+    };
+    assert!(matches!(
+        with_file.to_stream_config(),
+        Err(DomainError::InvalidTestPattern(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_test_pattern_needs_no_video_file() {
+    let config = Config::parse_from([
+        "pipeline-rtsp",
+        "--video-path",
+        "/nonexistent/video.mp4",
+        "--source",
+        "test-pattern",
+        "--pattern",
+        "snow",
+        "--pattern-overlay",
+        "clock",
+    ]);
+    assert!(config.validate().is_ok());
+
+    let spec = config.mount_specs().unwrap().remove(0);
+    let service = create_fake_service();
+    let session = service
+        .start_streaming(
+            spec.to_stream_config().unwrap(),
+            spec.to_server_config(8554).unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        session.stream_config().test_pattern().unwrap().pattern(),
+        "snow"
+    );
+
+    let listed = warp::test::request()
+        .method("GET")
+        .path("/mounts")
+        .reply(&mount_routes(service, 8554))
+        .await;
+    let mounts: serde_json::Value = serde_json::from_slice(listed.body()).unwrap();
+    assert_eq!(mounts[0]["source"], "test-pattern");
+    assert_eq!(mounts[0]["output_codec"], "H.264");
+    assert_eq!(mounts[0]["resolution"], serde_json::json!([1280, 720]));
+}
+
 #[test]
 fn test_config_validation() {
     let config = create_test_config();